@@ -24,6 +24,8 @@
 extern crate libc;
 extern crate test;
 extern crate getopts;
+extern crate serialize;
+extern crate serialize as rustc_serialize; // used by deriving
 
 #[macro_use]
 extern crate log;
@@ -42,6 +44,7 @@ pub mod header;
 pub mod runtest;
 pub mod common;
 pub mod errors;
+pub mod json;
 mod raise_fd_limit;
 
 pub fn main() {
@@ -256,12 +259,22 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
             None => None,
             Some(ref filter) => Some(filter.clone()),
         },
+        filter_exact: false,
+        skip: Vec::new(),
+        shard: None,
+        list: false,
         run_ignored: config.run_ignored,
         logfile: config.logfile.clone(),
         run_tests: true,
         bench_benchmarks: true,
         nocapture: env::var("RUST_TEST_NOCAPTURE").is_ok(),
         color: test::AutoColor,
+        save_metrics: None,
+        ratchet_metrics: None,
+        ratchet_noise_percent: None,
+        format: test::PrettyOutput,
+        test_timeout: None,
+        report_time: false,
     }
 }
 
@@ -314,6 +327,7 @@ pub fn make_test(config: &Config, testfile: &Path) -> test::TestDescAndFn
             name: make_test_name(config, testfile),
             ignore: header::is_test_ignored(config, testfile),
             should_panic: test::ShouldPanic::No,
+            timeout: None,
         },
         testfn: make_test_closure(config, &testfile),
     }
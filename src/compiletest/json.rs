@@ -0,0 +1,109 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reads the diagnostics produced by `rustc --error-format=json`.
+//!
+//! Each diagnostic is rendered back into the
+//! `filename:line1:col1: line2:col2 level: msg` form that the rest of
+//! compiletest matches expected errors against, so tests may be run with
+//! either error format.
+
+use serialize::json;
+
+// These structs are a subset of the ones found in `syntax::json`.
+
+#[derive(RustcDecodable)]
+struct Diagnostic {
+    message: String,
+    code: Option<DiagnosticCode>,
+    level: String,
+    spans: Vec<DiagnosticSpan>,
+    children: Vec<Diagnostic>,
+}
+
+#[derive(RustcDecodable)]
+struct DiagnosticSpan {
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+    suggested_replacement: Option<String>,
+    label: Option<String>,
+}
+
+#[derive(RustcDecodable)]
+struct DiagnosticCode {
+    code: String,
+}
+
+/// Converts every line of `output` which holds a JSON diagnostic to human
+/// readable form. All other lines are passed through unchanged.
+pub fn to_human_readable(output: &str) -> String {
+    let mut result = String::new();
+    for line in output.lines() {
+        match parse_line(line) {
+            Some(diagnostic) => push_diagnostic(&mut result, &diagnostic),
+            None => {
+                result.push_str(line);
+                result.push('\n');
+            }
+        }
+    }
+    result
+}
+
+fn parse_line(line: &str) -> Option<Diagnostic> {
+    if !line.starts_with('{') {
+        return None;
+    }
+    json::decode(line).ok()
+}
+
+fn push_diagnostic(result: &mut String, diagnostic: &Diagnostic) {
+    match diagnostic.spans.iter().find(|span| span.is_primary) {
+        Some(span) => {
+            result.push_str(&format!("{}:{}:{}: {}:{} ",
+                                     span.file_name,
+                                     span.line_start, span.column_start,
+                                     span.line_end, span.column_end));
+        }
+        None => {}
+    }
+    result.push_str(&format!("{}: {}", diagnostic.level, diagnostic.message));
+    if let Some(ref code) = diagnostic.code {
+        result.push_str(&format!(" [{}]", code.code));
+    }
+    result.push('\n');
+
+    for span in &diagnostic.spans {
+        if let Some(ref replacement) = span.suggested_replacement {
+            for line in replacement.lines() {
+                result.push_str(&format!("{}:{} {}\n", span.file_name, span.line_start, line));
+            }
+        }
+    }
+
+    // The labels of the other spans of a snippet are read as notes.
+    for span in diagnostic.spans.iter().filter(|span| !span.is_primary) {
+        if let Some(ref label) = span.label {
+            result.push_str(&format!("{}:{}:{}: {}:{} note: {}\n",
+                                     span.file_name,
+                                     span.line_start, span.column_start,
+                                     span.line_end, span.column_end,
+                                     label));
+        }
+    }
+
+    for child in &diagnostic.children {
+        push_diagnostic(result, child);
+    }
+}
@@ -12,10 +12,11 @@ use self::TargetLocation::*;
 
 use common::Config;
 use common::{CompileFail, ParseFail, Pretty, RunFail, RunPass, RunPassValgrind};
-use common::{Codegen, DebugInfoLldb, DebugInfoGdb, Rustdoc};
+use common::{Codegen, DebugInfoLldb, DebugInfoGdb, Rustdoc, MirOpt};
 use errors;
 use header::TestProps;
 use header;
+use json;
 use procsrv;
 use util::logv;
 
@@ -58,6 +59,7 @@ pub fn run(config: Config, testfile: &Path) {
         DebugInfoLldb => run_debuginfo_lldb_test(&config, &props, &testfile),
         Codegen => run_codegen_test(&config, &props, &testfile),
         Rustdoc => run_rustdoc_test(&config, &props, &testfile),
+        MirOpt => run_mir_opt_test(&config, &props, &testfile),
     }
 }
 
@@ -955,7 +957,11 @@ fn check_expected_errors(expected_errors: Vec<errors::ExpectedError>,
     //
     // This pattern is ambiguous on windows, because filename may contain
     // a colon, so any path prefix must be detected and removed first.
-    for line in proc_res.stderr.lines() {
+    //
+    // Diagnostics emitted with `--error-format=json` are first rendered
+    // into the same form.
+    let stderr = json::to_human_readable(&proc_res.stderr);
+    for line in stderr.lines() {
         let mut was_expected = false;
         let mut prev = 0;
         for (i, ee) in expected_errors.iter().enumerate() {
@@ -1739,3 +1745,142 @@ fn run_rustdoc_test(config: &Config, props: &TestProps, testfile: &Path) {
         fatal_proc_rec("htmldocck failed!", &res);
     }
 }
+
+// mir-opt tests (comparing against the MIR dumped by `-Z dump-mir`)
+
+fn run_mir_opt_test(config: &Config, props: &TestProps, testfile: &Path) {
+    let dump_dir = mir_dump_dir(config, testfile);
+    let _ = fs::remove_dir_all(&dump_dir);
+
+    let proc_res = compile_test_and_dump_mir(config, props, testfile, &dump_dir);
+    if !proc_res.status.success() {
+        fatal_proc_rec("compilation failed!", &proc_res);
+    }
+
+    check_mir_dump(testfile, &dump_dir);
+}
+
+fn mir_dump_dir(config: &Config, testfile: &Path) -> PathBuf {
+    let f = output_base_name(config, testfile);
+    let mut fname = f.file_name().unwrap().to_os_string();
+    fname.push(".mir_dump");
+    f.with_file_name(&fname)
+}
+
+fn compile_test_and_dump_mir(config: &Config, props: &TestProps,
+                             testfile: &Path, dump_dir: &Path) -> ProcRes {
+    let aux_dir = aux_output_dir_name(config, testfile);
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    let link_args = vec!("-L".to_owned(),
+                         aux_dir.to_str().unwrap().to_owned(),
+                         "-Z".to_owned(),
+                         "dump-mir=all".to_owned(),
+                         "-Z".to_owned(),
+                         format!("dump-mir-dir={}", dump_dir.to_str().unwrap()));
+    let args = make_compile_args(config,
+                                 props,
+                                 link_args,
+                                 |a, b| TargetLocation::ThisFile(make_exe_name(a, b)), testfile);
+    compose_and_run_compiler(config, props, testfile, args, None)
+}
+
+/// Checks the expectations written after `// END RUST SOURCE` in the
+/// test file. Each one has the form
+///
+/// ```text
+/// // START rustc.main.mir_map.0.mir
+/// //     BB(0): {
+/// //         ...
+/// //     }
+/// // END rustc.main.mir_map.0.mir
+/// ```
+///
+/// and requires the lines in between to appear, in order and without
+/// anything in between, in the named file of the MIR dump. Comments
+/// and blank lines are ignored on both sides, since they hold spans
+/// and scope information which vary from one build to the next.
+fn check_mir_dump(testfile: &Path, dump_dir: &Path) {
+    let mut test_file_contents = String::new();
+    File::open(testfile).unwrap().read_to_string(&mut test_file_contents).unwrap();
+    let test_after_source = match test_file_contents.split("// END RUST SOURCE").nth(1) {
+        Some(s) => s,
+        None => fatal("mir-opt test is missing a `// END RUST SOURCE` line"),
+    };
+
+    let mut lines = test_after_source.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if !line.starts_with("// START ") {
+            continue;
+        }
+        let file_name = line["// START ".len()..].trim();
+        let end_line = format!("// END {}", file_name);
+
+        let mut expected = vec![];
+        loop {
+            let line = match lines.next() {
+                Some(line) => line.trim(),
+                None => fatal(&format!("missing `{}`", end_line)),
+            };
+            if line == end_line {
+                break;
+            }
+            let line = if line.starts_with("//") { &line[2..] } else { line };
+            expected.push(line.to_owned());
+        }
+
+        compare_mir_dump(&dump_dir.join(file_name), &expected);
+    }
+}
+
+fn compare_mir_dump(dump_file: &Path, expected: &[String]) {
+    let mut dump_contents = String::new();
+    match File::open(dump_file).and_then(|mut f| f.read_to_string(&mut dump_contents)) {
+        Ok(_) => {}
+        Err(e) => fatal(&format!("could not read MIR dump `{}`: {}", dump_file.display(), e)),
+    }
+
+    let expected = normalize_mir_lines(expected.iter().map(|s| &s[..]));
+    let actual = normalize_mir_lines(dump_contents.lines());
+
+    let found = expected.is_empty() ||
+        (0..actual.len()).any(|start| actual[start..].starts_with(&expected));
+    if !found {
+        println!("expected:\n{}\n", expected.join("\n"));
+        println!("actual ({}):\n{}\n", dump_file.display(), actual.join("\n"));
+        fatal(&format!("MIR dump `{}` does not contain the expected lines",
+                       dump_file.display()));
+    }
+}
+
+/// Strips comments and surrounding whitespace, and drops empty lines.
+fn normalize_mir_lines<'a, I: Iterator<Item=&'a str>>(lines: I) -> Vec<String> {
+    lines.map(strip_mir_comment)
+         .map(|line| line.trim())
+         .filter(|line| !line.is_empty())
+         .map(|line| line.to_owned())
+         .collect()
+}
+
+/// Cuts `line` at the first `//` which is not inside a string literal,
+/// such as the value of a string constant.
+fn strip_mir_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == '/' && chars.peek().map(|&(_, next)| next) == Some('/') {
+            return &line[..index];
+        }
+    }
+    line
+}
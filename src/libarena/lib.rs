@@ -55,9 +55,34 @@ use std::marker;
 use std::mem;
 use std::ptr;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, ATOMIC_BOOL_INIT, ATOMIC_USIZE_INIT};
+use std::sync::atomic::Ordering::Relaxed;
 
 use alloc::heap::{allocate, deallocate};
 
+static COUNT_ALLOCATIONS: AtomicBool = ATOMIC_BOOL_INIT;
+static ALLOCATIONS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// Starts counting the objects allocated in any `Arena` or `TypedArena`
+/// of this process. Counting is off by default, since it costs an atomic
+/// increment per allocation.
+pub fn count_allocations() {
+    COUNT_ALLOCATIONS.store(true, Relaxed);
+}
+
+/// Returns the number of objects allocated in arenas since
+/// `count_allocations` was first called.
+pub fn allocation_count() -> usize {
+    ALLOCATIONS.load(Relaxed)
+}
+
+#[inline]
+fn record_allocation() {
+    if COUNT_ALLOCATIONS.load(Relaxed) {
+        ALLOCATIONS.fetch_add(1, Relaxed);
+    }
+}
+
 // The way arena uses arrays is really deeply awful. The arrays are
 // allocated, and have capacities reserved, but the fill for the array
 // will always stay at 0.
@@ -325,6 +350,7 @@ impl<'longer_than_self> Arena<'longer_than_self> {
     pub fn alloc<T: 'longer_than_self, F>(&self, op: F) -> &mut T
         where F: FnOnce() -> T
     {
+        record_allocation();
         unsafe {
             if intrinsics::needs_drop::<T>() {
                 self.alloc_noncopy(op)
@@ -487,6 +513,7 @@ impl<T> TypedArena<T> {
     /// Allocates an object in the `TypedArena`, returning a reference to it.
     #[inline]
     pub fn alloc(&self, object: T) -> &mut T {
+        record_allocation();
         if self.ptr == self.end {
             self.grow()
         }
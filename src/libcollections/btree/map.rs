@@ -20,7 +20,7 @@ use self::Entry::*;
 use core::cmp::Ordering;
 use core::fmt::Debug;
 use core::hash::{Hash, Hasher};
-use core::iter::{Map, FromIterator};
+use core::iter::{Map, FromIterator, Peekable};
 use core::ops::Index;
 use core::{fmt, mem, usize};
 use Bound::{self, Included, Excluded, Unbounded};
@@ -473,6 +473,158 @@ impl<K: Ord, V> BTreeMap<K, V> {
             }
         }
     }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty. If a key is present
+    /// in both maps, the value from `other` is kept.
+    ///
+    /// When every key in `other` is greater than every key in `self`, as when appending a
+    /// later batch of a time series, the entries of `other` are pushed straight onto the right
+    /// edge of `self`'s tree in O(m) time. Otherwise both maps are merged and the tree is
+    /// rebuilt bottom-up in O(n + m) time, without any per-element searching.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_append)]
+    ///
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    ///
+    /// let mut b = BTreeMap::new();
+    /// b.insert(3, "d");
+    /// b.insert(4, "e");
+    /// b.insert(5, "f");
+    ///
+    /// a.append(&mut b);
+    ///
+    /// assert_eq!(a.len(), 5);
+    /// assert_eq!(b.len(), 0);
+    ///
+    /// assert_eq!(a[&1], "a");
+    /// assert_eq!(a[&2], "b");
+    /// assert_eq!(a[&3], "d");
+    /// assert_eq!(a[&4], "e");
+    /// assert_eq!(a[&5], "f");
+    /// ```
+    #[unstable(feature = "btree_append", reason = "recently added", issue = "0")]
+    #[allow(deprecated)]
+    pub fn append(&mut self, other: &mut BTreeMap<K, V>) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            mem::swap(self, other);
+            return;
+        }
+
+        let b = other.b;
+        let other = mem::replace(other, BTreeMap::with_b(b));
+        let disjoint = match (self.last_key_value(), other.first_key_value()) {
+            (Some((last, _)), Some((first, _))) => last < first,
+            _ => false,
+        };
+        if disjoint {
+            self.bulk_push(other.into_iter());
+        } else {
+            let b = self.b;
+            let this = mem::replace(self, BTreeMap::with_b(b));
+            self.bulk_push(MergeIter {
+                left: this.into_iter().peekable(),
+                right: other.into_iter().peekable(),
+            });
+        }
+    }
+
+    /// Splits the map in two at the given key. Returns everything after the given key,
+    /// including the key itself.
+    ///
+    /// Only the nodes on the path to the key are cut in two, so this takes O(log n) time.
+    ///
+    /// The key may be any borrowed form of the map's key type, but the ordering
+    /// on the borrowed form *must* match the ordering on the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_split_off)]
+    ///
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(17, "d");
+    /// a.insert(41, "e");
+    ///
+    /// let b = a.split_off(&3);
+    ///
+    /// assert_eq!(a.len(), 2);
+    /// assert_eq!(b.len(), 3);
+    ///
+    /// assert_eq!(a[&1], "a");
+    /// assert_eq!(a[&2], "b");
+    ///
+    /// assert_eq!(b[&3], "c");
+    /// assert_eq!(b[&17], "d");
+    /// assert_eq!(b[&41], "e");
+    /// ```
+    #[unstable(feature = "btree_split_off", reason = "recently added", issue = "0")]
+    #[allow(deprecated)]
+    pub fn split_off<Q: ?Sized>(&mut self, key: &Q) -> BTreeMap<K, V> where
+        K: Borrow<Q>, Q: Ord
+    {
+        if self.is_empty() {
+            return BTreeMap::with_b(self.b);
+        }
+
+        let mut right = BTreeMap {
+            root: self.root.split_off(key),
+            length: 0,
+            depth: self.depth,
+            b: self.b,
+        };
+        self.root.fix_right_border();
+        self.fix_top();
+        right.root.fix_left_border();
+        right.fix_top();
+
+        right.length = right.root.subtree_len();
+        self.length -= right.length;
+        right
+    }
+}
+
+/// Merges two ascending iterators over entries into one, taking the entry from `right` when
+/// both yield the same key.
+struct MergeIter<I: Iterator> {
+    left: Peekable<I>,
+    right: Peekable<I>,
+}
+
+impl<K: Ord, V, I: Iterator<Item=(K, V)>> Iterator for MergeIter<I> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let order = match (self.left.peek(), self.right.peek()) {
+            (Some(&(ref left, _)), Some(&(ref right, _))) => left.cmp(right),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return None,
+        };
+        match order {
+            Ordering::Less => self.left.next(),
+            Ordering::Greater => self.right.next(),
+            Ordering::Equal => {
+                self.left.next();
+                self.right.next()
+            }
+        }
+    }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -704,6 +856,9 @@ mod stack {
         /// described in BTree's pop function.
         fn remove_leaf(mut self) -> (K, V) {
             self.map.length -= 1;
+            for handle in &mut self.stack {
+                unsafe { handle.from_raw_mut().count_removal(); }
+            }
 
             // Remove the key-value pair from the leaf that this search stack points to.
             // Then, note if the leaf is underfull, and promptly forget the leaf and its ptr
@@ -827,6 +982,9 @@ mod stack {
         pub fn insert(mut self, key: K, val: V) -> &'a mut V {
             unsafe {
                 self.map.length += 1;
+                for handle in &mut self.stack {
+                    handle.from_raw_mut().count_insertion();
+                }
 
                 // Insert the key and value into the leaf at the top of the stack
                 let (mut insertion, inserted_ptr) = self.top.from_raw_mut()
@@ -1359,6 +1517,104 @@ impl<K, V> BTreeMap<K, V> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Returns the first key-value pair in the map, which has the minimum key, or `None` if the
+    /// map is empty. This follows the first edge down to a leaf without iterating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_first_last)]
+    ///
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// assert_eq!(map.first_key_value(), None);
+    /// map.insert(2, "b");
+    /// map.insert(1, "a");
+    /// assert_eq!(map.first_key_value(), Some((&1, &"a")));
+    /// ```
+    #[unstable(feature = "btree_first_last", reason = "recently added", issue = "0")]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = &self.root;
+        while !node.is_leaf() {
+            node = &node.edges()[0];
+        }
+        match (node.keys().first(), node.vals().first()) {
+            (Some(key), Some(val)) => Some((key, val)),
+            _ => None,
+        }
+    }
+
+    /// Returns the last key-value pair in the map, which has the maximum key, or `None` if the
+    /// map is empty. This follows the last edge down to a leaf without iterating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_first_last)]
+    ///
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// assert_eq!(map.last_key_value(), None);
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// assert_eq!(map.last_key_value(), Some((&2, &"b")));
+    /// ```
+    #[unstable(feature = "btree_first_last", reason = "recently added", issue = "0")]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = &self.root;
+        while !node.is_leaf() {
+            node = &node.edges()[node.len()];
+        }
+        match (node.keys().last(), node.vals().last()) {
+            (Some(key), Some(val)) => Some((key, val)),
+            _ => None,
+        }
+    }
+
+    /// Returns the key-value pair at position `n` in the map's ascending order, counting from
+    /// zero, or `None` if the map has `n` or fewer elements.
+    ///
+    /// Every node knows how many elements are below it, so this follows a single path down the
+    /// tree rather than iterating, and takes O(log n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_nth)]
+    ///
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert(3, "c");
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// assert_eq!(map.nth_key_value(0), Some((&1, &"a")));
+    /// assert_eq!(map.nth_key_value(2), Some((&3, &"c")));
+    /// assert_eq!(map.nth_key_value(3), None);
+    /// ```
+    #[unstable(feature = "btree_nth", reason = "recently added", issue = "0")]
+    pub fn nth_key_value(&self, n: usize) -> Option<(&K, &V)> {
+        self.root.nth(n)
+    }
+
+    /// Appends `iter`, which must be ascending and above every key in the map, to the right
+    /// edge of the tree, then restores the invariants along that edge.
+    fn bulk_push<I: Iterator<Item=(K, V)>>(&mut self, iter: I) {
+        self.root.bulk_push(iter, &mut self.length, &mut self.depth);
+        self.root.fix_right_border();
+        self.fix_top();
+    }
+
+    /// Hoists away any internal roots that were left without keys by a split or bulk push.
+    fn fix_top(&mut self) {
+        while self.root.is_empty() && !self.root.is_leaf() {
+            self.root.hoist_lone_child();
+            self.depth -= 1;
+        }
+    }
 }
 
 macro_rules! range_impl {
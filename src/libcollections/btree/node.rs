@@ -68,6 +68,14 @@ pub struct Node<K, V> {
     // be more stable in the face of representation changes.
     _len: usize,
 
+    // How many key-value pairs the subtree rooted at this node contains: `_len` plus the
+    // `_size` of every edge. The vector functions keep this up to date as pairs and edges are
+    // pushed and popped; operations that only move pairs around inside the subtree must leave it
+    // alone.
+    //
+    // Note: instead of accessing this field directly, please call the `subtree_len()` method.
+    _size: usize,
+
     // FIXME(gereeter) It shouldn't be necessary to store the capacity in every node, as it should
     // be constant throughout the tree. Once a solution to this is found, it might be possible to
     // also pass down the offsets into the buffer that vals and edges are stored at, removing the
@@ -323,6 +331,7 @@ impl<K, V> Node<K, V> {
             vals: Unique::new(buffer.offset(vals_offset as isize) as *mut V),
             edges: Some(Unique::new(buffer.offset(edges_offset as isize) as *mut Node<K, V>)),
             _len: 0,
+            _size: 0,
             _capacity: capacity,
         }
     }
@@ -341,6 +350,7 @@ impl<K, V> Node<K, V> {
             vals: unsafe { Unique::new(buffer.offset(vals_offset as isize) as *mut V) },
             edges: None,
             _len: 0,
+            _size: 0,
             _capacity: capacity,
         }
     }
@@ -481,6 +491,7 @@ impl<K: Clone, V: Clone> Clone for Node<K, V> {
             mem::forget(edges);
 
             ret._len = self.len();
+            ret._size = self.subtree_len();
         }
 
         ret
@@ -598,6 +609,7 @@ impl <K, V> Node<K, V> {
             right: Node<K,V>) {
         let node = mem::replace(left_and_out, unsafe { Node::new_internal(capacity_from_b(b)) });
         left_and_out._len = 1;
+        left_and_out._size = node.subtree_len() + 1 + right.subtree_len();
         unsafe {
             ptr::write(left_and_out.keys_mut().get_unchecked_mut(0), key);
             ptr::write(left_and_out.vals_mut().get_unchecked_mut(0), value);
@@ -783,16 +795,33 @@ impl<K, V, NodeRef> Handle<NodeRef, handle::Edge, handle::Internal> where
         }
     }
 
+    /// Counts a key-value pair that is about to be inserted below the pointed-to edge in the
+    /// size of this node's subtree. The splits the insertion may cause leave the size alone, so
+    /// this must be called for every node on the path to the leaf, before inserting.
+    pub fn count_insertion(&mut self) {
+        self.node._size += 1;
+    }
+
+    /// Stops counting a key-value pair that is about to be removed from below the pointed-to edge
+    /// in the size of this node's subtree. Like `count_insertion`, this must be called for every
+    /// node on the path to the leaf, before removing.
+    pub fn count_removal(&mut self) {
+        self.node._size -= 1;
+    }
+
     /// Tries to insert this key-value pair at the given index in this internal node
     /// If the node is full, we have to split it.
     pub fn insert_as_internal(mut self, key: K, value: V, right: Node<K, V>)
             -> InsertionResult<K, V> {
         if !self.node.is_full() {
-            // The element can fit, just insert it
+            // The element can fit, just insert it. It and `right` were split off our child, so
+            // the subtree is no bigger for them.
+            let size = self.node.subtree_len();
             unsafe {
                 self.node.insert_kv(self.index, key, value);
                 self.node.insert_edge(self.index + 1, right); // +1 to insert to the right
             }
+            self.node._size = size;
             Fit
         } else {
             // The element can't fit, this node is full. Split it into two nodes.
@@ -1067,9 +1096,11 @@ impl<K, V, NodeRef> Handle<NodeRef, handle::KV, handle::Internal> where
     /// pair that separated them in their parent.
     unsafe fn merge_children(mut self) {
         // Permanently remove right's index, and the key-value pair that separates
-        // left and right
+        // left and right. They stay in our subtree, so its size doesn't change.
+        let size = self.node.subtree_len();
         let (key, val) = self.node.remove_kv(self.index);
         let right = self.node.remove_edge(self.index + 1);
+        self.node._size = size;
 
         // Give left right's stuff.
         self.left_edge().edge_mut()
@@ -1135,6 +1166,245 @@ impl<K, V> Node<K, V> {
             ptr::write(self, ret);
         }
     }
+
+    /// The inverse of `hoist_lone_child`: turn this node into the lone child of a new internal
+    /// node with no keys or values. The caller must push a key-value pair and an edge onto the
+    /// new node, or hoist the child back out, before the tree is used again.
+    pub fn push_internal_level(&mut self) {
+        let capacity = self.capacity();
+        unsafe {
+            let node = mem::replace(self, Node::new_internal(capacity));
+            self._size = node.subtree_len();
+            ptr::write(self.edges_mut().get_unchecked_mut(0), node);
+        }
+    }
+
+    /// How many key-value pairs the subtree rooted at this node contains
+    pub fn subtree_len(&self) -> usize {
+        self._size
+    }
+
+    /// Returns the key-value pair at position `index` in the ascending order of the subtree
+    /// rooted at this node, or `None` if the subtree contains `index` or fewer pairs.
+    ///
+    /// At every level, the sizes of the children let us skip straight to the one that holds the
+    /// pair, so this takes O(B log n) time.
+    pub fn nth(&self, mut index: usize) -> Option<(&K, &V)> {
+        if index >= self.subtree_len() {
+            return None;
+        }
+
+        let mut node = self;
+        'descend: loop {
+            let (keys, vals) = node.as_slices();
+            if node.is_leaf() {
+                return Some((&keys[index], &vals[index]));
+            }
+            for (i, edge) in node.edges().iter().enumerate() {
+                if index < edge.subtree_len() {
+                    node = edge;
+                    continue 'descend;
+                }
+                index -= edge.subtree_len();
+                if index == 0 {
+                    // We checked `index` against the size up front, so this is never past the
+                    // last edge.
+                    return Some((&keys[i], &vals[i]));
+                }
+                index -= 1;
+            }
+            unreachable!();
+        }
+    }
+}
+
+// Bulk operations
+impl<K: Ord, V> Node<K, V> {
+    /// Moves every key-value pair whose key is greater than or equal to `key` out of the subtree
+    /// rooted at this node and into a new subtree of the same height, which is returned.
+    ///
+    /// Only the nodes along the path the key would be inserted at are cut in two, so this does
+    /// O(B log n) work. The cut nodes may be left underfull, or even empty: call
+    /// `fix_right_border` on this node and `fix_left_border` on the result afterwards.
+    pub fn split_off<Q: ?Sized>(&mut self, key: &Q) -> Node<K, V> where K: Borrow<Q>, Q: Ord {
+        let (index, _) = self.as_slices_internal().search_linear(key);
+        unsafe {
+            let mut right = self.split_at(index);
+            if !self.is_leaf() {
+                // Everything under edge `index` lies between the pairs we kept and the pairs we
+                // moved, so split it the same way and give its upper half to `right`.
+                let child = self.edges_mut().get_unchecked_mut(index).split_off(key);
+                self._size -= child.subtree_len();
+                right.insert_edge(0, child);
+            }
+            right
+        }
+    }
+}
+
+impl<K, V> Node<K, V> {
+    /// Appends the key-value pairs yielded by `iter` to the tree rooted at this node. The pairs
+    /// must be in ascending order and greater than every key already in the tree.
+    ///
+    /// Pairs are pushed onto the last leaf until it fills up, at which point a fresh spine of
+    /// empty nodes is hung off the lowest ancestor that still has room, growing the tree by a
+    /// level if there is none. Every node that is left behind is full, so only the right border
+    /// needs fixing afterwards; this leaves it to the caller, via `fix_right_border`.
+    ///
+    /// `length` and `depth` are the tree's element count and height, and are kept up to date.
+    pub fn bulk_push<I>(&mut self, iter: I, length: &mut usize, depth: &mut usize) where
+        I: Iterator<Item=(K, V)>,
+    {
+        let mut leaf = self.last_leaf_mut() as *mut Node<K, V>;
+        for (key, val) in iter {
+            unsafe {
+                if !(*leaf).is_full() {
+                    (*leaf).push_kv(key, val);
+                } else {
+                    // Find the lowest internal node on the right spine with room for another
+                    // pair, counting levels from the root.
+                    let mut open = None;
+                    let mut level = 0;
+                    {
+                        let mut node: &Node<K, V> = &*self;
+                        while !node.is_leaf() {
+                            if !node.is_full() {
+                                open = Some(level);
+                            }
+                            node = node.edges().get_unchecked(node.len());
+                            level += 1;
+                        }
+                    }
+                    let open = match open {
+                        Some(open) => open,
+                        None => {
+                            self.push_internal_level();
+                            *depth += 1;
+                            level += 1;
+                            0
+                        }
+                    };
+
+                    let mut node: &mut Node<K, V> = &mut *self;
+                    for _ in 0..open {
+                        let parent = node;
+                        let len = parent.len();
+                        node = parent.edges_mut().get_unchecked_mut(len);
+                    }
+
+                    let mut right = Node::new_leaf(node.capacity());
+                    for _ in 0..level - open - 1 {
+                        right.push_internal_level();
+                    }
+                    // The spine below `node` is about to be left behind, so settle its sizes.
+                    node.recount_right_border();
+                    node.push_kv(key, val);
+                    node.push_edge(right);
+                    leaf = node.last_leaf_mut();
+                }
+            }
+            *length += 1;
+        }
+        // Pushing onto a leaf doesn't update the sizes of its ancestors; do it in one go.
+        self.recount_right_border();
+    }
+
+    /// Recomputes the subtree sizes of the nodes on the right border of the subtree rooted at
+    /// this node, from the bottom up.
+    fn recount_right_border(&mut self) {
+        if !self.is_leaf() {
+            let len = self.len();
+            unsafe { self.edges_mut().get_unchecked_mut(len).recount_right_border(); }
+            self.recount();
+        }
+    }
+
+    /// Restores the minimum load of every node on the right border of the subtree rooted at
+    /// this node, by merging each with its left sibling where they fit together and stealing
+    /// from it otherwise. This node itself may be left without any keys, in which case its lone
+    /// child should be hoisted.
+    pub fn fix_right_border(&mut self) {
+        let mut node = self;
+        while !node.is_leaf() {
+            let parent = node;
+            let len = parent.len();
+            if len == 0 {
+                // Left behind as the root of a split or push; it will be hoisted away.
+                node = unsafe { parent.edges_mut().get_unchecked_mut(0) };
+                continue;
+            }
+
+            let min_load = min_load_from_capacity(parent.capacity());
+            let (left_len, right_len) = {
+                let edges = parent.edges();
+                (edges[len - 1].len(), edges[len].len())
+            };
+            let merge = left_len + right_len < parent.capacity();
+            unsafe {
+                let mut kv: Handle<_, handle::KV, handle::Internal> = Handle {
+                    node: &mut *parent,
+                    index: len - 1,
+                    marker: PhantomData,
+                };
+                if merge {
+                    kv.merge_children();
+                } else {
+                    // The left sibling is loaded enough that it stays at or above the minimum.
+                    for _ in right_len..min_load + 1 {
+                        kv.steal_rightward();
+                    }
+                }
+            }
+            let index = if merge { len - 1 } else { len };
+            node = unsafe { parent.edges_mut().get_unchecked_mut(index) };
+        }
+    }
+
+    /// The mirror image of `fix_right_border`, for the left border of the subtree rooted at this
+    /// node.
+    pub fn fix_left_border(&mut self) {
+        let mut node = self;
+        while !node.is_leaf() {
+            let parent = node;
+            if parent.len() == 0 {
+                node = unsafe { parent.edges_mut().get_unchecked_mut(0) };
+                continue;
+            }
+
+            let min_load = min_load_from_capacity(parent.capacity());
+            let (left_len, right_len) = {
+                let edges = parent.edges();
+                (edges[0].len(), edges[1].len())
+            };
+            let merge = left_len + right_len < parent.capacity();
+            unsafe {
+                let mut kv: Handle<_, handle::KV, handle::Internal> = Handle {
+                    node: &mut *parent,
+                    index: 0,
+                    marker: PhantomData,
+                };
+                if merge {
+                    kv.merge_children();
+                } else {
+                    for _ in left_len..min_load + 1 {
+                        kv.steal_leftward();
+                    }
+                }
+            }
+            node = unsafe { parent.edges_mut().get_unchecked_mut(0) };
+        }
+    }
+
+    /// Follows the last edge of every node down to the rightmost leaf.
+    fn last_leaf_mut(&mut self) -> &mut Node<K, V> {
+        let mut node = self;
+        while !node.is_leaf() {
+            let parent = node;
+            let len = parent.len();
+            node = unsafe { parent.edges_mut().get_unchecked_mut(len) };
+        }
+        node
+    }
 }
 
 // Vector functions (all unchecked)
@@ -1148,6 +1418,7 @@ impl<K, V> Node<K, V> {
         ptr::write(self.vals_mut().get_unchecked_mut(len), val);
 
         self._len += 1;
+        self._size += 1;
     }
 
     // This can only be called immediately after a call to push_kv.
@@ -1155,6 +1426,7 @@ impl<K, V> Node<K, V> {
     unsafe fn push_edge(&mut self, edge: Node<K, V>) {
         let len = self.len();
 
+        self._size += edge.subtree_len();
         ptr::write(self.edges_mut().get_unchecked_mut(len), edge);
     }
 
@@ -1176,6 +1448,7 @@ impl<K, V> Node<K, V> {
         ptr::write(self.vals_mut().get_unchecked_mut(index), val);
 
         self._len += 1;
+        self._size += 1;
 
         self.vals_mut().get_unchecked_mut(index)
     }
@@ -1188,6 +1461,7 @@ impl<K, V> Node<K, V> {
             self.edges_mut().as_mut_ptr().offset(index as isize + 1),
             self.len() - index
         );
+        self._size += edge.subtree_len();
         ptr::write(self.edges_mut().get_unchecked_mut(index), edge);
     }
 
@@ -1198,6 +1472,7 @@ impl<K, V> Node<K, V> {
         let val = ptr::read(self.vals().get_unchecked(self.len() - 1));
 
         self._len -= 1;
+        self._size -= 1;
 
         (key, val)
     }
@@ -1207,6 +1482,7 @@ impl<K, V> Node<K, V> {
     unsafe fn pop_edge(&mut self) -> Node<K, V> {
         let edge = ptr::read(self.edges().get_unchecked(self.len() + 1));
 
+        self._size -= edge.subtree_len();
         edge
     }
 
@@ -1228,6 +1504,7 @@ impl<K, V> Node<K, V> {
         );
 
         self._len -= 1;
+        self._size -= 1;
 
         (key, val)
     }
@@ -1244,6 +1521,7 @@ impl<K, V> Node<K, V> {
             (self.len() + 1) - index
         );
 
+        self._size -= edge.subtree_len();
         edge
     }
 }
@@ -1287,11 +1565,50 @@ impl<K, V> Node<K, V> {
             let val = ptr::read(self.vals().get_unchecked(right_offset - 1));
 
             self._len = right_offset - 1;
+            self.recount();
+            right.recount();
 
             (key, val, right)
         }
     }
 
+    /// Move the key-value pairs from `index` onwards, along with the edges to their right, into a
+    /// new node. An internal result is missing its first edge, which must be inserted with
+    /// `insert_edge`.
+    unsafe fn split_at(&mut self, index: usize) -> Node<K, V> {
+        let size = self.subtree_len();
+        let mut right = if self.is_leaf() {
+            Node::new_leaf(self.capacity())
+        } else {
+            Node::new_internal(self.capacity())
+        };
+
+        right._len = self.len() - index;
+        ptr::copy_nonoverlapping(
+            self.keys().as_ptr().offset(index as isize),
+            right.keys_mut().as_mut_ptr(),
+            right.len()
+        );
+        ptr::copy_nonoverlapping(
+            self.vals().as_ptr().offset(index as isize),
+            right.vals_mut().as_mut_ptr(),
+            right.len()
+        );
+        if !self.is_leaf() {
+            ptr::copy_nonoverlapping(
+                self.edges().as_ptr().offset(index as isize + 1),
+                right.edges_mut().as_mut_ptr(),
+                right.len()
+            );
+        }
+
+        self._len = index;
+        self.recount();
+        right._size = size - self.subtree_len();
+
+        right
+    }
+
     /// Take all the values from right, separated by the given key and value
     fn absorb(&mut self, key: K, val: V, mut right: Node<K, V>) {
         // Necessary for correctness, but in a private function
@@ -1302,6 +1619,7 @@ impl<K, V> Node<K, V> {
         unsafe {
             let old_len = self.len();
             self._len += right.len() + 1;
+            self._size += right.subtree_len() + 1;
 
             ptr::write(self.keys_mut().get_unchecked_mut(old_len), key);
             ptr::write(self.vals_mut().get_unchecked_mut(old_len), val);
@@ -1328,6 +1646,11 @@ impl<K, V> Node<K, V> {
             mem::forget(right);
         }
     }
+
+    /// Recomputes the size of the subtree rooted at this node from the sizes of its edges.
+    fn recount(&mut self) {
+        self._size = self.edges().iter().fold(self.len(), |size, edge| size + edge.subtree_len());
+    }
 }
 
 /// Get the capacity of a node from the order of the parent B-Tree
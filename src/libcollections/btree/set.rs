@@ -132,6 +132,68 @@ impl<T> BTreeSet<T> {
     pub fn iter(&self) -> Iter<T> {
         Iter { iter: self.map.keys() }
     }
+
+    /// Returns the first value in the set, which is the minimum, or `None` if the set is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_first_last)]
+    ///
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut set = BTreeSet::new();
+    /// assert_eq!(set.first(), None);
+    /// set.insert(2);
+    /// set.insert(1);
+    /// assert_eq!(set.first(), Some(&1));
+    /// ```
+    #[unstable(feature = "btree_first_last", reason = "recently added", issue = "0")]
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|(key, _)| key)
+    }
+
+    /// Returns the last value in the set, which is the maximum, or `None` if the set is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_first_last)]
+    ///
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut set = BTreeSet::new();
+    /// assert_eq!(set.last(), None);
+    /// set.insert(1);
+    /// set.insert(2);
+    /// assert_eq!(set.last(), Some(&2));
+    /// ```
+    #[unstable(feature = "btree_first_last", reason = "recently added", issue = "0")]
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|(key, _)| key)
+    }
+
+    /// Returns the value at position `n` in the set's ascending order, counting from zero, or
+    /// `None` if the set has `n` or fewer elements. This takes O(log n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_nth)]
+    ///
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut set = BTreeSet::new();
+    /// set.insert(30);
+    /// set.insert(10);
+    /// set.insert(20);
+    /// assert_eq!(set.nth(1), Some(&20));
+    /// assert_eq!(set.nth(3), None);
+    /// ```
+    #[unstable(feature = "btree_nth", reason = "recently added", issue = "0")]
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.map.nth_key_value(n).map(|(key, _)| key)
+    }
 }
 
 impl<T: Ord> BTreeSet<T> {
@@ -494,6 +556,64 @@ impl<T: Ord> BTreeSet<T> {
     pub fn take<Q: ?Sized>(&mut self, value: &Q) -> Option<T> where T: Borrow<Q>, Q: Ord {
         Recover::take(&mut self.map, value)
     }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_append)]
+    ///
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut a = BTreeSet::new();
+    /// a.insert(1);
+    /// a.insert(2);
+    /// a.insert(3);
+    ///
+    /// let mut b = BTreeSet::new();
+    /// b.insert(3);
+    /// b.insert(4);
+    /// b.insert(5);
+    ///
+    /// a.append(&mut b);
+    ///
+    /// assert_eq!(a.len(), 5);
+    /// assert_eq!(b.len(), 0);
+    ///
+    /// assert!(a.contains(&1));
+    /// assert!(a.contains(&5));
+    /// ```
+    #[unstable(feature = "btree_append", reason = "recently added", issue = "0")]
+    pub fn append(&mut self, other: &mut BTreeSet<T>) {
+        self.map.append(&mut other.map);
+    }
+
+    /// Splits the set in two at the given value. Returns everything after the given value,
+    /// including the value itself.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_split_off)]
+    ///
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut a: BTreeSet<_> = [1, 2, 3, 17, 41].iter().cloned().collect();
+    ///
+    /// let b = a.split_off(&3);
+    ///
+    /// assert_eq!(a.iter().cloned().collect::<Vec<_>>(), [1, 2]);
+    /// assert_eq!(b.iter().cloned().collect::<Vec<_>>(), [3, 17, 41]);
+    /// ```
+    #[unstable(feature = "btree_split_off", reason = "recently added", issue = "0")]
+    pub fn split_off<Q: ?Sized>(&mut self, value: &Q) -> BTreeSet<T> where T: Borrow<Q>, Q: Ord {
+        BTreeSet { map: self.map.split_off(value) }
+    }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
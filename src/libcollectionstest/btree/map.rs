@@ -347,6 +347,154 @@ fn test_bad_zst() {
     }
 }
 
+#[test]
+fn test_first_last() {
+    let mut map = BTreeMap::new();
+    assert_eq!(map.first_key_value(), None);
+    assert_eq!(map.last_key_value(), None);
+
+    for i in 0..1000 {
+        map.insert(i, i * 2);
+    }
+    assert_eq!(map.first_key_value(), Some((&0, &0)));
+    assert_eq!(map.last_key_value(), Some((&999, &1998)));
+
+    for i in 0..500 {
+        map.remove(&i);
+        map.remove(&(999 - i));
+    }
+    assert_eq!(map.first_key_value(), None);
+    assert_eq!(map.last_key_value(), None);
+}
+
+// Checks the map holds exactly `expected`, then removes everything, which walks every
+// underflow path and so catches nodes left underfull by a bulk operation.
+fn check_and_drain(mut map: BTreeMap<u32, u32>, expected: Vec<(u32, u32)>) {
+    assert_eq!(map.len(), expected.len());
+    assert_eq!(map.iter().map(|(&k, &v)| (k, v)).collect::<Vec<_>>(), expected);
+    assert_eq!(map.first_key_value().map(|(&k, &v)| (k, v)), expected.first().cloned());
+    assert_eq!(map.last_key_value().map(|(&k, &v)| (k, v)), expected.last().cloned());
+    check_nth(&map);
+
+    for &(k, v) in &expected {
+        assert_eq!(map.remove(&k), Some(v));
+    }
+    assert!(map.is_empty());
+    map.insert(1, 1);
+    assert_eq!(map.len(), 1);
+}
+
+// Checks that `nth_key_value` agrees with iteration, which catches subtree sizes gone stale.
+fn check_nth(map: &BTreeMap<u32, u32>) {
+    for (i, kv) in map.iter().enumerate() {
+        assert_eq!(map.nth_key_value(i), Some(kv));
+    }
+    assert_eq!(map.nth_key_value(map.len()), None);
+}
+
+#[test]
+fn test_nth() {
+    let mut map = BTreeMap::new();
+    assert_eq!(map.nth_key_value(0), None);
+
+    // Insert out of order, so that splits happen all over the tree.
+    for i in 0..1000 {
+        map.insert((i * 37) % 1000, i);
+    }
+    check_nth(&map);
+    for i in 0..1000 {
+        assert_eq!(map.nth_key_value(i as usize).map(|(&k, _)| k), Some(i));
+    }
+
+    // Replacing a value doesn't change any sizes.
+    map.insert(500, 0);
+    assert_eq!(map.nth_key_value(500), Some((&500, &0)));
+
+    // Neither removal order hits the same merges and steals.
+    for i in 0..300u32 {
+        map.remove(&(i * 3));
+    }
+    check_nth(&map);
+    for i in 0..200u32 {
+        map.remove(&(999 - i));
+    }
+    check_nth(&map);
+
+    match map.entry(3) {
+        Vacant(e) => { e.insert(3); }
+        Occupied(_) => panic!(),
+    }
+    match map.entry(4) {
+        Occupied(e) => { e.remove(); }
+        Vacant(_) => panic!(),
+    }
+    check_nth(&map);
+    check_nth(&map.clone());
+}
+
+#[test]
+fn test_append_disjoint() {
+    for &(left, right) in &[(0, 0), (0, 10), (10, 0), (3, 1000), (1000, 3), (1000, 5000)] {
+        let mut a: BTreeMap<_, _> = (0..left).map(|i| (i, i)).collect();
+        let mut b: BTreeMap<_, _> = (left..left + right).map(|i| (i, i)).collect();
+
+        a.append(&mut b);
+
+        assert!(b.is_empty());
+        check_and_drain(a, (0..left + right).map(|i| (i, i)).collect());
+    }
+}
+
+#[test]
+fn test_append_overlapping() {
+    let mut a: BTreeMap<_, _> = (0..1000).map(|i| (i * 2, 0)).collect();
+    let mut b: BTreeMap<_, _> = (500..1500).map(|i| (i, 1)).collect();
+
+    a.append(&mut b);
+
+    assert!(b.is_empty());
+    let mut expected: Vec<_> = (0..250).map(|i| (i * 2, 0)).collect();
+    expected.extend((500..1500).map(|i| (i, 1)));
+    expected.extend((750..1000).map(|i| (i * 2, 0)));
+    check_and_drain(a, expected);
+}
+
+#[test]
+fn test_split_off() {
+    let size = 2000;
+    for &key in &[0, 1, 17, 500, 999, 1000, 1001, 1998, 1999, 2000, 3000] {
+        let mut a: BTreeMap<_, _> = (0..size).map(|i| (i, i)).collect();
+
+        let b = a.split_off(&key);
+
+        let split = if key < size { key } else { size };
+        check_and_drain(a, (0..split).map(|i| (i, i)).collect());
+        check_and_drain(b, (split..size).map(|i| (i, i)).collect());
+    }
+}
+
+#[test]
+fn test_split_off_absent_key() {
+    let mut a: BTreeMap<_, _> = (0..1000).map(|i| (i * 2, i)).collect();
+
+    let b = a.split_off(&501);
+
+    check_and_drain(a, (0..251).map(|i| (i * 2, i)).collect());
+    check_and_drain(b, (251..1000).map(|i| (i * 2, i)).collect());
+}
+
+#[test]
+fn test_split_off_then_append() {
+    let mut a: BTreeMap<_, _> = (0..3000).map(|i| (i, i)).collect();
+
+    let mut b = a.split_off(&1234);
+    let mut c = b.split_off(&2345);
+    b.append(&mut c);
+    a.append(&mut b);
+
+    check_and_drain(a, (0..3000).map(|i| (i, i)).collect());
+}
+
 mod bench {
     use std::collections::BTreeMap;
     use std::__rand::{Rng, thread_rng};
@@ -254,3 +254,64 @@ fn test_recovery() {
 
     assert_eq!(s.iter().next(), None);
 }
+
+#[test]
+fn test_first_last() {
+    let mut s = BTreeSet::new();
+    assert_eq!(s.first(), None);
+    assert_eq!(s.last(), None);
+
+    for i in 0..100 {
+        s.insert(i);
+    }
+    assert_eq!(s.first(), Some(&0));
+    assert_eq!(s.last(), Some(&99));
+}
+
+#[test]
+fn test_nth() {
+    let mut s: BTreeSet<usize> = (0..100).map(|i| i * 2).collect();
+    assert_eq!(s.nth(0), Some(&0));
+    assert_eq!(s.nth(50), Some(&100));
+    assert_eq!(s.nth(99), Some(&198));
+    assert_eq!(s.nth(100), None);
+
+    for i in 0..50 {
+        s.remove(&(i * 4));
+    }
+    for i in 0..50 {
+        assert_eq!(s.nth(i), Some(&(i * 4 + 2)));
+    }
+    assert_eq!(s.nth(50), None);
+}
+
+#[test]
+fn test_append() {
+    let mut a = BTreeSet::new();
+    a.insert(1);
+    a.insert(2);
+    a.insert(3);
+
+    let mut b = BTreeSet::new();
+    b.insert(3);
+    b.insert(4);
+    b.insert(5);
+
+    a.append(&mut b);
+
+    assert_eq!(a.len(), 5);
+    assert_eq!(b.len(), 0);
+    assert_eq!(a.iter().cloned().collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_split_off() {
+    let mut a: BTreeSet<_> = (0..100).collect();
+
+    let b = a.split_off(&40);
+
+    assert_eq!(a.len(), 40);
+    assert_eq!(b.len(), 60);
+    assert_eq!(a.last(), Some(&39));
+    assert_eq!(b.first(), Some(&40));
+}
@@ -11,7 +11,11 @@
 #![feature(ascii)]
 #![feature(binary_heap_extras)]
 #![feature(box_syntax)]
+#![feature(btree_append)]
+#![feature(btree_first_last)]
+#![feature(btree_nth)]
 #![feature(btree_range)]
+#![feature(btree_split_off)]
 #![feature(collections)]
 #![feature(collections_bound)]
 #![feature(const_fn)]
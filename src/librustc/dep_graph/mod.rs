@@ -0,0 +1,259 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The dependency graph records which results the compiler computed
+//! from which inputs, so that a later session can tell what has to be
+//! recomputed after an edit.
+//!
+//! Work is done inside a *task*, identified by the `DepNode` for the
+//! result it produces (e.g. `TypeckItemBody(def_id)`). While a task is
+//! active, every `read` of another node adds an edge from that node to
+//! the task, and every `write` adds an edge from the task to the node
+//! written. The graph is only built when `-Z incremental` is given;
+//! otherwise all of these operations are no-ops.
+//!
+//! Nodes of the previous session none of whose inputs changed are
+//! *clean*. Instead of running the task of a clean node, its result can
+//! be reused with `reuse`, which records the edges the task had then.
+
+use middle::def_id::DefId;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+mod edges;
+mod query;
+
+pub use self::query::DepGraphQuery;
+
+use self::edges::DepGraphEdges;
+
+/// A node in the dependency graph. The type parameter is the way
+/// definitions are identified: `DefId` within a session, and an index
+/// into a table of def-paths when the graph is saved to disk.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+pub enum DepNode<D = DefId> {
+    // Represents the `Krate` as a whole (the `hir::Krate` value) (as
+    // distinct from the krate module). This is basically a hash of
+    // the entire krate, so if you read from `Krate` (e.g., by calling
+    // `tcx.map.krate()`), we will have to assume that any change
+    // means that you need to be recompiled.
+    Krate,
+
+    // Represents the HIR node with the given def-id. Its fingerprint
+    // is saved between sessions and is the only thing compared when
+    // deciding what is dirty.
+    Hir(D),
+
+    // Represents the type, generics and predicates of an item, as
+    // computed by `collect`.
+    ItemSignature(D),
+
+    // Represents the predicates of an item.
+    Predicates(D),
+
+    // Represents the type-checking of an item, including the bodies
+    // of all methods within it.
+    TypeckItemBody(D),
+
+    // Represents the MIR built for a fn body.
+    Mir(D),
+
+    // Represents the translation of an item to LLVM IR.
+    TransCrateItem(D),
+}
+
+impl<D> DepNode<D> {
+    /// The definition this node is about, if any.
+    pub fn def(&self) -> Option<&D> {
+        match *self {
+            DepNode::Krate => None,
+            DepNode::Hir(ref d) |
+            DepNode::ItemSignature(ref d) |
+            DepNode::Predicates(ref d) |
+            DepNode::TypeckItemBody(ref d) |
+            DepNode::Mir(ref d) |
+            DepNode::TransCrateItem(ref d) => Some(d),
+        }
+    }
+
+    /// Rebuilds a node of the same kind with a different way of
+    /// identifying its definition. Returns `None` if `f` does.
+    pub fn map_def<E, F>(&self, f: F) -> Option<DepNode<E>>
+        where F: FnOnce(&D) -> Option<E>
+    {
+        match *self {
+            DepNode::Krate => Some(DepNode::Krate),
+            DepNode::Hir(ref d) => f(d).map(DepNode::Hir),
+            DepNode::ItemSignature(ref d) => f(d).map(DepNode::ItemSignature),
+            DepNode::Predicates(ref d) => f(d).map(DepNode::Predicates),
+            DepNode::TypeckItemBody(ref d) => f(d).map(DepNode::TypeckItemBody),
+            DepNode::Mir(ref d) => f(d).map(DepNode::Mir),
+            DepNode::TransCrateItem(ref d) => f(d).map(DepNode::TransCrateItem),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DepGraph {
+    data: Rc<DepGraphData>
+}
+
+struct DepGraphData {
+    /// True if we are actually building a dep-graph. If false, then
+    /// reads and writes are ignored.
+    enabled: bool,
+
+    /// The edges recorded so far.
+    edges: RefCell<DepGraphEdges>,
+
+    /// Nodes from the previous session none of whose inputs have
+    /// changed since, with the nodes they read. Everything else must be
+    /// assumed dirty.
+    clean: RefCell<HashMap<DepNode, Vec<DepNode>>>,
+}
+
+impl DepGraph {
+    pub fn new(enabled: bool) -> DepGraph {
+        DepGraph {
+            data: Rc::new(DepGraphData {
+                enabled: enabled,
+                edges: RefCell::new(DepGraphEdges::new()),
+                clean: RefCell::new(HashMap::new()),
+            })
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.data.enabled
+    }
+
+    /// Takes a snapshot of the current graph, which can be queried
+    /// for the transitive dependencies of a node.
+    pub fn query(&self) -> DepGraphQuery {
+        let edges = self.data.edges.borrow();
+        DepGraphQuery::new(edges.nodes(), &edges.edges())
+    }
+
+    /// Starts a new task producing `key`. The task ends when the
+    /// returned guard is dropped. Returns `None` if the graph is not
+    /// enabled.
+    pub fn in_task<'graph>(&'graph self, key: DepNode) -> Option<DepTask<'graph>> {
+        if self.data.enabled {
+            Some(DepTask::new(&self.data, key))
+        } else {
+            None
+        }
+    }
+
+    pub fn with_task<OP, R>(&self, key: DepNode, op: OP) -> R
+        where OP: FnOnce() -> R
+    {
+        let _task = self.in_task(key);
+        op()
+    }
+
+    /// Runs `op` outside of any task, so that nothing it reads or
+    /// writes is recorded.
+    pub fn with_ignore<OP, R>(&self, op: OP) -> R
+        where OP: FnOnce() -> R
+    {
+        let _ignore = self.in_ignore();
+        op()
+    }
+
+    pub fn in_ignore<'graph>(&'graph self) -> Option<IgnoreTask<'graph>> {
+        if self.data.enabled {
+            Some(IgnoreTask::new(&self.data))
+        } else {
+            None
+        }
+    }
+
+    /// Indicates that the current task read the data at `v`.
+    pub fn read(&self, v: DepNode) {
+        if self.data.enabled {
+            self.data.edges.borrow_mut().read(v);
+        }
+    }
+
+    /// Indicates that the current task wrote the data at `v`.
+    pub fn write(&self, v: DepNode) {
+        if self.data.enabled {
+            self.data.edges.borrow_mut().write(v);
+        }
+    }
+
+    /// Records nodes of the previous session which were found to be
+    /// unaffected by the changes since, each with the nodes it read.
+    pub fn mark_clean<I>(&self, nodes: I)
+        where I: IntoIterator<Item=(DepNode, Vec<DepNode>)>
+    {
+        self.data.clean.borrow_mut().extend(nodes);
+    }
+
+    /// True if `node` was computed in the previous session and none
+    /// of its inputs have changed since. Nodes which were not present
+    /// in the previous session are never clean.
+    pub fn is_clean(&self, node: &DepNode) -> bool {
+        self.data.clean.borrow().contains_key(node)
+    }
+
+    /// Reuses the result the previous session computed for the clean
+    /// `node` instead of running its task: the reads the task made then
+    /// are recorded again, so that the graph saved by this session still
+    /// knows what `node` depends on.
+    pub fn reuse(&self, node: DepNode) {
+        let clean = self.data.clean.borrow();
+        let inputs = match clean.get(&node) {
+            Some(inputs) => inputs,
+            None => panic!("reusing dirty node {:?}", node),
+        };
+        let _task = self.in_task(node);
+        for &input in inputs {
+            self.read(input);
+        }
+    }
+}
+
+pub struct DepTask<'graph> {
+    data: &'graph DepGraphData,
+    key: DepNode,
+}
+
+impl<'graph> DepTask<'graph> {
+    fn new(data: &'graph DepGraphData, key: DepNode) -> DepTask<'graph> {
+        data.edges.borrow_mut().push_task(key);
+        DepTask { data: data, key: key }
+    }
+}
+
+impl<'graph> Drop for DepTask<'graph> {
+    fn drop(&mut self) {
+        self.data.edges.borrow_mut().pop_task(self.key);
+    }
+}
+
+pub struct IgnoreTask<'graph> {
+    data: &'graph DepGraphData,
+}
+
+impl<'graph> IgnoreTask<'graph> {
+    fn new(data: &'graph DepGraphData) -> IgnoreTask<'graph> {
+        data.edges.borrow_mut().push_ignore();
+        IgnoreTask { data: data }
+    }
+}
+
+impl<'graph> Drop for IgnoreTask<'graph> {
+    fn drop(&mut self) {
+        self.data.edges.borrow_mut().pop_ignore();
+    }
+}
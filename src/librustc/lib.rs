@@ -94,6 +94,8 @@ pub mod back {
     pub use rustc_back::svh;
 }
 
+pub mod dep_graph;
+
 pub mod front {
     pub mod check_attr;
     pub mod map;
@@ -155,6 +157,7 @@ pub mod util {
     pub mod lev_distance;
     pub mod num;
     pub mod fs;
+    pub mod profiling;
 }
 
 pub mod lib {
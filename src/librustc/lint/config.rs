@@ -0,0 +1,132 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The lint configuration file given with `--lint-config`.
+//!
+//! It lets a lint policy be shared between crates instead of being
+//! repeated as `-W`/`-A`/`-D`/`-F` flags. It is a JSON object such as
+//!
+//! ```json
+//! {
+//!     "cap": "deny",
+//!     "levels": { "unused": "deny", "missing-docs": "warn" },
+//!     "paths": [
+//!         { "path": "src/generated", "levels": { "dead_code": "allow" } }
+//!     ]
+//! }
+//! ```
+//!
+//! where every key is optional:
+//!
+//! * `levels` maps lint and lint group names to the level they have in the
+//!   whole crate. The flags are applied after it, so they override it.
+//!   Renamed and removed lints are warned about, as they are in flags.
+//! * each entry of `paths` gives the levels of lints emitted in the source
+//!   files under `path`, which is compared with the file names rustc uses
+//!   in its messages. Later entries take precedence over earlier ones. They
+//!   don't override levels set by flags or by attributes.
+//! * `cap` works like `--cap-lints`. If both are given, the most
+//!   restrictive cap is used.
+
+use lint::Level;
+use session::Session;
+
+use serialize::json::{self, Json};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub struct LintConfig {
+    pub cap: Option<Level>,
+    /// The levels of lints and lint groups, by name.
+    pub levels: Vec<(String, Level)>,
+    /// The levels of lints and lint groups emitted under each path.
+    pub paths: Vec<(PathBuf, Vec<(String, Level)>)>,
+}
+
+impl LintConfig {
+    /// Reads the lint configuration file at `path`, aborting if it can't be
+    /// read or is malformed. Lint names aren't checked here, as plugins
+    /// may not have registered theirs yet.
+    pub fn load(sess: &Session, path: &Path) -> LintConfig {
+        let mut contents = String::new();
+        if let Err(e) = File::open(path).and_then(|mut f| f.read_to_string(&mut contents)) {
+            sess.fatal(&format!("couldn't read lint configuration file `{}`: {}",
+                                path.display(), e));
+        }
+        json::from_str(&contents)
+            .map_err(|e| e.to_string())
+            .and_then(|json| LintConfig::from_json(&json))
+            .unwrap_or_else(|e| {
+                sess.fatal(&format!("invalid lint configuration file `{}`: {}",
+                                    path.display(), e))
+            })
+    }
+
+    fn from_json(json: &Json) -> Result<LintConfig, String> {
+        let obj = match json.as_object() {
+            Some(obj) => obj,
+            None => return Err("expected an object".to_string()),
+        };
+
+        let mut config = LintConfig {
+            cap: None,
+            levels: Vec::new(),
+            paths: Vec::new(),
+        };
+        for (key, value) in obj {
+            match &key[..] {
+                "cap" => config.cap = Some(try!(parse_level(value, "cap"))),
+                "levels" => config.levels = try!(parse_levels(value)),
+                "paths" => {
+                    let entries = match value.as_array() {
+                        Some(entries) => entries,
+                        None => return Err("`paths` must be an array".to_string()),
+                    };
+                    for entry in entries {
+                        let path = match entry.find("path").and_then(|p| p.as_string()) {
+                            Some(path) => PathBuf::from(path),
+                            None => {
+                                return Err("every entry of `paths` must have a `path` \
+                                            string".to_string())
+                            }
+                        };
+                        let levels = match entry.find("levels") {
+                            Some(levels) => try!(parse_levels(levels)),
+                            None => Vec::new(),
+                        };
+                        config.paths.push((path, levels));
+                    }
+                }
+                _ => return Err(format!("unknown key `{}`", key)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn parse_levels(json: &Json) -> Result<Vec<(String, Level)>, String> {
+    let obj = match json.as_object() {
+        Some(obj) => obj,
+        None => return Err("`levels` must be an object".to_string()),
+    };
+    let mut levels = Vec::new();
+    for (name, level) in obj {
+        let level = try!(parse_level(level, name));
+        levels.push((name.replace("-", "_"), level));
+    }
+    Ok(levels)
+}
+
+fn parse_level(json: &Json, what: &str) -> Result<Level, String> {
+    json.as_string()
+        .and_then(Level::from_str)
+        .ok_or_else(|| format!("invalid lint level for `{}`: {}", what, json))
+}
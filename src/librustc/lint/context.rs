@@ -27,16 +27,18 @@ use self::TargetLint::*;
 
 use middle::privacy::ExportedItems;
 use middle::ty::{self, Ty};
-use session::{early_error, Session};
+use session::{config, early_error, Session};
 use lint::{Level, LevelSource, Lint, LintId, LintArray, LintPass};
 use lint::{EarlyLintPass, EarlyLintPassObject, LateLintPass, LateLintPassObject};
-use lint::{Default, CommandLine, Node, Allow, Warn, Deny, Forbid};
+use lint::{Default, CommandLine, ConfigFile, Node, Allow, Warn, Deny, Forbid};
 use lint::builtin;
+use lint::config::LintConfig;
 use util::nodemap::FnvHashMap;
 
 use std::cell::RefCell;
 use std::cmp;
 use std::mem;
+use std::path::{Path, PathBuf};
 use syntax::ast_util::{self, IdVisitingOperation};
 use syntax::attr::{self, AttrMetaMethods};
 use syntax::codemap::Span;
@@ -46,7 +48,6 @@ use rustc_front::hir;
 use rustc_front::util;
 use rustc_front::visit as hir_visit;
 use syntax::visit as ast_visit;
-use syntax::diagnostic;
 
 /// Information about the registered lints.
 ///
@@ -75,6 +76,10 @@ pub struct LintStore {
 
     /// Maximum level a lint can be
     lint_cap: Option<Level>,
+
+    /// Levels of lints emitted under certain paths, from the lint
+    /// configuration file. Later entries take precedence.
+    path_levels: Vec<(PathBuf, Vec<(LintId, Level)>)>,
 }
 
 /// The targed of the `by_name` map, which accounts for renaming/deprecation.
@@ -107,7 +112,9 @@ impl LintStore {
         if let Some(cap) = self.lint_cap {
             lvlsrc.0 = cmp::min(lvlsrc.0, cap);
         }
-        if lvlsrc.0 == Allow {
+        // Lints explicitly allowed are kept, so that the path overrides of
+        // the lint configuration file don't apply to them.
+        if lvlsrc.0 == Allow && lvlsrc.1 == Default {
             self.levels.remove(&lint);
         } else {
             self.levels.insert(lint, lvlsrc);
@@ -123,6 +130,7 @@ impl LintStore {
             levels: FnvHashMap(),
             lint_groups: FnvHashMap(),
             lint_cap: None,
+            path_levels: vec!(),
         }
     }
 
@@ -166,7 +174,7 @@ impl LintStore {
                 match (sess, from_plugin) {
                     // We load builtin lints first, so a duplicate is a compiler bug.
                     // Use early_error when handling -W help with no crate.
-                    (None, _) => early_error(diagnostic::Auto, &msg[..]),
+                    (None, _) => early_error(config::ErrorOutputType::default(), &msg[..]),
                     (Some(sess), false) => sess.bug(&msg[..]),
 
                     // A duplicate name from a plugin is a user error.
@@ -190,7 +198,7 @@ impl LintStore {
             match (sess, from_plugin) {
                 // We load builtin lints first, so a duplicate is a compiler bug.
                 // Use early_error when handling -W help with no crate.
-                (None, _) => early_error(diagnostic::Auto, &msg[..]),
+                (None, _) => early_error(config::ErrorOutputType::default(), &msg[..]),
                 (Some(sess), false) => sess.bug(&msg[..]),
 
                 // A duplicate name from a plugin is a user error.
@@ -238,7 +246,80 @@ impl LintStore {
         }
     }
 
+    /// Get the level of `lint` when emitted at `span`, taking into account
+    /// the path overrides of the lint configuration file.
+    fn get_level_source_at(&self, sess: &Session, lint: LintId, span: Option<Span>)
+                           -> LevelSource {
+        let mut lvlsrc = self.get_level_source(lint);
+        match (lvlsrc.1, span) {
+            (Default, Some(span)) | (ConfigFile, Some(span)) if !self.path_levels.is_empty() => {
+                let filename = sess.codemap().span_to_filename(span);
+                for &(ref path, ref levels) in &self.path_levels {
+                    if !Path::new(&filename).starts_with(path) {
+                        continue;
+                    }
+                    for &(lint_id, level) in levels {
+                        if lint_id == lint {
+                            lvlsrc = (level, ConfigFile);
+                        }
+                    }
+                }
+                if let Some(cap) = self.lint_cap {
+                    lvlsrc.0 = cmp::min(lvlsrc.0, cap);
+                }
+                lvlsrc
+            }
+            _ => lvlsrc,
+        }
+    }
+
+    /// Resolve a lint or lint group named in the lint configuration file.
+    /// Renamed and removed lints are warned about as for the `-W`/`-A`/`-D`
+    /// flags; the levels of removed ones are ignored.
+    fn config_lint_ids(&self, sess: &Session, lint_name: &str) -> Vec<LintId> {
+        match self.find_lint(lint_name, sess, None) {
+            Ok(lint_id) => vec![lint_id],
+            Err(FindLintError::Removed) => vec![],
+            Err(FindLintError::NotFound) => {
+                match self.lint_groups.get(lint_name) {
+                    Some(&(ref v, _)) => v.clone(),
+                    None => {
+                        sess.err(&format!("unknown lint in lint configuration file: {}",
+                                          lint_name));
+                        vec![]
+                    }
+                }
+            }
+        }
+    }
+
+    fn process_config_file(&mut self, sess: &Session, config: &LintConfig) {
+        for &(ref lint_name, level) in &config.levels {
+            for lint_id in self.config_lint_ids(sess, lint_name) {
+                self.set_level(lint_id, (level, ConfigFile));
+            }
+        }
+
+        let mut path_levels = vec![];
+        for &(ref path, ref levels) in &config.paths {
+            let mut lint_levels = vec![];
+            for &(ref lint_name, level) in levels {
+                for lint_id in self.config_lint_ids(sess, lint_name) {
+                    lint_levels.push((lint_id, level));
+                }
+            }
+            path_levels.push((path.clone(), lint_levels));
+        }
+        self.path_levels = path_levels;
+    }
+
     pub fn process_command_line(&mut self, sess: &Session) {
+        // The configuration file comes first, so that flags override it.
+        let config = sess.opts.lint_config.as_ref().map(|path| LintConfig::load(sess, path));
+        if let Some(ref config) = config {
+            self.process_config_file(sess, config);
+        }
+
         for &(ref lint_name, level) in &sess.opts.lint_opts {
             match self.find_lint(&lint_name[..], sess, None) {
                 Ok(lint_id) => self.set_level(lint_id, (level, CommandLine)),
@@ -260,7 +341,10 @@ impl LintStore {
             }
         }
 
-        self.lint_cap = sess.opts.lint_cap;
+        self.lint_cap = match (sess.opts.lint_cap, config.and_then(|c| c.cap)) {
+            (Some(a), Some(b)) => Some(cmp::min(a, b)),
+            (a, b) => a.or(b),
+        };
         if let Some(cap) = self.lint_cap {
             for level in self.levels.iter_mut().map(|p| &mut (p.1).0) {
                 *level = cmp::min(*level, cap);
@@ -381,6 +465,10 @@ pub fn raw_emit_lint(sess: &Session, lint: &'static Lint,
                         Allow => panic!()
                     }, name.replace("_", "-"))
         },
+        ConfigFile => {
+            format!("{}, #[{}({})] set by the lint configuration file", msg,
+                    level.as_str(), name)
+        },
         Node(src) => {
             def = Some(src);
             msg.to_string()
@@ -411,20 +499,21 @@ pub trait LintContext: Sized {
     fn enter_attrs(&mut self, attrs: &[ast::Attribute]);
     fn exit_attrs(&mut self, attrs: &[ast::Attribute]);
 
-    /// Get the level of `lint` at the current position of the lint
-    /// traversal.
-    fn current_level(&self, lint: &'static Lint) -> Level {
-        self.lints().levels.get(&LintId::of(lint)).map_or(Allow, |&(lvl, _)| lvl)
+    /// Get the level of `lint` when emitted at `span` from the current
+    /// position of the lint traversal.
+    fn current_level(&self, lint: &'static Lint, span: Span) -> Level {
+        self.lints().get_level_source_at(self.sess(), LintId::of(lint), Some(span)).0
     }
 
     fn lookup_and_emit(&self, lint: &'static Lint, span: Option<Span>, msg: &str) {
-        let (level, src) = match self.lints().levels.get(&LintId::of(lint)) {
-            None => return,
-            Some(&(Warn, src)) => {
+        let lint_id = LintId::of(lint);
+        let (level, src) = match self.lints().get_level_source_at(self.sess(), lint_id, span) {
+            (Allow, _) => return,
+            (Warn, src) => {
                 let lint_id = LintId::of(builtin::WARNINGS);
                 (self.lints().get_level_source(lint_id).0, src)
             }
-            Some(&pair) => pair,
+            pair => pair,
         };
 
         raw_emit_lint(&self.sess(), lint, (level, src), span, msg);
@@ -439,7 +528,7 @@ pub trait LintContext: Sized {
     fn span_lint_note(&self, lint: &'static Lint, span: Span, msg: &str,
                       note_span: Span, note: &str) {
         self.span_lint(lint, span, msg);
-        if self.current_level(lint) != Level::Allow {
+        if self.current_level(lint, span) != Level::Allow {
             if note_span == span {
                 self.sess().fileline_note(note_span, note)
             } else {
@@ -452,7 +541,7 @@ pub trait LintContext: Sized {
     fn span_lint_help(&self, lint: &'static Lint, span: Span,
                       msg: &str, help: &str) {
         self.span_lint(lint, span, msg);
-        if self.current_level(lint) != Level::Allow {
+        if self.current_level(lint, span) != Level::Allow {
             self.sess().span_help(span, help)
         }
     }
@@ -187,6 +187,11 @@ pub const tag_method_argument_name: usize = 0x86;
 pub const tag_reachable_ids: usize = 0x10c; // top-level only
 pub const tag_reachable_id: usize = 0x87;
 
+// The items of other crates that this crate refers to, for
+// `-Z unused-exports`.
+pub const tag_used_external_ids: usize = 0x113; // top-level only
+pub const tag_used_external_id: usize = 0xa7;
+
 pub const tag_items_data_item_stability: usize = 0x88;
 
 pub const tag_items_data_item_repr: usize = 0x89;
@@ -334,6 +334,14 @@ pub fn get_reachable_ids(cstore: &cstore::CStore, cnum: ast::CrateNum)
     decoder::get_reachable_ids(&*cdata)
 }
 
+/// The items of other crates that crate `cnum` refers to.
+pub fn get_used_external_ids(cstore: &cstore::CStore, cnum: ast::CrateNum)
+    -> Vec<DefId>
+{
+    let cdata = cstore.get_crate_data(cnum);
+    decoder::get_used_external_ids(&*cdata)
+}
+
 pub fn is_typedef(cstore: &cstore::CStore, did: DefId) -> bool {
     let cdata = cstore.get_crate_data(did.krate);
     decoder::is_typedef(&*cdata, did.index)
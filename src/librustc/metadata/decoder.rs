@@ -1409,6 +1409,17 @@ pub fn get_reachable_ids(cdata: Cmd) -> Vec<DefId> {
     }).collect()
 }
 
+pub fn get_used_external_ids(cdata: Cmd) -> Vec<DefId> {
+    match reader::maybe_get_doc(rbml::Doc::new(cdata.data()), tag_used_external_ids) {
+        Some(doc) => {
+            reader::tagged_docs(doc, tag_used_external_id).map(|doc| {
+                translated_def_id(cdata, doc)
+            }).collect()
+        }
+        None => vec![],
+    }
+}
+
 pub fn is_typedef(cdata: Cmd, id: DefIndex) -> bool {
     let item_doc = cdata.lookup_item(id);
     match item_family(item_doc) {
@@ -22,6 +22,7 @@ use metadata::decoder;
 use metadata::tyencode;
 use metadata::index::{self, IndexData};
 use metadata::inline::InlinedItemRef;
+use middle::dead;
 use middle::def;
 use middle::def_id::{CRATE_DEF_INDEX, DefId};
 use middle::dependency_format::Linkage;
@@ -1820,6 +1821,19 @@ fn encode_reachable(ecx: &EncodeContext, rbml_w: &mut Encoder) {
     rbml_w.end_tag();
 }
 
+// Encodes the items of other crates that this crate refers to, so that
+// crates depending on this one can tell which public items of their
+// dependencies are unused. This is only done under `-Z unused-exports`.
+fn encode_used_external_ids(ecx: &EncodeContext, rbml_w: &mut Encoder) {
+    let mut used: Vec<_> = dead::used_external_items(ecx.tcx).into_iter().collect();
+    used.sort();
+    rbml_w.start_tag(tag_used_external_ids);
+    for def_id in used {
+        rbml_w.wr_tagged_u64(tag_used_external_id, def_to_u64(def_id));
+    }
+    rbml_w.end_tag();
+}
+
 fn encode_crate_dep(rbml_w: &mut Encoder,
                     dep: &cstore::crate_metadata) {
     rbml_w.start_tag(tag_crate_dep);
@@ -2017,6 +2031,9 @@ fn encode_metadata_inner(wr: &mut Cursor<Vec<u8>>,
     i = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap();
     encode_misc_info(&ecx, krate, &mut rbml_w);
     encode_reachable(&ecx, &mut rbml_w);
+    if ecx.tcx.sess.opts.debugging_opts.unused_exports.is_some() {
+        encode_used_external_ids(&ecx, &mut rbml_w);
+    }
     stats.misc_bytes = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap() - i;
 
     // Encode and index the items.
@@ -14,6 +14,7 @@ use self::ConstVal::*;
 use self::ErrKind::*;
 use self::EvalHint::*;
 
+use dep_graph::DepNode;
 use front::map as ast_map;
 use front::map::blocks::FnLikeNode;
 use metadata::csearch;
@@ -94,6 +95,8 @@ pub fn lookup_const_by_id<'a, 'tcx: 'a>(tcx: &'a ty::ctxt<'tcx>,
                                         maybe_ref_id: Option<ast::NodeId>)
                                         -> Option<&'tcx Expr> {
     if let Some(node_id) = tcx.map.as_local_node_id(def_id) {
+        // The value of the constant is part of its HIR.
+        tcx.dep_graph.read(DepNode::Hir(def_id));
         match tcx.map.find(node_id) {
             None => None,
             Some(ast_map::NodeItem(it)) => match it.node {
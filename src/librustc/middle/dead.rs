@@ -16,10 +16,12 @@ use front::map as ast_map;
 use rustc_front::hir;
 use rustc_front::visit::{self, Visitor};
 
+use metadata::csearch;
+use metadata::decoder::DlDef;
 use middle::{def, pat_util, privacy, ty};
 use middle::def_id::{DefId};
 use lint;
-use util::nodemap::NodeSet;
+use util::nodemap::{DefIdSet, NodeSet};
 
 use std::collections::HashSet;
 use syntax::{ast, codemap};
@@ -614,3 +616,102 @@ pub fn check_crate(tcx: &ty::ctxt,
     let mut visitor = DeadVisitor { tcx: tcx, live_symbols: live_symbols };
     visit::walk_crate(&mut visitor, krate);
 }
+
+/// Returns the items of other crates that this crate refers to, whether
+/// by path or through a method call.
+pub fn used_external_items(tcx: &ty::ctxt) -> DefIdSet {
+    let mut used = DefIdSet();
+    {
+        let mut insert = |def_id: DefId| {
+            if !def_id.is_local() {
+                used.insert(def_id);
+            }
+        };
+        for resolution in tcx.def_map.borrow().values() {
+            match resolution.base_def {
+                def::DefVariant(enum_id, variant_id, _) => {
+                    insert(enum_id);
+                    insert(variant_id);
+                }
+                def::DefLabel(..) | def::DefPrimTy(..) | def::DefSelfTy(..) => {}
+                def => insert(def.def_id()),
+            }
+        }
+        for callee in tcx.tables.borrow().method_map.values() {
+            insert(callee.def_id);
+        }
+    }
+    used
+}
+
+/// Warns about the public items of the crates named in `crate_names` that
+/// neither this crate nor any of the crates it depends on refers to. Only
+/// the uses of the dependencies built with `-Z unused-exports` are known.
+///
+/// Only the items reachable through public modules are checked, not the
+/// methods of impls. Since uses are taken from paths and method calls, a
+/// type which is used without ever being named is reported too.
+pub fn check_unused_exports(tcx: &ty::ctxt, crate_names: &[&str]) {
+    let cstore = &tcx.sess.cstore;
+
+    let mut used = used_external_items(tcx);
+    let mut checked = vec![];
+    cstore.iter_crate_data(|cnum, cdata| {
+        used.extend(csearch::get_used_external_ids(cstore, cnum));
+        if crate_names.contains(&&cdata.name[..]) {
+            checked.push(cnum);
+        }
+    });
+
+    for &name in crate_names {
+        if !checked.iter().any(|&cnum| cstore.get_crate_data(cnum).name == name) {
+            tcx.sess.err(&format!("`-Z unused-exports`: `{}` is not a dependency of this crate",
+                                  name));
+        }
+    }
+
+    let mut unused = vec![];
+    for cnum in checked {
+        let mut worklist = vec![];
+        let mut visited = DefIdSet();
+        let mut visit_child = |def: def::Def, vis: hir::Visibility,
+                               worklist: &mut Vec<DefId>| {
+            if vis != hir::Public {
+                return;
+            }
+            match def {
+                def::DefMod(def_id) if def_id.krate == cnum => {
+                    if visited.insert(def_id) {
+                        worklist.push(def_id);
+                    }
+                }
+                def::DefFn(def_id, _) | def::DefStatic(def_id, _) | def::DefConst(def_id) |
+                def::DefStruct(def_id) | def::DefTy(def_id, _) | def::DefTrait(def_id)
+                        if def_id.krate == cnum => {
+                    if !used.contains(&def_id) && visited.insert(def_id) {
+                        unused.push(tcx.item_path_str(def_id));
+                    }
+                }
+                _ => {}
+            }
+        };
+
+        csearch::each_top_level_item_of_crate(cstore, cnum, |def_like, _, vis| {
+            if let DlDef(def) = def_like {
+                visit_child(def, vis, &mut worklist);
+            }
+        });
+        while let Some(module) = worklist.pop() {
+            csearch::each_child_of_item(cstore, module, |def_like, _, vis| {
+                if let DlDef(def) = def_like {
+                    visit_child(def, vis, &mut worklist);
+                }
+            });
+        }
+    }
+
+    unused.sort();
+    for path in unused {
+        tcx.sess.warn(&format!("public item `{}` is not used by any crate in this build", path));
+    }
+}
@@ -13,6 +13,7 @@
 // FIXME: (@jroesch) @eddyb should remove this when he renames ctxt
 #![allow(non_camel_case_types)]
 
+use dep_graph::DepGraph;
 use front::map as ast_map;
 use session::Session;
 use lint;
@@ -230,6 +231,10 @@ pub struct ctxt<'tcx> {
     pub sess: &'tcx Session,
     pub def_map: RefCell<DefMap>,
 
+    /// Records which results were computed from which inputs, for
+    /// incremental compilation.
+    pub dep_graph: DepGraph,
+
     pub named_region_map: resolve_lifetime::NamedRegionMap,
 
     pub region_maps: RegionMaps,
@@ -481,6 +486,7 @@ impl<'tcx> ctxt<'tcx> {
             variance_computed: Cell::new(false),
             sess: s,
             def_map: def_map,
+            dep_graph: DepGraph::new(s.opts.build_dep_graph()),
             tables: RefCell::new(Tables::empty()),
             impl_trait_refs: RefCell::new(DefIdMap()),
             trait_defs: RefCell::new(DefIdMap()),
@@ -19,6 +19,7 @@ pub use self::ImplOrTraitItem::*;
 pub use self::IntVarValue::*;
 pub use self::LvaluePreference::*;
 
+use dep_graph::DepNode;
 use front::map as ast_map;
 use front::map::LinkedPath;
 use metadata::csearch;
@@ -2305,6 +2306,7 @@ impl<'tcx> ctxt<'tcx> {
     // If the given item is in an external crate, looks up its type and adds it to
     // the type cache. Returns the type parameters and type.
     pub fn lookup_item_type(&self, did: DefId) -> TypeScheme<'tcx> {
+        self.dep_graph.read(DepNode::ItemSignature(did));
         lookup_locally_or_in_crate_store(
             "tcache", did, &self.tcache,
             || csearch::get_type(self, did))
@@ -2337,6 +2339,7 @@ impl<'tcx> ctxt<'tcx> {
 
     /// Given the did of an item, returns its full set of predicates.
     pub fn lookup_predicates(&self, did: DefId) -> GenericPredicates<'tcx> {
+        self.dep_graph.read(DepNode::Predicates(did));
         lookup_locally_or_in_crate_store(
             "predicates", did, &self.predicates,
             || csearch::get_predicates(self, did))
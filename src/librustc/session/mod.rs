@@ -17,12 +17,14 @@ use util::nodemap::{NodeMap, FnvHashMap};
 
 use syntax::ast::{NodeId, NodeIdAssigner};
 use syntax::codemap::Span;
-use syntax::diagnostic::{self, Emitter};
+use syntax::diagnostic::{self, Emitter, SpanLabel};
 use syntax::diagnostics;
 use syntax::feature_gate;
+use syntax::json::JsonEmitter;
 use syntax::parse;
 use syntax::parse::token;
 use syntax::parse::ParseSess;
+use syntax::suggestions;
 use syntax::{ast, codemap};
 use syntax::feature_gate::AttributeType;
 
@@ -128,6 +130,26 @@ impl Session {
             None => self.diagnostic().span_err_with_code(sp, msg, code)
         }
     }
+    /// Reports an error pointing at all of `spans` at once; see
+    /// `diagnostic::SpanLabel`.
+    pub fn labelled_span_err(&self, spans: &[SpanLabel], msg: &str) {
+        if self.opts.treat_err_as_bug {
+            self.span_bug(diagnostic::primary_span(spans), msg);
+        }
+        match split_msg_into_multilines(msg) {
+            Some(msg) => self.diagnostic().labelled_span_err(spans, &msg[..]),
+            None => self.diagnostic().labelled_span_err(spans, msg)
+        }
+    }
+    pub fn labelled_span_err_with_code(&self, spans: &[SpanLabel], msg: &str, code: &str) {
+        if self.opts.treat_err_as_bug {
+            self.span_bug(diagnostic::primary_span(spans), msg);
+        }
+        match split_msg_into_multilines(msg) {
+            Some(msg) => self.diagnostic().labelled_span_err_with_code(spans, &msg[..], code),
+            None => self.diagnostic().labelled_span_err_with_code(spans, msg, code)
+        }
+    }
     pub fn err(&self, msg: &str) {
         if self.opts.treat_err_as_bug {
             self.bug(msg);
@@ -141,6 +163,11 @@ impl Session {
         self.diagnostic().handler().has_errors()
     }
     pub fn abort_if_errors(&self) {
+        // Compilation stops because of the errors reported so far, so
+        // all their suggestions have been made.
+        if self.has_errors() {
+            self.apply_suggestions();
+        }
         self.diagnostic().handler().abort_if_errors();
 
         let delayed_bug = self.delayed_span_bug.borrow();
@@ -286,6 +313,20 @@ impl Session {
     pub fn unstable_options(&self) -> bool {
         self.opts.debugging_opts.unstable_options
     }
+    /// Rewrites the source files according to the suggestions made
+    /// so far, if `-Z apply-suggestions` was given.
+    pub fn apply_suggestions(&self) {
+        if !self.opts.debugging_opts.apply_suggestions {
+            return;
+        }
+        let suggestions = self.diagnostic().handler().take_suggestions();
+        match suggestions::apply_suggestions(self.codemap(), &suggestions) {
+            Ok(0) => {}
+            Ok(1) => self.note("applied 1 suggestion"),
+            Ok(n) => self.note(&format!("applied {} suggestions", n)),
+            Err(e) => self.err(&format!("could not apply suggestions: {}", e)),
+        }
+    }
     pub fn print_enum_sizes(&self) -> bool {
         self.opts.debugging_opts.print_enum_sizes
     }
@@ -400,14 +441,28 @@ pub fn build_session(sopts: config::Options,
         .unwrap_or(true);
 
     let codemap = codemap::CodeMap::new();
+    let emitter = build_emitter(sopts.error_format, Some(registry));
     let diagnostic_handler =
-        diagnostic::Handler::new(sopts.color, Some(registry), can_print_warnings);
+        diagnostic::Handler::with_emitter(can_print_warnings, emitter);
     let span_diagnostic_handler =
         diagnostic::SpanHandler::new(diagnostic_handler, codemap);
 
     build_session_(sopts, local_crate_source_file, span_diagnostic_handler)
 }
 
+/// Creates an emitter writing to stderr in the format selected by
+/// `--error-format`.
+pub fn build_emitter(output: config::ErrorOutputType,
+                     registry: Option<diagnostics::registry::Registry>)
+                     -> Box<Emitter + Send> {
+    match output {
+        config::ErrorOutputType::HumanReadable(color_config) => {
+            Box::new(diagnostic::EmitterWriter::stderr(color_config, registry))
+        }
+        config::ErrorOutputType::Json => Box::new(JsonEmitter::stderr(registry)),
+    }
+}
+
 pub fn build_session_(sopts: config::Options,
                       local_crate_source_file: Option<PathBuf>,
                       span_diagnostic: diagnostic::SpanHandler)
@@ -480,13 +535,13 @@ pub fn expect<T, M>(sess: &Session, opt: Option<T>, msg: M) -> T where
     diagnostic::expect(sess.diagnostic(), opt, msg)
 }
 
-pub fn early_error(color: diagnostic::ColorConfig, msg: &str) -> ! {
-    let mut emitter = diagnostic::EmitterWriter::stderr(color, None);
+pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
+    let mut emitter = build_emitter(output, None);
     emitter.emit(None, msg, None, diagnostic::Fatal);
     panic!(diagnostic::FatalError);
 }
 
-pub fn early_warn(color: diagnostic::ColorConfig, msg: &str) {
-    let mut emitter = diagnostic::EmitterWriter::stderr(color, None);
+pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
+    let mut emitter = build_emitter(output, None);
     emitter.emit(None, msg, None, diagnostic::Warning);
 }
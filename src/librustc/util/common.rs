@@ -22,6 +22,7 @@ use std::time::Duration;
 
 use rustc_front::hir;
 use rustc_front::visit;
+use util::profiling;
 use rustc_front::visit::Visitor;
 
 // The name of the associated type for `Fn` return types
@@ -34,6 +35,15 @@ pub struct ErrorReported;
 
 pub fn time<T, F>(do_it: bool, what: &str, f: F) -> T where
     F: FnOnce() -> T,
+{
+    if profiling::is_enabled() {
+        return profiling::profile_pass(what, || time_inner(do_it, what, f));
+    }
+    time_inner(do_it, what, f)
+}
+
+fn time_inner<T, F>(do_it: bool, what: &str, f: F) -> T where
+    F: FnOnce() -> T,
 {
     thread_local!(static DEPTH: Cell<usize> = Cell::new(0));
     if !do_it { return f(); }
@@ -82,7 +92,7 @@ macro_rules! option_try(
 
 // Memory reporting
 #[cfg(unix)]
-fn get_resident() -> Option<usize> {
+pub fn get_resident() -> Option<usize> {
     use std::fs::File;
     use std::io::Read;
 
@@ -95,9 +105,36 @@ fn get_resident() -> Option<usize> {
     Some(npages * 4096)
 }
 
+/// The largest resident set size the process has had so far.
+#[cfg(unix)]
+pub fn get_peak_resident() -> Option<usize> {
+    use std::fs::File;
+    use std::io::Read;
+
+    let mut f = option_try!(File::open("/proc/self/status").ok());
+    let mut contents = String::new();
+    option_try!(f.read_to_string(&mut contents).ok());
+    let line = option_try!(contents.lines().find(|line| line.starts_with("VmHWM:")));
+    let s = option_try!(line.split_whitespace().nth(1));
+    let kb = option_try!(s.parse::<usize>().ok());
+    Some(kb * 1024)
+}
+
+#[cfg(windows)]
+pub fn get_resident() -> Option<usize> {
+    get_memory_counters().map(|(resident, _)| resident)
+}
+
+/// The largest resident set size the process has had so far.
+#[cfg(windows)]
+pub fn get_peak_resident() -> Option<usize> {
+    get_memory_counters().map(|(_, peak)| peak)
+}
+
+// Returns the current and the peak working set size.
 #[cfg(windows)]
 #[cfg_attr(stage0, allow(improper_ctypes))]
-fn get_resident() -> Option<usize> {
+fn get_memory_counters() -> Option<(usize, usize)> {
     type BOOL = i32;
     type DWORD = u32;
     type HANDLE = *mut u8;
@@ -128,7 +165,7 @@ fn get_resident() -> Option<usize> {
     pmc.cb = mem::size_of_val(&pmc) as DWORD;
     match unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut pmc, pmc.cb) } {
         0 => None,
-        _ => Some(pmc.WorkingSetSize as usize),
+        _ => Some((pmc.WorkingSetSize as usize, pmc.PeakWorkingSetSize as usize)),
     }
 }
 
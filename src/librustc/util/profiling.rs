@@ -0,0 +1,202 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The self-profiler behind `-Z self-profile`.
+//!
+//! Once `start` has been called, every pass run through
+//! `util::common::time` and every item wrapped in `profile_item` is
+//! recorded as an event, together with the time it took and the number of
+//! arena allocations made meanwhile. Passes also record the resident set
+//! size at their end; this is too costly to do for every item.
+//!
+//! Events are kept in pre-order, so a pass is followed by the events
+//! nested in it, which have a larger `depth`. `finish` writes them out as
+//! JSON or CSV, preceded by the totals for the whole session.
+
+use arena;
+use serialize::json;
+use util::common::{get_resident, get_peak_resident};
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match *self {
+            Format::Json => "json",
+            Format::Csv => "csv",
+        }
+    }
+}
+
+#[derive(RustcEncodable)]
+struct Profile {
+    /// The sum of the durations of the outermost passes.
+    total_ns: u64,
+    peak_rss: Option<usize>,
+    arena_allocs: usize,
+    events: Vec<Event>,
+}
+
+#[derive(RustcEncodable)]
+struct Event {
+    /// `pass` for a pass, otherwise what was done to the item, e.g. `typeck`.
+    kind: &'static str,
+    /// The name of the pass, or the path of the item.
+    name: String,
+    depth: usize,
+    duration_ns: u64,
+    rss: Option<usize>,
+    arena_allocs: usize,
+}
+
+struct Profiler {
+    events: Vec<Event>,
+    depth: usize,
+    initial_arena_allocs: usize,
+}
+
+thread_local!(static PROFILER: RefCell<Option<Profiler>> = RefCell::new(None));
+
+/// Starts recording events on this thread.
+pub fn start() {
+    arena::count_allocations();
+    PROFILER.with(|profiler| {
+        *profiler.borrow_mut() = Some(Profiler {
+            events: Vec::new(),
+            depth: 0,
+            initial_arena_allocs: arena::allocation_count(),
+        });
+    });
+}
+
+pub fn is_enabled() -> bool {
+    PROFILER.with(|profiler| profiler.borrow().is_some())
+}
+
+/// Runs the pass `what`, recording it if the profiler is enabled.
+pub fn profile_pass<T, F>(what: &str, f: F) -> T where
+    F: FnOnce() -> T,
+{
+    if !is_enabled() { return f(); }
+    record("pass", what.to_string(), true, f)
+}
+
+/// Runs `f`, which does the work of kind `kind` (`typeck`, `borrowck`,
+/// `trans`...) for a single item, recording it if the profiler is enabled.
+/// `name` is only called in that case.
+pub fn profile_item<T, N, F>(kind: &'static str, name: N, f: F) -> T where
+    N: FnOnce() -> String,
+    F: FnOnce() -> T,
+{
+    if !is_enabled() { return f(); }
+    record(kind, name(), false, f)
+}
+
+fn record<T, F>(kind: &'static str, name: String, measure_rss: bool, f: F) -> T where
+    F: FnOnce() -> T,
+{
+    let index = PROFILER.with(|profiler| {
+        let mut profiler = profiler.borrow_mut();
+        let profiler = profiler.as_mut().unwrap();
+        profiler.events.push(Event {
+            kind: kind,
+            name: name,
+            depth: profiler.depth,
+            duration_ns: 0,
+            rss: None,
+            arena_allocs: 0,
+        });
+        profiler.depth += 1;
+        profiler.events.len() - 1
+    });
+
+    let allocs_before = arena::allocation_count();
+    let mut rv = None;
+    let dur = {
+        let ref mut rvp = rv;
+
+        Duration::span(move || {
+            *rvp = Some(f())
+        })
+    };
+    let arena_allocs = arena::allocation_count() - allocs_before;
+    let rss = if measure_rss { get_resident() } else { None };
+
+    PROFILER.with(|profiler| {
+        if let Some(ref mut profiler) = *profiler.borrow_mut() {
+            let event = &mut profiler.events[index];
+            event.duration_ns = nanos(dur);
+            event.rss = rss;
+            event.arena_allocs = arena_allocs;
+            profiler.depth -= 1;
+        }
+    });
+
+    rv.unwrap()
+}
+
+fn nanos(dur: Duration) -> u64 {
+    dur.as_secs() * 1_000_000_000 + dur.subsec_nanos() as u64
+}
+
+/// Stops recording events on this thread, and writes those recorded so
+/// far to `path`.
+pub fn finish(path: &Path, format: Format) -> io::Result<()> {
+    let profiler = match PROFILER.with(|profiler| profiler.borrow_mut().take()) {
+        Some(profiler) => profiler,
+        None => return Ok(()),
+    };
+    let profile = Profile {
+        total_ns: profiler.events.iter()
+                                 .filter(|event| event.depth == 0)
+                                 .map(|event| event.duration_ns)
+                                 .fold(0, |a, b| a + b),
+        peak_rss: get_peak_resident(),
+        arena_allocs: arena::allocation_count() - profiler.initial_arena_allocs,
+        events: profiler.events,
+    };
+
+    let mut file = try!(File::create(path));
+    match format {
+        Format::Json => writeln!(file, "{}", json::as_pretty_json(&profile)),
+        Format::Csv => write_csv(&profile, &mut file),
+    }
+}
+
+/// Writes one row per event, preceded by a `total` row for the session.
+fn write_csv(profile: &Profile, w: &mut Write) -> io::Result<()> {
+    fn opt(n: Option<usize>) -> String {
+        n.map(|n| n.to_string()).unwrap_or(String::new())
+    }
+
+    try!(writeln!(w, "kind,name,depth,duration_ns,rss,arena_allocs"));
+    try!(writeln!(w, "total,\"\",0,{},{},{}",
+                  profile.total_ns, opt(profile.peak_rss), profile.arena_allocs));
+    for event in &profile.events {
+        try!(writeln!(w, "{},\"{}\",{},{},{},{}",
+                      event.kind,
+                      event.name.replace("\"", "\"\""),
+                      event.depth,
+                      event.duration_ns,
+                      opt(event.rss),
+                      event.arena_allocs));
+    }
+    Ok(())
+}
@@ -127,7 +127,7 @@ impl fmt::Display for Svh {
 // hash computation, but for many kinds of items the order of
 // declaration should be irrelevant to the ABI.
 
-mod svh_visitor {
+pub mod svh_visitor {
     pub use self::SawExprComponent::*;
     pub use self::SawStmtComponent::*;
     use self::SawAbiComponent::*;
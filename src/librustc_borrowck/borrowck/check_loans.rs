@@ -27,7 +27,8 @@ use rustc::middle::mem_categorization::Categorization;
 use rustc::middle::region;
 use rustc::middle::ty;
 use syntax::ast;
-use syntax::codemap::Span;
+use syntax::codemap::{BytePos, Span};
+use syntax::diagnostic::SpanLabel;
 use rustc_front::hir;
 
 use std::rc::Rc;
@@ -465,12 +466,26 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
                 format!("`{}`", ol)
             };
 
+            let old_loan_span = self.tcx().map.span(
+                old_loan.kill_scope.node_id(&self.tcx().region_maps));
+
             match (new_loan.kind, old_loan.kind) {
                 (ty::MutBorrow, ty::MutBorrow) => {
-                    span_err!(self.bccx, new_loan.span, E0499,
-                              "cannot borrow `{}`{} as mutable \
-                               more than once at a time",
-                              nl, new_loan_msg);
+                    let spans = [
+                        SpanLabel::primary(new_loan.span,
+                                           Some("second mutable borrow occurs here".to_string())),
+                        SpanLabel::secondary(old_loan.span,
+                                             Some(format!("first mutable borrow occurs here{}",
+                                                          old_loan_msg))),
+                        SpanLabel::secondary(end_point(old_loan_span),
+                                             Some("first borrow ends here".to_string())),
+                    ];
+                    labelled_span_err!(self.bccx, &spans, E0499,
+                                       "cannot borrow `{}`{} as mutable \
+                                        more than once at a time",
+                                       nl, new_loan_msg);
+                    self.note_closure_capture(new_loan, &nl);
+                    return false;
                 }
 
                 (ty::UniqueImmBorrow, _) => {
@@ -488,27 +503,32 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
                 }
 
                 (_, _) => {
-                    span_err!(self.bccx, new_loan.span, E0502,
-                              "cannot borrow `{}`{} as {} because \
-                               {} is also borrowed as {}{}",
-                              nl,
-                              new_loan_msg,
-                              new_loan.kind.to_user_str(),
-                              ol_pronoun,
-                              old_loan.kind.to_user_str(),
-                              old_loan_msg);
+                    let new_kind = new_loan.kind.to_user_str();
+                    let old_kind = old_loan.kind.to_user_str();
+                    let spans = [
+                        SpanLabel::primary(new_loan.span,
+                                           Some(format!("{} borrow occurs here", new_kind))),
+                        SpanLabel::secondary(old_loan.span,
+                                             Some(format!("{} borrow occurs here{}",
+                                                          old_kind, old_loan_msg))),
+                        SpanLabel::secondary(end_point(old_loan_span),
+                                             Some(format!("{} borrow ends here", old_kind))),
+                    ];
+                    labelled_span_err!(self.bccx, &spans, E0502,
+                                       "cannot borrow `{}`{} as {} because \
+                                        {} is also borrowed as {}{}",
+                                       nl,
+                                       new_loan_msg,
+                                       new_kind,
+                                       ol_pronoun,
+                                       old_kind,
+                                       old_loan_msg);
+                    self.note_closure_capture(new_loan, &nl);
+                    return false;
                 }
             }
 
-            match new_loan.cause {
-                euv::ClosureCapture(span) => {
-                    self.bccx.span_note(
-                        span,
-                        &format!("borrow occurs due to use of `{}` in closure",
-                                nl));
-                }
-                _ => { }
-            }
+            self.note_closure_capture(new_loan, &nl);
 
             let rule_summary = match old_loan.kind {
                 ty::MutBorrow => {
@@ -557,8 +577,6 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
                 old_loan.span,
                 &format!("{}; {}", borrow_summary, rule_summary));
 
-            let old_loan_span = self.tcx().map.span(
-                old_loan.kill_scope.node_id(&self.tcx().region_maps));
             self.bccx.span_end_note(old_loan_span,
                                     "previous borrow ends here");
 
@@ -568,6 +586,18 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
         true
     }
 
+    fn note_closure_capture(&self, new_loan: &Loan, nl: &str) {
+        match new_loan.cause {
+            euv::ClosureCapture(span) => {
+                self.bccx.span_note(
+                    span,
+                    &format!("borrow occurs due to use of `{}` in closure",
+                            nl));
+            }
+            _ => { }
+        }
+    }
+
     fn consume_common(&self,
                       id: ast::NodeId,
                       span: Span,
@@ -640,23 +670,34 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
             UseOk => { }
             UseWhileBorrowed(loan_path, loan_span) => {
                 match move_kind {
-                    move_data::Captured =>
+                    move_data::Captured => {
                         span_err!(self.bccx, span, E0504,
                                   "cannot move `{}` into closure because it is borrowed",
-                                  &self.bccx.loan_path_to_string(move_path)),
+                                  &self.bccx.loan_path_to_string(move_path));
+                        self.bccx.span_note(
+                            loan_span,
+                            &format!("borrow of `{}` occurs here",
+                                    &self.bccx.loan_path_to_string(&*loan_path))
+                            );
+                    }
                     move_data::Declared |
                     move_data::MoveExpr |
-                    move_data::MovePat =>
-                        span_err!(self.bccx, span, E0505,
-                                  "cannot move out of `{}` because it is borrowed",
-                                  &self.bccx.loan_path_to_string(move_path))
-                };
-
-                self.bccx.span_note(
-                    loan_span,
-                    &format!("borrow of `{}` occurs here",
-                            &self.bccx.loan_path_to_string(&*loan_path))
-                    );
+                    move_data::MovePat => {
+                        let move_path_str = self.bccx.loan_path_to_string(move_path);
+                        let spans = [
+                            SpanLabel::primary(span,
+                                               Some(format!("move out of `{}` occurs here",
+                                                            move_path_str))),
+                            SpanLabel::secondary(loan_span,
+                                                 Some(format!("borrow of `{}` occurs here",
+                                                              self.bccx.loan_path_to_string(
+                                                                  &*loan_path)))),
+                        ];
+                        labelled_span_err!(self.bccx, &spans, E0505,
+                                           "cannot move out of `{}` because it is borrowed",
+                                           move_path_str);
+                    }
+                }
             }
         }
     }
@@ -827,3 +868,9 @@ impl<'a, 'tcx> CheckLoanCtxt<'a, 'tcx> {
                     self.bccx.loan_path_to_string(loan_path)));
     }
 }
+
+/// The last character of `span`, such as the closing brace of a block.
+fn end_point(span: Span) -> Span {
+    let lo = if span.hi > span.lo { span.hi - BytePos(1) } else { span.hi };
+    Span { lo: lo, hi: span.hi, expn_id: span.expn_id }
+}
@@ -34,12 +34,14 @@ use rustc::middle::mem_categorization as mc;
 use rustc::middle::mem_categorization::Categorization;
 use rustc::middle::region;
 use rustc::middle::ty::{self, Ty};
+use rustc::util::profiling;
 
 use std::fmt;
 use std::mem;
 use std::rc::Rc;
 use syntax::ast::{self, NodeId};
 use syntax::codemap::Span;
+use syntax::diagnostic::SpanLabel;
 
 use rustc_front::hir;
 use rustc_front::hir::{FnDecl, Block};
@@ -159,27 +161,30 @@ fn borrowck_fn(this: &mut BorrowckCtxt,
                sp: Span,
                id: ast::NodeId) {
     debug!("borrowck_fn(id={})", id);
-    let cfg = cfg::CFG::new(this.tcx, body);
-    let AnalysisData { all_loans,
-                       loans: loan_dfcx,
-                       move_data: flowed_moves } =
-        build_borrowck_dataflow_data(this, fk, decl, &cfg, body, sp, id);
-
-    move_data::fragments::instrument_move_fragments(&flowed_moves.move_data,
-                                                    this.tcx,
-                                                    sp,
-                                                    id);
-    move_data::fragments::build_unfragmented_map(this,
-                                                 &flowed_moves.move_data,
-                                                 id);
-
-    check_loans::check_loans(this,
-                             &loan_dfcx,
-                             &flowed_moves,
-                             &all_loans[..],
-                             id,
-                             decl,
-                             body);
+    let tcx = this.tcx;
+    profiling::profile_item("borrowck", || tcx.map.path_to_string(id), || {
+        let cfg = cfg::CFG::new(this.tcx, body);
+        let AnalysisData { all_loans,
+                           loans: loan_dfcx,
+                           move_data: flowed_moves } =
+            build_borrowck_dataflow_data(this, fk, decl, &cfg, body, sp, id);
+
+        move_data::fragments::instrument_move_fragments(&flowed_moves.move_data,
+                                                        this.tcx,
+                                                        sp,
+                                                        id);
+        move_data::fragments::build_unfragmented_map(this,
+                                                     &flowed_moves.move_data,
+                                                     id);
+
+        check_loans::check_loans(this,
+                                 &loan_dfcx,
+                                 &flowed_moves,
+                                 &all_loans[..],
+                                 id,
+                                 decl,
+                                 body);
+    });
 
     visit::walk_fn(this, fk, decl, body, sp);
 }
@@ -808,6 +813,10 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
         self.tcx.sess.span_err_with_code(s, msg, code);
     }
 
+    pub fn labelled_span_err_with_code(&self, spans: &[SpanLabel], msg: &str, code: &str) {
+        self.tcx.sess.labelled_span_err_with_code(spans, msg, code);
+    }
+
     pub fn span_bug(&self, s: Span, m: &str) {
         self.tcx.sess.span_bug(s, m);
     }
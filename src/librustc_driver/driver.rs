@@ -25,7 +25,9 @@ use rustc::plugin::registry::Registry;
 use rustc::plugin;
 use rustc::util::nodemap::NodeMap;
 use rustc::util::common::time;
+use rustc::util::profiling;
 use rustc_borrowck as borrowck;
+use rustc_incremental;
 use rustc_resolve as resolve;
 use rustc_trans::back::link;
 use rustc_trans::back::write;
@@ -44,6 +46,7 @@ use std::ffi::{OsString, OsStr};
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::thread;
 use syntax::ast::{self, NodeIdAssigner};
 use syntax::attr;
 use syntax::attr::AttrMetaMethods;
@@ -63,6 +66,22 @@ pub fn compile_input(sess: Session,
                      output: &Option<PathBuf>,
                      addl_plugins: Option<Vec<String>>,
                      control: CompileController) {
+    compile_input_phases(&sess, cfg, input, outdir, output, addl_plugins, control);
+
+    // With `-Z apply-suggestions`, rewrite the source files now that
+    // compilation is over. If it is aborted because of errors, this
+    // happens in `Session::abort_if_errors` instead, and never after an
+    // internal compiler error.
+    sess.apply_suggestions();
+}
+
+fn compile_input_phases(sess: &Session,
+                        cfg: ast::CrateConfig,
+                        input: &Input,
+                        outdir: &Option<PathBuf>,
+                        output: &Option<PathBuf>,
+                        addl_plugins: Option<Vec<String>>,
+                        control: CompileController) {
     macro_rules! controller_entry_point{($point: ident, $tsess: expr, $make_state: expr) => ({
         let state = $make_state;
         (control.$point.callback)(state);
@@ -73,6 +92,11 @@ pub fn compile_input(sess: Session,
         }
     })}
 
+    // With `-Z self-profile`, record every pass from here on. The profile
+    // is named after the output files, so it is only written once those
+    // are known.
+    let mut self_profile = SelfProfile::new(&sess);
+
     // We need nested scopes here, because the intermediate results can keep
     // large chunks of memory alive and we want to free them as soon as
     // possible to keep the peak memory usage low
@@ -85,6 +109,7 @@ pub fn compile_input(sess: Session,
                                     CompileState::state_after_parse(input, &sess, outdir, &krate));
 
             let outputs = build_output_filenames(input, outdir, output, &krate.attrs, &sess);
+            self_profile.filestem = Some(outputs.filestem());
             let id = link::find_crate_name(Some(&sess), &krate.attrs, input);
             let expanded_crate = match phase_2_configure_and_expand(&sess,
                                                                     krate,
@@ -161,6 +186,12 @@ pub fn compile_input(sess: Session,
                                             }
                                         }
 
+                                        if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
+                                            time(tcx.sess.time_passes(),
+                                                 "writing MIR",
+                                                 || write_out_mir(tcx, &mir_map, &outputs));
+                                        }
+
                                         if log_enabled!(::log::INFO) {
                                             println!("Pre-trans");
                                             tcx.print_debug_stats();
@@ -169,6 +200,13 @@ pub fn compile_input(sess: Session,
                                                                               &mir_map,
                                                                               analysis);
 
+                                        // The dep-graph is only written once the
+                                        // outputs have been linked.
+                                        let dep_graph =
+                                            time(tcx.sess.time_passes(),
+                                                 "serialize dep graph",
+                                                 || rustc_incremental::encode_dep_graph(tcx));
+
                                         if log_enabled!(::log::INFO) {
                                             println!("Post-trans");
                                             tcx.print_debug_stats();
@@ -177,11 +215,11 @@ pub fn compile_input(sess: Session,
                                         // Discard interned strings as they are no longer required.
                                         token::get_ident_interner().clear();
 
-                                        Ok((outputs, trans))
+                                        Ok((outputs, trans, dep_graph))
                                     })
     };
 
-    let (outputs, trans) = if let Ok(out) = result {
+    let (outputs, trans, dep_graph) = if let Ok(out) = result {
         out
     } else {
         return;
@@ -194,6 +232,59 @@ pub fn compile_input(sess: Session,
                             CompileState::state_after_llvm(input, &sess, outdir, &trans));
 
     phase_6_link_output(&sess, &trans, &outputs);
+
+    // The object files saved with the dep-graph are those of the units
+    // which were rebuilt; the other ones were saved by an earlier session.
+    if let Some(dep_graph) = dep_graph {
+        if !sess.has_errors() {
+            let objects: Vec<_> = trans.incremental_units
+                                       .iter()
+                                       .filter(|unit| !unit.reuse)
+                                       .map(|unit| unit.name.clone())
+                                       .collect();
+            time(sess.time_passes(),
+                 "save dep graph",
+                 || rustc_incremental::save_dep_graph(&sess, dep_graph, &objects));
+        }
+    }
+}
+
+struct SelfProfile<'a> {
+    sess: &'a Session,
+    filestem: Option<String>,
+}
+
+impl<'a> SelfProfile<'a> {
+    fn new(sess: &'a Session) -> SelfProfile<'a> {
+        if sess.opts.debugging_opts.self_profile.is_some() {
+            profiling::start();
+        }
+        SelfProfile { sess: sess, filestem: None }
+    }
+}
+
+impl<'a> Drop for SelfProfile<'a> {
+    fn drop(&mut self) {
+        let dir = match self.sess.opts.debugging_opts.self_profile {
+            Some(ref dir) => PathBuf::from(dir),
+            None => return,
+        };
+        // A compilation aborted by errors leaves its passes unfinished.
+        let filestem = match self.filestem {
+            Some(ref filestem) if !thread::panicking() => filestem,
+            _ => return,
+        };
+        let format = match self.sess.opts.debugging_opts.self_profile_format {
+            Some(ref format) if format == "csv" => profiling::Format::Csv,
+            _ => profiling::Format::Json,
+        };
+        let path = dir.join(format!("{}.profile.{}", filestem, format.extension()));
+        let result = fs::create_dir_all(&dir).and_then(|()| profiling::finish(&path, format));
+        if let Err(e) = result {
+            self.sess.err(&format!("could not write self-profile to `{}`: {}",
+                                   path.display(), e));
+        }
+    }
 }
 
 /// The name used for source code that doesn't originate in a file
@@ -734,6 +825,9 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
                                lang_items,
                                stability::Index::new(krate),
                                |tcx| {
+                                   time(time_passes,
+                                        "load_dep_graph",
+                                        || rustc_incremental::load_dep_graph(tcx));
 
                                    // passes are timed inside typeck
                                    typeck::check_crate(tcx, trait_map);
@@ -811,6 +905,15 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
                                                                  &reachable_map)
                                    });
 
+                                   if let Some(ref crates) =
+                                          tcx.sess.opts.debugging_opts.unused_exports {
+                                       let crates: Vec<&str> =
+                                           crates.split(',').filter(|c| !c.is_empty()).collect();
+                                       time(time_passes, "unused exports checking", || {
+                                           middle::dead::check_unused_exports(tcx, &crates)
+                                       });
+                                   }
+
                                    let ref lib_features_used =
                                        time(time_passes,
                                             "stability checking",
@@ -961,6 +1064,27 @@ fn write_out_deps(sess: &Session, outputs: &OutputFilenames, id: &str) {
     }
 }
 
+fn write_out_mir(tcx: &ty::ctxt, mir_map: &MirMap, outputs: &OutputFilenames) {
+    let mir_filename = outputs.path(OutputType::Mir);
+
+    // Sort by node id, so that the fns appear in source order.
+    let mut mirs: Vec<_> = mir_map.iter().collect();
+    mirs.sort_by(|a, b| a.0.cmp(b.0));
+
+    let result = fs::File::create(&mir_filename).and_then(|mut file| {
+        mir::pretty::write_mir_pretty(tcx, mirs.into_iter(), &mut file)
+    });
+
+    match result {
+        Ok(()) => {}
+        Err(e) => {
+            tcx.sess.fatal(&format!("error writing MIR to `{}`: {}",
+                                    mir_filename.display(),
+                                    e));
+        }
+    }
+}
+
 pub fn collect_crate_types(session: &Session, attrs: &[ast::Attribute]) -> Vec<config::CrateType> {
     // Unconditionally collect crate types from attributes to make them used
     let attr_types: Vec<config::CrateType> =
@@ -20,12 +20,16 @@ use rustc_trans::back::link;
 use driver;
 
 use rustc::middle::ty;
+use rustc::middle::ty::adjustment::{AdjustReifyFnPointer, AdjustUnsafeFnPointer, AdjustDerefRef};
+use rustc::middle::ty::adjustment::{AutoPtr, AutoUnsafe};
 use rustc::middle::cfg;
 use rustc::middle::cfg::graphviz::LabelledCFG;
 use rustc::session::Session;
 use rustc::session::config::Input;
+use rustc::util::nodemap::NodeSet;
 use rustc_borrowck as borrowck;
 use rustc_borrowck::graphviz as borrowck_dot;
+use rustc_mir::pretty as mir_pretty;
 use rustc_resolve as resolve;
 
 use syntax::ast;
@@ -59,6 +63,7 @@ pub enum PpSourceMode {
     PpmExpandedIdentified,
     PpmExpandedHygiene,
     PpmTyped,
+    PpmTypedAnnotated,
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -75,6 +80,7 @@ pub enum PpMode {
     PpmSource(PpSourceMode),
     PpmHir(PpSourceMode),
     PpmFlowGraph(PpFlowGraphMode),
+    PpmMir,
 }
 
 pub fn parse_pretty(sess: &Session,
@@ -94,6 +100,8 @@ pub fn parse_pretty(sess: &Session,
         ("hir", true) => PpmHir(PpmNormal),
         ("hir,identified", true) => PpmHir(PpmIdentified),
         ("hir,typed", true) => PpmHir(PpmTyped),
+        ("hir,typed,annotated", true) => PpmHir(PpmTypedAnnotated),
+        ("mir", true) => PpmMir,
         ("flowgraph", true) => PpmFlowGraph(PpFlowGraphMode::Default),
         ("flowgraph,unlabelled", true) => PpmFlowGraph(PpFlowGraphMode::UnlabelledEdges),
         _ => {
@@ -101,7 +109,8 @@ pub fn parse_pretty(sess: &Session,
                 sess.fatal(&format!("argument to `unpretty` must be one of `normal`, \
                                      `expanded`, `flowgraph[,unlabelled]=<nodeid>`, \
                                      `identified`, `expanded,identified`, `everybody_loops`, \
-                                     `hir`, `hir,identified`, or `hir,typed`; got {}",
+                                     `hir`, `hir,identified`, `hir,typed`, \
+                                     `hir,typed,annotated`, or `mir`; got {}",
                                     name));
             } else {
                 sess.fatal(&format!("argument to `pretty` must be one of `normal`, `expanded`, \
@@ -191,7 +200,8 @@ impl PpSourceMode {
                 };
                 f(&annotation, payload, &ast_map.forest.krate)
             }
-            PpmTyped => {
+            PpmTyped | PpmTypedAnnotated => {
+                let verbose = *self == PpmTypedAnnotated;
                 driver::phase_3_run_analysis_passes(sess,
                                                     ast_map.clone(),
                                                     arenas,
@@ -200,6 +210,7 @@ impl PpSourceMode {
                                                     |tcx, _, _| {
                                                         let annotation = TypedAnnotation {
                                                             tcx: tcx,
+                                                            verbose: verbose,
                                                         };
                                                         f(&annotation,
                                                           payload,
@@ -423,6 +434,10 @@ impl<'ast> pprust::PpAnn for HygieneAnnotation<'ast> {
 
 struct TypedAnnotation<'a, 'tcx: 'a> {
     tcx: &'a ty::ctxt<'tcx>,
+    /// Whether to also print, in comments, the adjustments, method callees
+    /// and closure kinds recorded by type-checking, and the types of
+    /// patterns.
+    verbose: bool,
 }
 
 impl<'b, 'tcx> HirPrinterSupport<'tcx> for TypedAnnotation<'b, 'tcx> {
@@ -453,13 +468,96 @@ impl<'a, 'tcx> pprust_hir::PpAnn for TypedAnnotation<'a, 'tcx> {
                 try!(pp::word(&mut s.s, "as"));
                 try!(pp::space(&mut s.s));
                 try!(pp::word(&mut s.s, &self.tcx.expr_ty(expr).to_string()));
+                if self.verbose {
+                    for comment in self.expr_annotations(expr) {
+                        try!(pp::space(&mut s.s));
+                        try!(s.synth_comment(comment));
+                    }
+                }
                 s.pclose()
             }
+            pprust_hir::NodePat(pat) if self.verbose => {
+                match self.tcx.tables.borrow().node_types.get(&pat.id) {
+                    Some(ty) => {
+                        try!(pp::space(&mut s.s));
+                        s.synth_comment(format!("pat: {}", ty))
+                    }
+                    None => Ok(()),
+                }
+            }
             _ => Ok(()),
         }
     }
 }
 
+impl<'a, 'tcx> TypedAnnotation<'a, 'tcx> {
+    /// Describes what type-checking recorded about `expr` besides its type.
+    fn expr_annotations(&self, expr: &hir::Expr) -> Vec<String> {
+        let tables = self.tcx.tables.borrow();
+        let mut comments = vec![];
+
+        if let Some(callee) = tables.method_map.get(&ty::MethodCall::expr(expr.id)) {
+            comments.push(format!("callee: {} as {}",
+                                  self.tcx.item_path_str(callee.def_id),
+                                  callee.ty));
+        }
+
+        if let Some(adjustment) = tables.adjustments.get(&expr.id) {
+            let steps = match *adjustment {
+                AdjustReifyFnPointer => vec!["reify fn pointer".to_string()],
+                AdjustUnsafeFnPointer => vec!["unsafe fn pointer".to_string()],
+                AdjustDerefRef(ref adj) => {
+                    let mut steps = vec![];
+                    for i in 0..adj.autoderefs {
+                        let method_call = ty::MethodCall::autoderef(expr.id, i as u32);
+                        match tables.method_map.get(&method_call) {
+                            Some(callee) => {
+                                steps.push(format!("overloaded deref via {}",
+                                                   self.tcx.item_path_str(callee.def_id)));
+                            }
+                            None => steps.push("deref".to_string()),
+                        }
+                    }
+                    match adj.autoref {
+                        Some(AutoPtr(_, hir::MutImmutable)) => steps.push("borrow &".to_string()),
+                        Some(AutoPtr(_, hir::MutMutable)) => {
+                            steps.push("borrow &mut".to_string())
+                        }
+                        Some(AutoUnsafe(hir::MutImmutable)) => {
+                            steps.push("cast to *const".to_string())
+                        }
+                        Some(AutoUnsafe(hir::MutMutable)) => steps.push("cast to *mut".to_string()),
+                        None => {}
+                    }
+                    if let Some(target) = adj.unsize {
+                        steps.push(format!("unsize to {}", target));
+                    }
+                    steps
+                }
+            };
+            if !steps.is_empty() {
+                comments.push(format!("adjusted: {} => {}",
+                                      steps.join(", "),
+                                      self.tcx.expr_ty_adjusted(expr)));
+            }
+        }
+
+        if let hir::ExprClosure(..) = expr.node {
+            let def_id = self.tcx.map.local_def_id(expr.id);
+            if let Some(kind) = tables.closure_kinds.get(&def_id) {
+                let kind = match *kind {
+                    ty::FnClosureKind => "Fn",
+                    ty::FnMutClosureKind => "FnMut",
+                    ty::FnOnceClosureKind => "FnOnce",
+                };
+                comments.push(format!("closure kind: {}", kind));
+            }
+        }
+
+        comments
+    }
+}
+
 fn gather_flowgraph_variants(sess: &Session) -> Vec<borrowck_dot::Variant> {
     let print_loans = sess.opts.debugging_opts.flowgraph_print_loans;
     let print_moves = sess.opts.debugging_opts.flowgraph_print_moves;
@@ -564,8 +662,10 @@ fn needs_ast_map(ppm: &PpMode, opt_uii: &Option<UserIdentifiedItem>) -> bool {
         PpmSource(PpmExpandedIdentified) |
         PpmSource(PpmExpandedHygiene) |
         PpmHir(_) |
+        PpmMir |
         PpmFlowGraph(_) => true,
-        PpmSource(PpmTyped) => panic!("invalid state"),
+        PpmSource(PpmTyped) |
+        PpmSource(PpmTypedAnnotated) => panic!("invalid state"),
     }
 }
 
@@ -579,8 +679,10 @@ fn needs_expansion(ppm: &PpMode) -> bool {
         PpmSource(PpmExpandedIdentified) |
         PpmSource(PpmExpandedHygiene) |
         PpmHir(_) |
+        PpmMir |
         PpmFlowGraph(_) => true,
-        PpmSource(PpmTyped) => panic!("invalid state"),
+        PpmSource(PpmTyped) |
+        PpmSource(PpmTypedAnnotated) => panic!("invalid state"),
     }
 }
 
@@ -789,6 +891,26 @@ pub fn pretty_print_input(sess: Session,
             })
         }
 
+        (PpmMir, opt_uii) => {
+            let out: &mut Write = &mut out;
+            driver::phase_3_run_analysis_passes(&sess,
+                                                ast_map.unwrap(),
+                                                &arenas,
+                                                &id,
+                                                resolve::MakeGlobMap::No,
+                                                |tcx, mir_map, _| {
+                // Print the fns in source order, or only those selected.
+                let selected: Option<NodeSet> = opt_uii.map(|uii| {
+                    uii.all_matching_node_ids(&tcx.map).collect()
+                });
+                let mut mirs: Vec<_> = mir_map.iter().filter(|&(id, _)| {
+                    selected.as_ref().map_or(true, |selected| selected.contains(id))
+                }).collect();
+                mirs.sort_by(|a, b| a.0.cmp(b.0));
+                mir_pretty::write_mir_pretty(tcx, mirs.into_iter(), out)
+            })
+        }
+
         (PpmFlowGraph(mode), opt_uii) => {
             debug!("pretty printing flow graph for {:?}", opt_uii);
             let uii = opt_uii.unwrap_or_else(|| {
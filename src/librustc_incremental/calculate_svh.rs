@@ -0,0 +1,52 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Calculation of the fingerprint of a single item, used to decide
+//! whether it changed between two sessions.
+
+use std::hash::{SipHasher, Hasher};
+use rustc::front::map as ast_map;
+use rustc::middle::def_id::DefId;
+use rustc::middle::ty;
+use rustc_back::svh::svh_visitor;
+use rustc_front::visit;
+
+/// Hashes the HIR of the local item `def_id`, ignoring spans and node
+/// ids, so that the hash only changes when the item itself does.
+///
+/// Definitions which are not items (type parameters, fields and the
+/// like) all get the same hash; they are covered by the hash of the
+/// item which contains them.
+pub fn calculate_item_hash(tcx: &ty::ctxt, def_id: DefId) -> u64 {
+    assert!(def_id.is_local());
+
+    let mut state = SipHasher::new();
+
+    {
+        let mut visit = svh_visitor::make(&mut state);
+        match tcx.map.get_if_local(def_id) {
+            Some(ast_map::NodeItem(item)) => {
+                visit::walk_item(&mut visit, item);
+            }
+            Some(ast_map::NodeForeignItem(item)) => {
+                visit::walk_foreign_item(&mut visit, item);
+            }
+            Some(ast_map::NodeTraitItem(item)) => {
+                visit::walk_trait_item(&mut visit, item);
+            }
+            Some(ast_map::NodeImplItem(item)) => {
+                visit::walk_impl_item(&mut visit, item);
+            }
+            _ => {}
+        }
+    }
+
+    state.finish()
+}
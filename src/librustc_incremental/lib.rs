@@ -0,0 +1,53 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for serializing the dep-graph and reloading it.
+//!
+//! Of the work of a session, only translation is reused so far: the
+//! codegen units into which only clean items go keep the object files
+//! saved by the previous session, and their items are not translated
+//! again. Type checking, and the passes which read its tables, still run
+//! for every item.
+//!
+//! # Note
+//!
+//! This API is completely unstable and subject to change.
+
+// Do not remove on snapshot creation. Needed for bootstrap. (Issue #22364)
+#![cfg_attr(stage0, feature(custom_attribute))]
+#![crate_name = "rustc_incremental"]
+#![unstable(feature = "rustc_private", issue = "27812")]
+#![staged_api]
+#![crate_type = "dylib"]
+#![crate_type = "rlib"]
+#![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk-v2.png",
+      html_favicon_url = "https://doc.rust-lang.org/favicon.ico",
+      html_root_url = "https://doc.rust-lang.org/nightly/")]
+
+#![feature(rustc_private)]
+#![feature(staged_api)]
+
+extern crate rustc;
+extern crate rustc_back;
+extern crate rustc_data_structures;
+extern crate rustc_front;
+extern crate serialize;
+extern crate serialize as rustc_serialize; // used by deriving
+
+#[macro_use] extern crate log;
+extern crate syntax;
+
+mod calculate_svh;
+mod persist;
+
+pub use calculate_svh::calculate_item_hash;
+pub use persist::load_dep_graph;
+pub use persist::{EncodedDepGraph, encode_dep_graph, save_dep_graph};
+pub use persist::{saved_object_path, staged_object_path};
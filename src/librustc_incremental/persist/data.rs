@@ -0,0 +1,67 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The data that we will serialize and deserialize.
+
+use rustc::dep_graph::DepNode;
+
+use super::directory::{DefIdDirectory, DefPathIndex};
+
+/// Everything saved between two sessions.
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct SerializedDepGraphFile {
+    /// The crates this one was compiled against, with their strict
+    /// version hashes. If any of them changed, the whole graph is
+    /// discarded.
+    pub upstream: Vec<(String, String)>,
+
+    /// The command line of the session. If it changed, the whole graph
+    /// is discarded as well.
+    pub command_line: Vec<String>,
+
+    /// A hash of the definitions of the crate, see `definitions_hash`.
+    pub definitions: u64,
+
+    /// The def-paths of all definitions mentioned in `graph`.
+    pub directory: DefIdDirectory,
+
+    pub graph: SerializedDepGraph,
+}
+
+/// Data for use when recompiling the **current crate**.
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct SerializedDepGraph {
+    pub nodes: Vec<DepNode<DefPathIndex>>,
+    pub edges: Vec<SerializedEdge>,
+
+    /// These are hashes of two things:
+    /// - the HIR nodes in this crate
+    /// - the metadata nodes from dependent crates we use
+    ///
+    /// In each case, we store a hash summarizing the contents of
+    /// those items as they were at the time we did this compilation.
+    /// In the case of HIR nodes, this hash is derived by walking the
+    /// HIR itself.
+    pub hashes: Vec<SerializedHash>,
+}
+
+/// Represents a "reduced" dependency edge. Unlike the full dep-graph,
+/// the dep-graph we serialize contains only edges `S -> T` where the
+/// source `S` is something hashable (a HIR node or foreign metadata)
+/// and the target `T` is something significant, like a work-product.
+pub type SerializedEdge = (DepNode<DefPathIndex>, DepNode<DefPathIndex>);
+
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct SerializedHash {
+    pub index: DefPathIndex,
+
+    /// the hash itself, computed by `calculate_item_hash`
+    pub hash: u64,
+}
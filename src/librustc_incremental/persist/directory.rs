@@ -0,0 +1,101 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code to convert a DefId into a DefPath (when serializing) and then
+//! back again (when deserializing). Note that the new DefId
+//! necessarily will not be the same as the old (and of course the
+//! item might even be removed in the meantime).
+
+use rustc::front::map::DefPath;
+use rustc::middle::def_id::{DefId, DefIndex};
+use rustc::middle::ty;
+use rustc_data_structures::fnv::FnvHashMap;
+
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct DefIdDirectory {
+    // N.B. don't use Removable here because these def-ids are loaded
+    // directly without remapping, so loading them should not fail.
+    paths: Vec<DefPath>
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+pub struct DefPathIndex {
+    index: u32
+}
+
+impl DefIdDirectory {
+    pub fn new() -> DefIdDirectory {
+        DefIdDirectory { paths: vec![] }
+    }
+
+    /// Maps every path in the directory to the local definition with
+    /// that path in the current session, if there still is one.
+    pub fn retrace(&self, tcx: &ty::ctxt) -> RetracedDefIdDirectory {
+        let mut current = FnvHashMap();
+        for index in 0..tcx.map.num_local_def_ids() {
+            let def_id = DefId::local(DefIndex::new(index));
+            current.insert(tcx.map.def_path(def_id), def_id);
+        }
+
+        let ids = self.paths.iter()
+                            .map(|path| current.get(path).cloned())
+                            .collect();
+        RetracedDefIdDirectory { ids: ids }
+    }
+}
+
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct RetracedDefIdDirectory {
+    ids: Vec<Option<DefId>>
+}
+
+impl RetracedDefIdDirectory {
+    pub fn def_id(&self, index: DefPathIndex) -> Option<DefId> {
+        self.ids[index.index as usize]
+    }
+}
+
+pub struct DefIdDirectoryBuilder<'a,'tcx:'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    hash: FnvHashMap<DefId, DefPathIndex>,
+    directory: DefIdDirectory,
+}
+
+impl<'a,'tcx> DefIdDirectoryBuilder<'a,'tcx> {
+    pub fn new(tcx: &'a ty::ctxt<'tcx>) -> DefIdDirectoryBuilder<'a, 'tcx> {
+        DefIdDirectoryBuilder {
+            tcx: tcx,
+            hash: FnvHashMap(),
+            directory: DefIdDirectory::new()
+        }
+    }
+
+    /// Returns the index of `def_id` in the directory, adding it if
+    /// needed. Only local definitions can be added, since those are
+    /// the only ones whose paths are known to be stable.
+    pub fn add(&mut self, def_id: DefId) -> Option<DefPathIndex> {
+        if !def_id.is_local() {
+            return None;
+        }
+
+        let tcx = self.tcx;
+        let paths = &mut self.directory.paths;
+        Some(*self.hash.entry(def_id).or_insert_with(|| {
+            let def_path = tcx.map.def_path(def_id);
+            let index = paths.len() as u32;
+            paths.push(def_path);
+            DefPathIndex { index: index }
+        }))
+    }
+
+    pub fn into_directory(self) -> DefIdDirectory {
+        self.directory
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//! Code to load the dep-graph from files.
+
+use rustc::dep_graph::DepNode;
+use rustc::middle::ty;
+use rustc_serialize::json;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+
+use calculate_svh::calculate_item_hash;
+use super::data::*;
+use super::directory::*;
+use super::util::*;
+
+type DirtyNodes = HashSet<DepNode<DefPathIndex>>;
+
+/// Loads the dep-graph saved by the previous session, if any, and
+/// marks every node none of whose inputs changed since as clean in
+/// `tcx.dep_graph`.
+///
+/// A missing graph is not an error: it simply means that nothing is
+/// clean. A graph which cannot be read is reported as a warning and
+/// otherwise ignored.
+pub fn load_dep_graph(tcx: &ty::ctxt) {
+    let _ignore = tcx.dep_graph.in_ignore();
+
+    let path = match dep_graph_path(tcx) {
+        Some(path) => path,
+        None => return,
+    };
+
+    if !path.exists() {
+        return;
+    }
+
+    let mut data = String::new();
+    let result = File::open(&path).and_then(|mut f| f.read_to_string(&mut data));
+    if let Err(err) = result {
+        tcx.sess.warn(
+            &format!("could not load dep-graph from `{}`: {}",
+                     path.display(), err));
+        return;
+    }
+
+    let file: SerializedDepGraphFile = match json::decode(&data) {
+        Ok(file) => file,
+        Err(err) => {
+            tcx.sess.warn(
+                &format!("could not decode dep-graph from `{}`: {:?}",
+                         path.display(), err));
+            return;
+        }
+    };
+
+    decode_dep_graph(tcx, file);
+}
+
+fn decode_dep_graph(tcx: &ty::ctxt, file: SerializedDepGraphFile) {
+    // If any of the crates we link against changed, their metadata
+    // may have changed in ways we do not track, so nothing is clean.
+    if file.upstream != upstream_crates(tcx) {
+        debug!("decode_dep_graph: upstream crates changed");
+        return;
+    }
+
+    // Likewise, nothing is clean if the compiler options changed, or if
+    // definitions were added or removed.
+    if file.command_line != command_line() {
+        debug!("decode_dep_graph: command line changed");
+        return;
+    }
+    if file.definitions != definitions_hash(tcx) {
+        debug!("decode_dep_graph: definitions changed");
+        return;
+    }
+
+    let directory = file.directory.retrace(tcx);
+    let graph = file.graph;
+
+    let dirty = initial_dirty_nodes(tcx, &graph, &directory);
+    let dirty = transitive_dirty_nodes(&graph.edges, dirty);
+
+    // Clean nodes keep what they read, so that their results can be
+    // reused without losing their edges.
+    let mut inputs = HashMap::new();
+    for &(ref source, ref target) in &graph.edges {
+        inputs.entry(target.clone()).or_insert(vec![]).push(source.clone());
+    }
+
+    let retrace = |node: &DepNode<DefPathIndex>| node.map_def(|&index| directory.def_id(index));
+    let clean: Vec<_> =
+        graph.nodes
+             .iter()
+             .filter(|&node| !dirty.contains(node))
+             .filter_map(|node| {
+                 let sources = match inputs.get(node) {
+                     Some(sources) => sources.iter().filter_map(&retrace).collect(),
+                     None => vec![],
+                 };
+                 retrace(node).map(|node| (node, sources))
+             })
+             .collect();
+
+    debug!("decode_dep_graph: {} of {} nodes clean", clean.len(), graph.nodes.len());
+
+    tcx.dep_graph.mark_clean(clean);
+}
+
+/// The HIR nodes which no longer exist or whose hash changed.
+fn initial_dirty_nodes(tcx: &ty::ctxt,
+                       graph: &SerializedDepGraph,
+                       directory: &RetracedDefIdDirectory)
+                       -> DirtyNodes {
+    let mut dirty_nodes = HashSet::new();
+    for hash in &graph.hashes {
+        let changed = match directory.def_id(hash.index) {
+            Some(def_id) => calculate_item_hash(tcx, def_id) != hash.hash,
+            None => true,
+        };
+        if changed {
+            debug!("initial_dirty_nodes: {:?} changed", hash.index);
+            dirty_nodes.insert(DepNode::Hir(hash.index));
+        }
+    }
+
+    // Anything which read the crate as a whole has to be redone if
+    // any part of it changed.
+    if !dirty_nodes.is_empty() {
+        dirty_nodes.insert(DepNode::Krate);
+    }
+
+    dirty_nodes
+}
+
+/// Extends `dirty_nodes` with everything reachable from it.
+fn transitive_dirty_nodes(edges: &[SerializedEdge],
+                          mut dirty_nodes: DirtyNodes)
+                          -> DirtyNodes {
+    let mut len = 0;
+    while len != dirty_nodes.len() {
+        len = dirty_nodes.len();
+        for &(ref source, ref target) in edges {
+            if dirty_nodes.contains(source) {
+                dirty_nodes.insert(target.clone());
+            }
+        }
+    }
+    dirty_nodes
+}
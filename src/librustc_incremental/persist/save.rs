@@ -0,0 +1,139 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+use rustc::dep_graph::DepNode;
+use rustc::middle::ty;
+use rustc::session::Session;
+use rustc_serialize::json;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use calculate_svh::calculate_item_hash;
+use super::data::*;
+use super::directory::*;
+use super::util::*;
+
+/// The dep-graph built during this session, together with the hashes
+/// of the HIR nodes it mentions, encoded but not written yet.
+pub struct EncodedDepGraph {
+    path: PathBuf,
+    data: String,
+}
+
+/// Encodes the dep-graph built during this session. This has to happen
+/// while `tcx` is still alive, but the result is only written by
+/// `save_dep_graph` once the session produced its outputs.
+pub fn encode_dep_graph(tcx: &ty::ctxt) -> Option<EncodedDepGraph> {
+    let _ignore = tcx.dep_graph.in_ignore();
+
+    let path = match dep_graph_path(tcx) {
+        Some(path) => path,
+        None => return None,
+    };
+
+    let file = serialize_dep_graph(tcx);
+    match json::encode(&file) {
+        Ok(data) => Some(EncodedDepGraph { path: path, data: data }),
+        Err(err) => {
+            tcx.sess.err(
+                &format!("could not encode dep-graph: {:?}", err));
+            None
+        }
+    }
+}
+
+/// Writes the dep-graph of this session to the incremental directory,
+/// and moves the object files of the codegen units named in `objects`
+/// next to it, replacing those of the previous session. This must only
+/// be called once the outputs of the session have been linked.
+pub fn save_dep_graph(sess: &Session, graph: EncodedDepGraph, objects: &[String]) {
+    if let Err(err) = write_dep_graph(sess, &graph, objects) {
+        sess.err(
+            &format!("could not write dep-graph to `{}`: {}",
+                     graph.path.display(), err));
+    }
+}
+
+fn write_dep_graph(sess: &Session, graph: &EncodedDepGraph, objects: &[String])
+                   -> io::Result<()> {
+    // The graph tells which of the saved objects are still valid, so
+    // the old one is removed before any object is replaced: a session
+    // interrupted half-way leaves no graph rather than a wrong one.
+    if graph.path.exists() {
+        try!(fs::remove_file(&graph.path));
+    }
+
+    for name in objects {
+        if let (Some(staged), Some(saved)) = (staged_object_path(sess, name),
+                                              saved_object_path(sess, name)) {
+            try!(fs::rename(&staged, &saved));
+        }
+    }
+
+    let mut file = try!(File::create(&graph.path));
+    file.write_all(graph.data.as_bytes())
+}
+
+fn serialize_dep_graph(tcx: &ty::ctxt) -> SerializedDepGraphFile {
+    let query = tcx.dep_graph.query();
+    let mut builder = DefIdDirectoryBuilder::new(tcx);
+
+    // Nodes about definitions of other crates are dropped, together
+    // with their edges; changes to other crates are detected by
+    // comparing their crate hashes instead.
+    let nodes: Vec<_> =
+        query.nodes()
+             .into_iter()
+             .filter_map(|node| node.map_def(|&def_id| builder.add(def_id)))
+             .collect();
+
+    let edges: Vec<_> =
+        query.edges()
+             .into_iter()
+             .filter_map(|(source, target)| {
+                 let source = source.map_def(|&def_id| builder.add(def_id));
+                 let target = target.map_def(|&def_id| builder.add(def_id));
+                 match (source, target) {
+                     (Some(source), Some(target)) => Some((source, target)),
+                     _ => None,
+                 }
+             })
+             .collect();
+
+    let hashes: Vec<_> =
+        query.nodes()
+             .into_iter()
+             .filter_map(|node| match node {
+                 DepNode::Hir(def_id) if def_id.is_local() => {
+                     let hash = calculate_item_hash(tcx, def_id);
+                     builder.add(def_id).map(|index| SerializedHash {
+                         index: index,
+                         hash: hash,
+                     })
+                 }
+                 _ => None,
+             })
+             .collect();
+
+    debug!("serialize_dep_graph: {} nodes, {} edges, {} hashes",
+           nodes.len(), edges.len(), hashes.len());
+
+    SerializedDepGraphFile {
+        upstream: upstream_crates(tcx),
+        command_line: command_line(),
+        definitions: definitions_hash(tcx),
+        directory: builder.into_directory(),
+        graph: SerializedDepGraph {
+            nodes: nodes,
+            edges: edges,
+            hashes: hashes,
+        },
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rustc::front::map::DefPathData;
+use rustc::middle::def_id::{DefId, DefIndex};
+use rustc::middle::ty;
+use rustc::session::Session;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher, SipHasher};
+use std::path::PathBuf;
+
+/// The file holding the dep-graph of the previous session, if
+/// incremental compilation is enabled. The directory is created if
+/// it does not exist yet.
+pub fn dep_graph_path(tcx: &ty::ctxt) -> Option<PathBuf> {
+    // For now, just save/load dep-graph from
+    // directory/dep_graph.json
+    incremental_dir(&tcx.sess).map(|dir| dir.join("dep_graph.json"))
+}
+
+/// The object file of the codegen unit `name` saved by the previous
+/// session, if incremental compilation is enabled.
+pub fn saved_object_path(sess: &Session, name: &str) -> Option<PathBuf> {
+    incremental_dir(sess).map(|dir| dir.join(format!("{}.o", name)))
+}
+
+/// Where the object file of the codegen unit `name` is kept until the
+/// outputs of the current session have been linked. Only then does it
+/// replace the saved one.
+pub fn staged_object_path(sess: &Session, name: &str) -> Option<PathBuf> {
+    incremental_dir(sess).map(|dir| dir.join(format!("{}.o.new", name)))
+}
+
+fn incremental_dir(sess: &Session) -> Option<PathBuf> {
+    let incremental_dir = match sess.opts.debugging_opts.incremental {
+        Some(ref dir) => PathBuf::from(dir),
+        None => return None,
+    };
+
+    if let Err(err) = fs::create_dir_all(&incremental_dir) {
+        sess.err(
+            &format!("could not create the directory `{}`: {}",
+                     incremental_dir.display(), err));
+        return None;
+    }
+
+    Some(incremental_dir)
+}
+
+/// The crates the current one links against, with their strict
+/// version hashes, sorted by name.
+pub fn upstream_crates(tcx: &ty::ctxt) -> Vec<(String, String)> {
+    let mut crates = vec![];
+    tcx.sess.cstore.iter_crate_data(|cnum, data| {
+        let hash = tcx.sess.cstore.get_crate_hash(cnum);
+        crates.push((data.name.clone(), hash.as_str().to_string()));
+    });
+    crates.sort();
+    crates
+}
+
+/// The command line of the current session. The objects saved by a
+/// session compiled with different options cannot be reused.
+pub fn command_line() -> Vec<String> {
+    env::args().collect()
+}
+
+/// A hash of the definitions of the current crate, in the order of
+/// their indices. Symbol names depend on those indices, so the objects
+/// saved by a session cannot be reused if any definition moved.
+pub fn definitions_hash(tcx: &ty::ctxt) -> u64 {
+    let mut state = SipHasher::new();
+    for index in 0..tcx.map.num_local_def_ids() {
+        let key = tcx.map.def_key(DefId::local(DefIndex::new(index)));
+
+        // Items inlined from other crates come last, and only once
+        // translation started.
+        if let DefPathData::InlinedRoot(..) = key.disambiguated_data.data {
+            break;
+        }
+
+        key.parent.map(|parent| parent.as_usize()).hash(&mut state);
+        key.disambiguated_data.data.as_interned_str().hash(&mut state);
+        key.disambiguated_data.disambiguator.hash(&mut state);
+    }
+    state.finish()
+}
@@ -847,7 +847,7 @@ impl LateLintPass for UnconditionalRecursion {
                          "function cannot return without recurring");
 
             // FIXME #19668: these could be span_lint_note's instead of this manual guard.
-            if cx.current_level(UNCONDITIONAL_RECURSION) != Level::Allow {
+            if cx.current_level(UNCONDITIONAL_RECURSION, sp) != Level::Allow {
                 let sess = cx.sess();
                 // offer some help to the programmer.
                 for call in &self_call_spans {
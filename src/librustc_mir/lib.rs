@@ -34,6 +34,7 @@ pub mod mir_map;
 mod hair;
 pub mod repr;
 mod graphviz;
+pub mod pretty;
 pub mod tcx;
 pub mod visit;
 
@@ -15,6 +15,9 @@
 //!
 //! - `#[rustc_mir(graphviz="file.gv")]`
 //! - `#[rustc_mir(pretty="file.mir")]`
+//!
+//! The MIR of every fn is also handed to `pretty::dump_mir` under the
+//! pass name `mir_map`, so that it can be inspected with `-Z dump-mir`.
 
 extern crate syntax;
 extern crate rustc;
@@ -24,8 +27,10 @@ use build;
 use dot;
 use repr::Mir;
 use hair::cx::Cx;
+use pretty;
 use std::fs::File;
 
+use self::rustc::dep_graph::DepNode;
 use self::rustc::middle::infer;
 use self::rustc::middle::region::CodeExtentData;
 use self::rustc::middle::ty::{self, Ty};
@@ -60,9 +65,19 @@ struct OuterDump<'a, 'tcx: 'a> {
 }
 
 impl<'a, 'tcx> OuterDump<'a, 'tcx> {
-    fn visit_mir<OP>(&mut self, attributes: &'a [ast::Attribute], mut walk_op: OP)
+    /// Builds the MIR for the fn `id` and the closures within it. The
+    /// fn was type-checked as part of the item `typeck_id`.
+    fn visit_mir<OP>(&mut self,
+                     id: ast::NodeId,
+                     typeck_id: ast::NodeId,
+                     attributes: &'a [ast::Attribute],
+                     mut walk_op: OP)
         where OP: for<'m> FnMut(&mut InnerDump<'a, 'm, 'tcx>)
     {
+        let def_id = self.tcx.map.local_def_id(id);
+        let _task = self.tcx.dep_graph.in_task(DepNode::Mir(def_id));
+        self.tcx.dep_graph.read(DepNode::TypeckItemBody(self.tcx.map.local_def_id(typeck_id)));
+
         let mut closure_dump = InnerDump {
             tcx: self.tcx,
             attr: None,
@@ -80,14 +95,16 @@ impl<'a, 'tcx> OuterDump<'a, 'tcx> {
 
 impl<'a, 'tcx> visit::Visitor<'tcx> for OuterDump<'a, 'tcx> {
     fn visit_item(&mut self, item: &'tcx hir::Item) {
-        self.visit_mir(&item.attrs, |c| visit::walk_item(c, item));
+        self.visit_mir(item.id, item.id, &item.attrs, |c| visit::walk_item(c, item));
         visit::walk_item(self, item);
     }
 
     fn visit_trait_item(&mut self, trait_item: &'tcx hir::TraitItem) {
         match trait_item.node {
             hir::MethodTraitItem(_, Some(_)) => {
-                self.visit_mir(&trait_item.attrs, |c| visit::walk_trait_item(c, trait_item));
+                let parent = self.tcx.map.get_parent(trait_item.id);
+                self.visit_mir(trait_item.id, parent, &trait_item.attrs,
+                               |c| visit::walk_trait_item(c, trait_item));
             }
             hir::MethodTraitItem(_, None) |
             hir::ConstTraitItem(..) |
@@ -99,7 +116,9 @@ impl<'a, 'tcx> visit::Visitor<'tcx> for OuterDump<'a, 'tcx> {
     fn visit_impl_item(&mut self, impl_item: &'tcx hir::ImplItem) {
         match impl_item.node {
             hir::MethodImplItem(..) => {
-                self.visit_mir(&impl_item.attrs, |c| visit::walk_impl_item(c, impl_item));
+                let parent = self.tcx.map.get_parent(impl_item.id);
+                self.visit_mir(impl_item.id, parent, &impl_item.attrs,
+                               |c| visit::walk_impl_item(c, impl_item));
             }
             hir::ConstImplItem(..) | hir::TypeImplItem(..) => {}
         }
@@ -148,6 +167,8 @@ impl<'a, 'm, 'tcx> visit::Visitor<'tcx> for InnerDump<'a,'m,'tcx> {
 
         match build_mir(Cx::new(&infcx), implicit_arg_tys, id, span, decl, body) {
             Ok(mir) => {
+                pretty::dump_mir(self.tcx, "mir_map", &0, id, &mir);
+
                 let meta_item_list = self.attr
                                          .iter()
                                          .flat_map(|a| a.meta_item_list())
@@ -176,6 +197,31 @@ impl<'a, 'm, 'tcx> visit::Visitor<'tcx> for InnerDump<'a,'m,'tcx> {
                                     "graphviz attribute requires a path");
                             }
                         }
+                    } else if item.check_name("pretty") {
+                        match item.value_str() {
+                            Some(s) => {
+                                match
+                                    File::create(format!("{}{}", prefix, s))
+                                    .and_then(|ref mut output| {
+                                        pretty::write_mir_fn(self.tcx, id, &mir, output)
+                                    })
+                                {
+                                    Ok(()) => { }
+                                    Err(e) => {
+                                        self.tcx.sess.span_fatal(
+                                            item.span,
+                                            &format!("Error writing pretty \
+                                                      results to `{}`: {}",
+                                                     s, e));
+                                    }
+                                }
+                            }
+                            None => {
+                                self.tcx.sess.span_err(
+                                    item.span,
+                                    "pretty attribute requires a path");
+                            }
+                        }
                     }
                 }
 
@@ -0,0 +1,199 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A textual form of the MIR, used by `--emit=mir`, `-Z dump-mir` and
+//! the `#[rustc_mir(pretty="file.mir")]` attribute.
+//!
+//! Statements and terminators are printed in the same form as their
+//! `Debug` impls. Anything which varies from one build to the next,
+//! such as spans and scope extents, is printed in `//` comments, so
+//! that tests can ignore it.
+
+use repr::*;
+use rustc::front::map as ast_map;
+use rustc::middle::ty;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use syntax::ast::NodeId;
+
+const INDENT: &'static str = "    ";
+
+/// If the session was given `-Z dump-mir=<filter>` and `filter` selects
+/// the fn `node_id` and the pass `pass_name`, writes `mir` to the file
+/// `rustc.<fn path>.<pass_name>.<disambiguator>.mir` in the MIR dump
+/// directory. The `::` separators of the fn path are replaced by `-`,
+/// and closures are named after the fn containing them, followed by
+/// `closure-node<node_id>`.
+///
+/// The disambiguator distinguishes dumps made by the same pass, e.g.
+/// `before` and `after` it ran.
+pub fn dump_mir<'tcx>(tcx: &ty::ctxt<'tcx>,
+                      pass_name: &str,
+                      disambiguator: &Display,
+                      node_id: NodeId,
+                      mir: &Mir<'tcx>) {
+    let filters = match tcx.sess.opts.debugging_opts.dump_mir {
+        None => return,
+        Some(ref filters) => filters,
+    };
+    let node_path = tcx.map.path_to_string(node_id);
+    let is_matched = filters == "all" || filters.split('|').any(|filter| {
+        filter.split('&').all(|term| {
+            let term = term.trim();
+            node_path.contains(term) || pass_name.contains(term)
+        })
+    });
+    if !is_matched {
+        return;
+    }
+
+    let dump_dir = match tcx.sess.opts.debugging_opts.dump_mir_dir {
+        Some(ref dir) => PathBuf::from(dir),
+        None => PathBuf::from("mir_dump"),
+    };
+    let mut file_stem = node_path.replace("::", "-");
+    if let Some(ast_map::NodeExpr(..)) = tcx.map.find(node_id) {
+        file_stem.push_str(&format!(".closure-node{}", node_id));
+    }
+    let file_name = format!("rustc.{}.{}.{}.mir", file_stem, pass_name, disambiguator);
+    let path = dump_dir.join(file_name);
+
+    let result = fs::create_dir_all(&dump_dir).and_then(|()| {
+        let mut file = try!(fs::File::create(&path));
+        try!(writeln!(file, "// MIR for `{}`", node_path));
+        try!(writeln!(file, "// node_id = {}", node_id));
+        try!(writeln!(file, "// pass_name = {}", pass_name));
+        try!(writeln!(file, "// disambiguator = {}", disambiguator));
+        try!(writeln!(file, ""));
+        write_mir_fn(tcx, node_id, mir, &mut file)
+    });
+    if let Err(e) = result {
+        tcx.sess.err(&format!("could not dump MIR to `{}`: {}", path.display(), e));
+    }
+}
+
+/// Writes the MIR of every fn in `iter` to `w`, in the order given.
+pub fn write_mir_pretty<'a, 'tcx, I>(tcx: &ty::ctxt<'tcx>, iter: I, w: &mut Write)
+                                     -> io::Result<()>
+    where 'tcx: 'a, I: Iterator<Item=(&'a NodeId, &'a Mir<'tcx>)>
+{
+    let mut first = true;
+    for (&node_id, mir) in iter {
+        if !first {
+            try!(writeln!(w, ""));
+        }
+        first = false;
+        try!(writeln!(w, "// MIR for `{}`", tcx.map.path_to_string(node_id)));
+        try!(write_mir_fn(tcx, node_id, mir, w));
+    }
+    Ok(())
+}
+
+/// Writes the MIR of a single fn to `w`.
+pub fn write_mir_fn<'tcx>(tcx: &ty::ctxt<'tcx>,
+                          node_id: NodeId,
+                          mir: &Mir<'tcx>,
+                          w: &mut Write)
+                          -> io::Result<()> {
+    try!(write_mir_intro(tcx, node_id, mir, w));
+    try!(write_scopes(mir, w));
+    for block in mir.all_basic_blocks() {
+        try!(write_basic_block(tcx, block, mir, w));
+    }
+    writeln!(w, "}}")
+}
+
+/// Writes the signature of the fn and the declarations of its
+/// variables and temporaries.
+fn write_mir_intro<'tcx>(tcx: &ty::ctxt<'tcx>,
+                         node_id: NodeId,
+                         mir: &Mir<'tcx>,
+                         w: &mut Write)
+                         -> io::Result<()> {
+    let kind = match tcx.map.find(node_id) {
+        Some(ast_map::NodeExpr(..)) => "closure",
+        _ => "fn",
+    };
+    try!(write!(w, "{}(", kind));
+    for (index, arg) in mir.arg_decls.iter().enumerate() {
+        if index > 0 {
+            try!(write!(w, ", "));
+        }
+        try!(write!(w, "{:?}: {}", Lvalue::Arg(index as u32), arg.ty));
+    }
+    try!(write!(w, ") -> "));
+    match mir.return_ty {
+        ty::FnConverging(ty) => try!(write!(w, "{}", ty)),
+        ty::FnDiverging => try!(write!(w, "!")),
+    }
+    try!(writeln!(w, " {{"));
+
+    for (index, var) in mir.var_decls.iter().enumerate() {
+        let mut_str = if var.mutability == Mutability::Mut { "mut " } else { "" };
+        try!(writeln!(w, "{}let {}{:?}: {}; // {}",
+                      INDENT, mut_str, Lvalue::Var(index as u32), var.ty, var.name));
+    }
+    for (index, temp) in mir.temp_decls.iter().enumerate() {
+        try!(writeln!(w, "{}let mut {:?}: {};", INDENT, Lvalue::Temp(index as u32), temp.ty));
+    }
+    writeln!(w, "")
+}
+
+/// Writes the graph extent computed for every scope of the fn, as
+/// comments, in order of their code extents.
+fn write_scopes(mir: &Mir, w: &mut Write) -> io::Result<()> {
+    let mut extents: Vec<_> = mir.extents.iter().collect();
+    extents.sort_by(|a, b| a.0.cmp(b.0));
+    for (extent, graph_extents) in extents {
+        for graph_extent in graph_extents {
+            try!(write!(w, "{}// scope {:?}: {} -> ",
+                        INDENT, extent, execution_point(graph_extent.entry)));
+            match graph_extent.exit {
+                GraphExtentExit::Statement(statement) => {
+                    try!(writeln!(w, "{}", execution_point(ExecutionPoint {
+                        block: graph_extent.entry.block,
+                        statement: statement,
+                    })));
+                }
+                GraphExtentExit::Points(ref points) => {
+                    let points: Vec<_> = points.iter().map(|&p| execution_point(p)).collect();
+                    try!(writeln!(w, "{{ {} }}", points.join(", ")));
+                }
+            }
+        }
+    }
+    if !mir.extents.is_empty() {
+        try!(writeln!(w, ""));
+    }
+    Ok(())
+}
+
+fn execution_point(point: ExecutionPoint) -> String {
+    format!("{:?}[{}]", point.block, point.statement)
+}
+
+/// Writes a basic block, with the span of each of its statements.
+fn write_basic_block(tcx: &ty::ctxt,
+                     block: BasicBlock,
+                     mir: &Mir,
+                     w: &mut Write)
+                     -> io::Result<()> {
+    let data = mir.basic_block_data(block);
+
+    try!(writeln!(w, "{}{:?}: {{", INDENT, block));
+    for statement in &data.statements {
+        try!(writeln!(w, "{0}{0}{1:?}; // {2}",
+                      INDENT, statement, tcx.sess.codemap().span_to_string(statement.span)));
+    }
+    try!(writeln!(w, "{0}{0}{1:?};", INDENT, data.terminator));
+    writeln!(w, "{}}}", INDENT)
+}
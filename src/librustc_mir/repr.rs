@@ -12,12 +12,13 @@ use rustc::middle::const_eval::ConstVal;
 use rustc::middle::def_id::DefId;
 use rustc::middle::region::CodeExtent;
 use rustc::middle::subst::Substs;
-use rustc::middle::ty::{AdtDef, ClosureSubsts, FnOutput, Region, Ty};
+use rustc::middle::ty::{self, AdtDef, ClosureSubsts, FnOutput, Region, Ty};
 use rustc_back::slice;
 use rustc_data_structures::fnv::FnvHashMap;
 use rustc_front::hir::InlineAsm;
 use syntax::ast::Name;
 use syntax::codemap::Span;
+use std::ascii;
 use std::fmt::{Debug, Formatter, Error};
 use std::u32;
 
@@ -705,14 +706,14 @@ impl<'tcx> Debug for Rvalue<'tcx> {
 // this does not necessarily mean that they are "==" in Rust -- in
 // particular one must be wary of `NaN`!
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct Constant<'tcx> {
     pub span: Span,
     pub ty: Ty<'tcx>,
     pub literal: Literal<'tcx>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, PartialEq)]
 pub enum Literal<'tcx> {
     Item {
         def_id: DefId,
@@ -722,3 +723,49 @@ pub enum Literal<'tcx> {
         value: ConstVal,
     },
 }
+
+impl<'tcx> Debug for Constant<'tcx> {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
+        write!(fmt, "const {:?}", self.literal)
+    }
+}
+
+impl<'tcx> Debug for Literal<'tcx> {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
+        use self::Literal::*;
+        match *self {
+            Item { def_id, .. } => write_item_path(fmt, def_id),
+            Value { ref value } => fmt_const_val(fmt, value),
+        }
+    }
+}
+
+fn fmt_const_val(fmt: &mut Formatter, const_val: &ConstVal) -> Result<(), Error> {
+    use rustc::middle::const_eval::ConstVal::*;
+    match *const_val {
+        Float(f) => write!(fmt, "{:?}", f),
+        Int(n) => write!(fmt, "{:?}", n),
+        Uint(n) => write!(fmt, "{:?}", n),
+        Str(ref s) => write!(fmt, "{:?}", &s[..]),
+        ByteStr(ref bytes) => {
+            let escaped: String = bytes
+                .iter()
+                .flat_map(|&ch| ascii::escape_default(ch).map(|c| c as char))
+                .collect();
+            write!(fmt, "b\"{}\"", escaped)
+        }
+        Bool(b) => write!(fmt, "{:?}", b),
+        Struct(node_id) => write!(fmt, "Struct({:?})", node_id),
+        Tuple(node_id) => write!(fmt, "Tuple({:?})", node_id),
+        Function(def_id) => write_item_path(fmt, def_id),
+    }
+}
+
+/// Writes the path of `def_id` if a type context is available, and the
+/// `DefId` itself otherwise.
+fn write_item_path(fmt: &mut Formatter, def_id: DefId) -> Result<(), Error> {
+    match ty::tls::with_opt(|opt_tcx| opt_tcx.map(|tcx| tcx.item_path_str(def_id))) {
+        Some(path) => write!(fmt, "{}", path),
+        None => write!(fmt, "{:?}", def_id),
+    }
+}
@@ -3719,21 +3719,26 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                 );
                             } else {
                                 let last_name = path.segments.last().unwrap().identifier.name;
-                                let mut msg = match self.find_fallback_in_self_type(last_name) {
+                                let (mut msg, replacement) =
+                                    match self.find_fallback_in_self_type(last_name) {
                                     NoSuggestion => {
                                         // limit search to 5 to reduce the number
                                         // of stupid suggestions
-                                        self.find_best_match_for_name(&path_name)
-                                                            .map_or("".to_string(),
-                                                                    |x| format!("`{}`", x))
+                                        match self.find_best_match_for_name(&path_name) {
+                                            Some(x) => (format!("`{}`", x), Some(x)),
+                                            None => ("".to_string(), None),
+                                        }
                                     }
-                                    Field => format!("`self.{}`", path_name),
+                                    Field => (format!("`self.{}`", path_name),
+                                              Some(format!("self.{}", path_name))),
                                     Method |
                                     TraitItem =>
-                                        format!("to call `self.{}`", path_name),
+                                        (format!("to call `self.{}`", path_name),
+                                         Some(format!("self.{}", path_name))),
                                     TraitMethod(path_str) |
                                     StaticMethod(path_str) =>
-                                        format!("to call `{}::{}`", path_str, path_name)
+                                        (format!("to call `{}::{}`", path_str, path_name),
+                                         Some(format!("{}::{}", path_str, path_name)))
                                 };
 
                                 if !msg.is_empty() {
@@ -3744,6 +3749,16 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                               expr.span,
                                               ResolutionError::UnresolvedName(&*path_name,
                                                                                &*msg));
+
+                                // The same fix, in a form which can be applied
+                                // automatically.
+                                if let Some(replacement) = replacement {
+                                    if self.emit_errors {
+                                        self.session.span_suggestion(expr.span,
+                                                                     "try this:",
+                                                                     replacement);
+                                    }
+                                }
                             }
                         }
                     }
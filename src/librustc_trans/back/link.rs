@@ -14,6 +14,7 @@ use super::rpath::RPathConfig;
 use super::rpath;
 use super::msvc;
 use super::svh::Svh;
+use super::write;
 use session::config;
 use session::config::NoDebugInfo;
 use session::config::{OutputFilenames, Input, OutputType};
@@ -37,6 +38,7 @@ use std::char;
 use std::env;
 use std::ffi::OsString;
 use std::fs;
+use std::hash::{Hash, Hasher, SipHasher};
 use std::io::{self, Read, Write};
 use std::iter::once;
 use std::mem;
@@ -185,9 +187,22 @@ pub fn find_crate_name(sess: Option<&Session>,
 
 pub fn build_link_meta(sess: &Session, krate: &hir::Crate,
                        name: &str) -> LinkMeta {
+    // Symbol names and type ids are derived from the crate hash, so if
+    // it changed with every edit, no object file could ever be reused
+    // with `-Z incremental`. Only then is it not a hash of the sources,
+    // which is fine as long as no other crate links against this one.
+    let crate_hash = if sess.opts.debugging_opts.incremental.is_some() &&
+                        write::can_reuse_objects(sess) {
+        let mut state = SipHasher::new();
+        sess.opts.cg.metadata.hash(&mut state);
+        Svh::new(&format!("{:016x}", state.finish()))
+    } else {
+        Svh::calculate(&sess.opts.cg.metadata, krate)
+    };
+
     let r = LinkMeta {
         crate_name: name.to_owned(),
-        crate_hash: Svh::calculate(&sess.opts.cg.metadata, krate),
+        crate_hash: crate_hash,
     };
     info!("{:?}", r);
     return r;
@@ -333,6 +348,18 @@ pub fn mangle_exported_name<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, path: DefPat
                                       t: Ty<'tcx>, id: ast::NodeId) -> String {
     let mut hash = get_symbol_hash(ccx, t);
 
+    // With `-Z incremental`, node ids cannot be used below, because they
+    // change whenever an item is added in front of this one. The
+    // disambiguators of the def-path tell identical paths apart instead.
+    if ccx.incremental() {
+        for (index, elem) in path.iter().enumerate() {
+            if elem.disambiguator != 0 {
+                hash.push_str(&format!("{}d{}", index, elem.disambiguator));
+            }
+        }
+        return exported_name(path, &hash[..]);
+    }
+
     // Paths can be completely identical for different nodes,
     // e.g. `fn foo() { { fn a() {} } { fn a() {} } }`, so we
     // generate unique characters from the node id. For now
@@ -415,6 +442,12 @@ pub fn link_binary(sess: &Session,
                    trans: &CrateTranslation,
                    outputs: &OutputFilenames,
                    crate_name: &str) -> Vec<PathBuf> {
+    if sess.opts.prints.contains(&config::PrintRequest::NativeStaticLibs) &&
+       !sess.crate_types.borrow().contains(&config::CrateTypeStaticlib) {
+        sess.warn("skipping `--print native-static-libs`: \
+                   it only applies to the `staticlib` crate type");
+    }
+
     let mut out_filenames = Vec::new();
     for &crate_type in sess.crate_types.borrow().iter() {
         if invalid_output_for_target(sess, crate_type) {
@@ -817,6 +850,11 @@ fn link_staticlib(sess: &Session, objects: &[PathBuf], out_filename: &Path,
     ab.update_symbols();
     ab.build();
 
+    if sess.opts.prints.contains(&config::PrintRequest::NativeStaticLibs) {
+        print_native_static_libs(sess, &all_native_libs);
+        return;
+    }
+
     if !all_native_libs.is_empty() {
         sess.note("link against the following native artifacts when linking against \
                   this static library");
@@ -834,6 +872,32 @@ fn link_staticlib(sess: &Session, objects: &[PathBuf], out_filename: &Path,
     }
 }
 
+// Prints, for `--print native-static-libs`, the arguments which need to be
+// passed to the linker alongside a static library. The local crate's own
+// libraries come first, followed by those of its upstream crates. Static
+// native libraries are already bundled into the archive and are left out.
+fn print_native_static_libs(sess: &Session,
+                            upstream_libs: &[(cstore::NativeLibraryKind, String)]) {
+    let local_libs = sess.cstore.get_used_libraries().borrow().iter()
+                                .map(|&(ref lib, kind)| (kind, lib.clone()))
+                                .collect::<Vec<_>>();
+    let all_libs = local_libs.iter().chain(upstream_libs);
+    let lib_args: Vec<_> = all_libs.filter_map(|&(kind, ref lib)| {
+        match kind {
+            cstore::NativeStatic => None,
+            cstore::NativeUnknown => {
+                if sess.target.target.options.is_like_msvc {
+                    Some(format!("{}.lib", lib))
+                } else {
+                    Some(format!("-l{}", lib))
+                }
+            }
+            cstore::NativeFramework => Some(format!("-framework {}", lib)),
+        }
+    }).collect();
+    println!("{}", lib_args.join(" "));
+}
+
 // Create a dynamic library or executable
 //
 // This will invoke the system linker/cc to create the resulting file. This
@@ -16,7 +16,8 @@ use session::config::{self, OutputType};
 use llvm;
 use llvm::{ModuleRef, TargetMachineRef, PassManagerRef, DiagnosticInfoRef, ContextRef};
 use llvm::SMDiagnosticRef;
-use trans::{CrateTranslation, ModuleTranslation};
+use rustc_incremental;
+use trans::{CrateTranslation, IncrementalUnit, ModuleTranslation};
 use util::common::time;
 use util::common::path2cstr;
 use syntax::codemap;
@@ -562,6 +563,57 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
     llvm::LLVMRustDisposeTargetMachine(tm);
 }
 
+/// True if object files are all LLVM has to produce, so that with
+/// `-Z incremental` those saved by the previous session can be reused.
+pub fn can_reuse_objects(sess: &Session) -> bool {
+    let mut wants_objects = false;
+    for output_type in sess.opts.output_types.keys() {
+        match *output_type {
+            OutputType::Object | OutputType::Exe => wants_objects = true,
+            OutputType::DepInfo | OutputType::Mir => {}
+            OutputType::Bitcode | OutputType::LlvmAssembly | OutputType::Assembly => {
+                return false;
+            }
+        }
+    }
+
+    // Other crates cannot link against the result, see
+    // `link::build_link_meta`.
+    let crate_types = sess.crate_types.borrow();
+    let final_artifacts = crate_types.iter().all(|crate_type| {
+        *crate_type == config::CrateTypeExecutable ||
+        *crate_type == config::CrateTypeStaticlib
+    });
+
+    wants_objects &&
+        final_artifacts &&
+        !sess.lto() &&
+        !sess.opts.cg.save_temps &&
+        !sess.opts.cg.no_integrated_as
+}
+
+/// Copies the object file saved for `unit` by the previous session to
+/// `output`, instead of running LLVM on `mtrans`.
+fn reuse_object(sess: &Session,
+                mtrans: ModuleTranslation,
+                unit: &IncrementalUnit,
+                output: &Path) {
+    if sess.opts.debugging_opts.incremental_info {
+        println!("incremental: reusing the object file of unit `{}`", unit.name);
+    }
+
+    if let Some(saved) = rustc_incremental::saved_object_path(sess, &unit.name) {
+        if let Err(e) = fs::copy(&saved, output) {
+            sess.err(&format!("could not copy {:?} to {:?}: {}", saved, output, e));
+        }
+    }
+
+    unsafe {
+        llvm::LLVMDisposeModule(mtrans.llmod);
+        llvm::LLVMContextDispose(mtrans.llcx);
+    }
+}
+
 pub fn run_passes(sess: &Session,
                   trans: &CrateTranslation,
                   output_types: &HashMap<OutputType, Option<PathBuf>>,
@@ -629,7 +681,7 @@ pub fn run_passes(sess: &Session,
                 modules_config.emit_obj = true;
                 metadata_config.emit_obj = true;
             },
-            OutputType::DepInfo => {}
+            OutputType::DepInfo | OutputType::Mir => {}
         }
     }
 
@@ -652,6 +704,14 @@ pub fn run_passes(sess: &Session,
     }
 
     for (index, mtrans) in trans.modules.iter().enumerate() {
+        if let Some(unit) = trans.incremental_units.get(index) {
+            if unit.reuse {
+                let output = crate_output.with_extension(&format!("{}.o", index));
+                reuse_object(sess, *mtrans, unit, &output);
+                continue;
+            }
+        }
+
         let work = build_work_item(sess,
                                    *mtrans,
                                    modules_config.clone(),
@@ -679,6 +739,18 @@ pub fn run_passes(sess: &Session,
         }
     };
 
+    // Keep the object files of the units which were rebuilt for the next
+    // session. They only replace the saved ones once linking succeeded.
+    for (index, unit) in trans.incremental_units.iter().enumerate() {
+        if unit.reuse {
+            continue;
+        }
+        if let Some(staged) = rustc_incremental::staged_object_path(sess, &unit.name) {
+            let output = crate_output.with_extension(&format!("{}.o", index));
+            copy_gracefully(&output, &staged);
+        }
+    }
+
     let copy_if_one_unit = |ext: &str,
                             output_type: OutputType,
                             keep_numbered: bool| {
@@ -733,7 +805,8 @@ pub fn run_passes(sess: &Session,
                 copy_if_one_unit("0.o", OutputType::Object, true);
             }
             OutputType::Exe |
-            OutputType::DepInfo => {}
+            OutputType::DepInfo |
+            OutputType::Mir => {}
         }
     }
     let user_wants_bitcode = user_wants_bitcode;
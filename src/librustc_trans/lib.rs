@@ -53,6 +53,7 @@ extern crate rustc;
 extern crate rustc_back;
 extern crate rustc_data_structures;
 extern crate rustc_front;
+extern crate rustc_incremental;
 extern crate rustc_llvm as llvm;
 extern crate rustc_mir;
 extern crate rustc_platform_intrinsics as intrinsics;
@@ -61,6 +62,7 @@ extern crate serialize;
 #[macro_use] extern crate log;
 #[macro_use] extern crate syntax;
 
+pub use rustc::dep_graph;
 pub use rustc::session;
 pub use rustc::metadata;
 pub use rustc::middle;
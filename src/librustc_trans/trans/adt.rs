@@ -47,6 +47,7 @@ use std::rc::Rc;
 
 use llvm::{ValueRef, True, IntEQ, IntNE};
 use back::abi::FAT_PTR_ADDR;
+use dep_graph::DepNode;
 use middle::subst;
 use middle::ty::{self, Ty};
 use middle::ty::Disr;
@@ -238,6 +239,14 @@ fn dtor_active(flag: u8) -> bool {
 
 fn represent_type_uncached<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                                      t: Ty<'tcx>) -> Repr<'tcx> {
+    // The layout of a local type follows from its HIR.
+    match t.sty {
+        ty::TyStruct(def, _) | ty::TyEnum(def, _) if def.did.is_local() => {
+            cx.tcx().dep_graph.read(DepNode::Hir(def.did));
+        }
+        _ => {}
+    }
+
     match t.sty {
         ty::TyTuple(ref elems) => {
             Univariant(mk_struct(cx, &elems[..], false, t), 0)
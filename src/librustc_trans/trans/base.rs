@@ -27,10 +27,12 @@
 pub use self::ValueOrigin::*;
 
 use super::CrateTranslation;
+use super::IncrementalUnit;
 use super::ModuleTranslation;
 
 use back::link::mangle_exported_name;
-use back::{link, abi};
+use back::{link, abi, write};
+use dep_graph::DepNode;
 use lint;
 use llvm::{BasicBlockRef, Linkage, ValueRef, Vector, get_param};
 use llvm;
@@ -44,6 +46,7 @@ use middle::pat_util::simple_name;
 use middle::subst::Substs;
 use middle::ty::{self, Ty, HasTypeFlags};
 use rustc::front::map as hir_map;
+use rustc_incremental;
 use rustc_mir::mir_map::MirMap;
 use session::config::{self, NoDebugInfo, FullDebugInfo};
 use session::Session;
@@ -85,6 +88,7 @@ use trans::value::Value;
 use util::common::indenter;
 use util::sha2::Sha256;
 use util::nodemap::{NodeMap, NodeSet};
+use util::profiling;
 
 use arena::TypedArena;
 use libc::c_uint;
@@ -1707,8 +1711,10 @@ pub fn trans_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     let fn_ty = ccx.tcx().node_id_to_type(id);
     let output_type = ccx.tcx().erase_late_bound_regions(&fn_ty.fn_ret());
     let abi = fn_ty.fn_abi();
-    trans_closure(ccx, decl, body, llfndecl, param_substs, id, attrs, output_type, abi,
-                  closure::ClosureEnv::NotClosure);
+    profiling::profile_item("trans", || ccx.tcx().map.path_to_string(id), || {
+        trans_closure(ccx, decl, body, llfndecl, param_substs, id, attrs, output_type, abi,
+                      closure::ClosureEnv::NotClosure);
+    });
 }
 
 pub fn trans_enum_variant<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
@@ -2043,6 +2049,10 @@ pub fn update_linkage(ccx: &CrateContext,
         Some(id) if ccx.reachable().contains(&id) => {
             llvm::SetLinkage(llval, llvm::ExternalLinkage);
         },
+        None if ccx.incremental() => {
+            // See `CrateContext::incremental`.
+            llvm::SetLinkage(llval, llvm::InternalLinkage);
+        },
         _ => {
             // `id` does not refer to an item in `ccx.reachable`.
             if ccx.sess().opts.cg.codegen_units > 1 {
@@ -2074,6 +2084,19 @@ fn set_global_section(ccx: &CrateContext, llval: ValueRef, i: &hir::Item) {
 pub fn trans_item(ccx: &CrateContext, item: &hir::Item) {
     let _icx = push_ctxt("trans_item");
 
+    // The items of a unit which reuses its saved object file are only
+    // walked, to translate their copies into other units and to record the
+    // dependencies of the previous session; see `trans_incremental_root_mod`.
+    let tcx = ccx.tcx();
+    let def_id = tcx.map.local_def_id(item.id);
+    let reused = ccx.is_reused();
+    if reused {
+        tcx.dep_graph.reuse(DepNode::TransCrateItem(def_id));
+    }
+    let _task = if reused { None } else { tcx.dep_graph.in_task(DepNode::TransCrateItem(def_id)) };
+    let _ignore = if reused { tcx.dep_graph.in_ignore() } else { None };
+    tcx.dep_graph.read(DepNode::TypeckItemBody(def_id));
+
     let from_external = ccx.external_srcs().borrow().contains_key(&item.id);
 
     match item.node {
@@ -2085,6 +2108,9 @@ pub fn trans_item(ccx: &CrateContext, item: &hir::Item) {
             // compilation unit that references the item, so it will still get
             // translated everywhere it's needed.
             for (ref ccx, is_origin) in ccx.maybe_iter(!from_external && trans_everywhere) {
+                if ccx.is_reused() {
+                    continue;
+                }
                 let llfn = get_item_val(ccx, item.id);
                 let empty_substs = ccx.tcx().mk_substs(Substs::trans_empty());
                 if abi != Rust {
@@ -2124,7 +2150,13 @@ pub fn trans_item(ccx: &CrateContext, item: &hir::Item) {
                          item.id);
       }
       hir::ItemMod(ref m) => {
-        trans_mod(&ccx.rotate(), m);
+        // With `-Z incremental`, a module stays in the unit
+        // `trans_incremental_root_mod` assigned it to.
+        if ccx.incremental() {
+            trans_mod(ccx, m);
+        } else {
+            trans_mod(&ccx.rotate(), m);
+        }
       }
       hir::ItemEnum(ref enum_definition, ref gens) => {
         if gens.ty_params.is_empty() {
@@ -2143,6 +2175,9 @@ pub fn trans_item(ccx: &CrateContext, item: &hir::Item) {
           let mut v = TransItemVisitor{ ccx: ccx };
           v.visit_expr(&**expr);
 
+          if reused {
+              return;
+          }
           let g = match consts::trans_static(ccx, m, expr, item.id, &item.attrs) {
               Ok(g) => g,
               Err(err) => ccx.tcx().sess.span_fatal(expr.span, &err.description()),
@@ -2177,6 +2212,98 @@ pub fn trans_mod(ccx: &CrateContext, m: &hir::Mod) {
     }
 }
 
+/// Translates the root module with `-Z incremental`. Its items go to the
+/// first compilation unit, which is always rebuilt, and its modules are
+/// spread over the other units in order, so that each module ends up in
+/// the same unit from one session to the next.
+///
+/// Before anything is translated, the units into which only clean items
+/// go are marked as reusing the object file saved by the previous
+/// session, and their items are not translated again.
+fn trans_incremental_root_mod(shared_ccx: &SharedCrateContext, m: &hir::Mod) {
+    let _icx = push_ctxt("trans_mod");
+    shared_ccx.get_ccx(0).mark_changed();
+
+    let units = shared_ccx.sess().opts.cg.codegen_units;
+    let mut modules = 0;
+    let items: Vec<_> = m.items.iter().map(|item| {
+        let ccx = match item.node {
+            hir::ItemMod(_) if units > 1 => {
+                let ccx = shared_ccx.get_ccx(1 + modules % (units - 1));
+                ccx.incremental_modules().borrow_mut().push(item.name.as_str().to_string());
+                modules += 1;
+                ccx
+            }
+            _ => shared_ccx.get_ccx(0),
+        };
+        mark_changed_units(&ccx, item);
+        (ccx, item)
+    }).collect();
+
+    let sess = shared_ccx.sess();
+    if write::can_reuse_objects(sess) {
+        for (index, ccx) in shared_ccx.iter().enumerate() {
+            let saved = rustc_incremental::saved_object_path(sess, &unit_name(index, &ccx));
+            if !ccx.is_changed() && saved.map_or(false, |path| path.exists()) {
+                ccx.mark_reused();
+            }
+        }
+    }
+
+    for &(ref ccx, item) in &items {
+        trans_item(ccx, &**item);
+    }
+}
+
+/// Marks the compilation units into which `item`, which goes to the unit
+/// of `ccx`, has to be translated anew because it changed since the
+/// previous session: that unit, or every unit if the item is translated
+/// into each of them.
+fn mark_changed_units(ccx: &CrateContext, item: &hir::Item) {
+    if let hir::ItemMod(ref m) = item.node {
+        for item in &m.items {
+            mark_changed_units(ccx, &**item);
+        }
+    }
+
+    let tcx = ccx.tcx();
+    if tcx.dep_graph.is_clean(&DepNode::TransCrateItem(tcx.map.local_def_id(item.id))) {
+        return;
+    }
+    for (ccx, _) in ccx.maybe_iter(translated_everywhere(item)) {
+        ccx.mark_changed();
+    }
+}
+
+/// True if `trans_item` may translate `item`, or a method of it, into
+/// every compilation unit.
+fn translated_everywhere(item: &hir::Item) -> bool {
+    match item.node {
+        hir::ItemFn(..) => attr::requests_inline(&item.attrs),
+        hir::ItemImpl(_, _, _, _, _, ref impl_items) => {
+            impl_items.iter().any(|impl_item| attr::requests_inline(&impl_item.attrs))
+        }
+        _ => false,
+    }
+}
+
+/// Names a compilation unit after the modules translated into it.
+fn unit_name(index: usize, ccx: &CrateContext) -> String {
+    let mut name = index.to_string();
+    for module in ccx.incremental_modules().borrow().iter() {
+        name.push('-');
+        name.push_str(module);
+    }
+    name
+}
+
+/// Describes the compilation units for `write::run_passes`.
+fn incremental_units(shared_ccx: &SharedCrateContext) -> Vec<IncrementalUnit> {
+    shared_ccx.iter().enumerate().map(|(index, ccx)| {
+        IncrementalUnit { name: unit_name(index, &ccx), reuse: ccx.is_reused() }
+    }).collect()
+}
+
 
 // only use this for foreign function ABIs and glue, use `register_fn` for Rust functions
 pub fn register_fn_llvmty(ccx: &CrateContext,
@@ -2360,6 +2487,11 @@ pub fn get_item_val(ccx: &CrateContext, id: ast::NodeId) -> ValueRef {
         None => {}
     }
 
+    // The symbol name depends on the type of the item.
+    if let Some(def_id) = ccx.tcx().map.opt_local_def_id(id) {
+        ccx.tcx().dep_graph.read(DepNode::ItemSignature(def_id));
+    }
+
     let item = ccx.tcx().map.get(id);
     debug!("get_item_val: id={} item={:?}", id, item);
     let val = match item {
@@ -2805,7 +2937,11 @@ pub fn trans_crate<'tcx>(tcx: &ty::ctxt<'tcx>,
         // Next, translate the module.
         {
             let _icx = push_ctxt("text");
-            trans_mod(&ccx, &krate.module);
+            if ccx.incremental() {
+                trans_incremental_root_mod(&shared_ccx, &krate.module);
+            } else {
+                trans_mod(&ccx, &krate.module);
+            }
         }
     }
 
@@ -2883,7 +3019,10 @@ pub fn trans_crate<'tcx>(tcx: &ty::ctxt<'tcx>,
         });
     }
 
-    if codegen_units > 1 {
+    // With `-Z incremental`, the object files reused from the previous
+    // session may refer to any symbol of the other units.
+    let incremental = tcx.sess.opts.debugging_opts.incremental.is_some();
+    if codegen_units > 1 && !incremental {
         internalize_symbols(&shared_ccx,
                             &reachable_symbols.iter().map(|x| &x[..]).collect());
     }
@@ -2893,6 +3032,12 @@ pub fn trans_crate<'tcx>(tcx: &ty::ctxt<'tcx>,
         create_imps(&shared_ccx);
     }
 
+    let incremental_units = if incremental && write::can_reuse_objects(sess) {
+        incremental_units(&shared_ccx)
+    } else {
+        Vec::new()
+    };
+
     let metadata_module = ModuleTranslation {
         llcx: shared_ccx.metadata_llcx(),
         llmod: shared_ccx.metadata_llmod(),
@@ -2906,5 +3051,6 @@ pub fn trans_crate<'tcx>(tcx: &ty::ctxt<'tcx>,
         metadata: metadata,
         reachable: reachable_symbols,
         no_builtins: no_builtins,
+        incremental_units: incremental_units,
     }
 }
@@ -163,6 +163,19 @@ pub struct LocalCrateContext<'tcx> {
 
     trait_cache: RefCell<FnvHashMap<ty::PolyTraitRef<'tcx>,
                                     traits::Vtable<'tcx, ()>>>,
+
+    /// With `-Z incremental`, the names of the top-level modules
+    /// translated into this compilation unit.
+    incremental_modules: RefCell<Vec<String>>,
+
+    /// With `-Z incremental`, true if something translated into this
+    /// compilation unit may have changed since the previous session.
+    changed: Cell<bool>,
+
+    /// With `-Z incremental`, true if this compilation unit reuses the
+    /// object file saved by the previous session, so that nothing is
+    /// translated into it.
+    reused: Cell<bool>,
 }
 
 pub struct CrateContext<'a, 'tcx: 'a> {
@@ -479,6 +492,9 @@ impl<'tcx> LocalCrateContext<'tcx> {
                 n_llvm_insns: Cell::new(0),
                 type_of_depth: Cell::new(0),
                 trait_cache: RefCell::new(FnvHashMap()),
+                incremental_modules: RefCell::new(Vec::new()),
+                changed: Cell::new(false),
+                reused: Cell::new(false),
             };
 
             local_ccx.int_type = Type::int(&local_ccx.dummy_ccx(shared));
@@ -757,6 +773,39 @@ impl<'b, 'tcx> CrateContext<'b, 'tcx> {
         &self.local.trait_cache
     }
 
+    /// True if compiling with `-Z incremental`. Drop glue is then
+    /// translated into every compilation unit which uses it, and glue and
+    /// monomorphizations are internal to their unit, so that a unit never
+    /// refers to symbols which only exist because of another one.
+    pub fn incremental(&self) -> bool {
+        self.sess().opts.debugging_opts.incremental.is_some()
+    }
+
+    pub fn incremental_modules(&self) -> &RefCell<Vec<String>> {
+        &self.local.incremental_modules
+    }
+
+    /// Records that the object file saved for this compilation unit by
+    /// the previous session cannot be reused.
+    pub fn mark_changed(&self) {
+        self.local.changed.set(true);
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.local.changed.get()
+    }
+
+    /// Records that this compilation unit reuses the object file saved
+    /// for it by the previous session. This has to be decided before
+    /// anything is translated.
+    pub fn mark_reused(&self) {
+        self.local.reused.set(true);
+    }
+
+    pub fn is_reused(&self) -> bool {
+        self.local.reused.get()
+    }
+
     /// Return exclusive upper bound on object size.
     ///
     /// The theoretical maximum object size is defined as the maximum positive `int` value. This
@@ -244,11 +244,15 @@ fn get_drop_glue_core<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
 
     // To avoid infinite recursion, don't `make_drop_glue` until after we've
     // added the entry to the `drop_glues` cache.
-    if let Some(old_sym) = ccx.available_drop_glues().borrow().get(&g) {
-        let llfn = declare::declare_cfn(ccx, &old_sym, llfnty, ccx.tcx().mk_nil());
-        ccx.drop_glues().borrow_mut().insert(g, llfn);
-        return llfn;
-    };
+    // With `-Z incremental`, each unit has its own glue, see
+    // `CrateContext::incremental`.
+    if !ccx.incremental() {
+        if let Some(old_sym) = ccx.available_drop_glues().borrow().get(&g) {
+            let llfn = declare::declare_cfn(ccx, &old_sym, llfnty, ccx.tcx().mk_nil());
+            ccx.drop_glues().borrow_mut().insert(g, llfn);
+            return llfn;
+        }
+    }
 
     let fn_nm = mangle_internal_name_by_type_and_seq(ccx, t, "drop");
     let llfn = declare::define_cfn(ccx, &fn_nm, llfnty, ccx.tcx().mk_nil()).unwrap_or_else(||{
@@ -83,6 +83,9 @@ pub fn trans_impl(ccx: &CrateContext,
                 if sig.generics.ty_params.is_empty() {
                     let trans_everywhere = attr::requests_inline(&impl_item.attrs);
                     for (ref ccx, is_origin) in ccx.maybe_iter(trans_everywhere) {
+                        if ccx.is_reused() {
+                            continue;
+                        }
                         let llfn = get_item_val(ccx, impl_item.id);
                         let empty_substs = tcx.mk_substs(Substs::trans_empty());
                         trans_fn(ccx, &sig.decl, body, llfn,
@@ -75,4 +75,17 @@ pub struct CrateTranslation {
     pub metadata: Vec<u8>,
     pub reachable: Vec<String>,
     pub no_builtins: bool,
+    /// One entry per element of `modules` if their object files are
+    /// saved between sessions with `-Z incremental`, and none otherwise.
+    pub incremental_units: Vec<IncrementalUnit>,
+}
+
+/// A codegen unit, as seen by incremental compilation.
+pub struct IncrementalUnit {
+    /// Identifies the unit from one session to the next, and names its
+    /// saved object file.
+    pub name: String,
+    /// True if the object file saved by the previous session is used
+    /// instead of running LLVM on the unit.
+    pub reuse: bool,
 }
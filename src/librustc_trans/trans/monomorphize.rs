@@ -9,6 +9,7 @@
 // except according to those terms.
 
 use back::link::exported_name;
+use dep_graph::DepNode;
 use session;
 use llvm::ValueRef;
 use llvm;
@@ -52,6 +53,9 @@ pub fn monomorphic_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     // we can only monomorphize things in this crate (or inlined into it)
     let fn_node_id = ccx.tcx().map.as_local_node_id(fn_id).unwrap();
 
+    // The body is translated into the compilation unit of the caller.
+    ccx.tcx().dep_graph.read(DepNode::Hir(fn_id));
+
     let _icx = push_ctxt("monomorphic_fn");
 
     let hash_id = MonoId {
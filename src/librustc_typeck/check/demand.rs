@@ -67,6 +67,42 @@ pub fn coerce<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
       Ok(()) => { /* ok */ }
       Err(ref err) => {
         fcx.report_mismatched_types(sp, expected, expr_ty, err);
+        suggest_borrow(fcx, expected, expr_ty, expr);
       }
     }
 }
+
+// If `expr` would have the `expected` type had it been borrowed,
+// suggests borrowing it.
+fn suggest_borrow<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
+                            expected: Ty<'tcx>,
+                            expr_ty: Ty<'tcx>,
+                            expr: &hir::Expr) {
+    if expr_ty.references_error() {
+        return;
+    }
+    let (referent_ty, mutbl) = match expected.sty {
+        ty::TyRef(_, ty::TypeAndMut { ty, mutbl }) => (ty, mutbl),
+        _ => return,
+    };
+    if infer::can_mk_subty(fcx.infcx(), expr_ty, referent_ty).is_err() {
+        return;
+    }
+    if let Ok(snippet) = fcx.tcx().sess.codemap().span_to_snippet(expr.span) {
+        let borrow = match mutbl {
+            hir::MutMutable => "&mut ",
+            hir::MutImmutable => "&",
+        };
+        // `&` binds more tightly than these.
+        let snippet = match expr.node {
+            hir::ExprBinary(..) | hir::ExprCast(..) | hir::ExprRange(..) |
+            hir::ExprAssign(..) | hir::ExprAssignOp(..) | hir::ExprClosure(..) => {
+                format!("({})", snippet)
+            }
+            _ => snippet,
+        };
+        fcx.tcx().sess.span_suggestion(expr.span,
+                                       "consider borrowing here:",
+                                       format!("{}{}", borrow, snippet));
+    }
+}
@@ -83,6 +83,7 @@ use self::TupleArgumentsFlag::*;
 use astconv::{self, ast_region_to_region, ast_ty_to_ty, AstConv, PathParamMode};
 use check::_match::pat_ctxt;
 use fmt_macros::{Parser, Piece, Position};
+use dep_graph::DepNode;
 use metadata::cstore::LOCAL_CRATE;
 use middle::astconv_util::prohibit_type_params;
 use middle::def;
@@ -109,6 +110,7 @@ use {CrateCtxt, lookup_full_def};
 use TypeAndSubsts;
 use lint;
 use util::common::{block_query, ErrorReported, indenter, loop_query};
+use util::profiling;
 use util::nodemap::{DefIdMap, FnvHashMap, NodeMap};
 use util::lev_distance::lev_distance;
 
@@ -385,7 +387,13 @@ impl<'a, 'tcx> Visitor<'tcx> for CheckItemTypesVisitor<'a, 'tcx> {
 
 impl<'a, 'tcx> Visitor<'tcx> for CheckItemBodiesVisitor<'a, 'tcx> {
     fn visit_item(&mut self, i: &'tcx hir::Item) {
-        check_item_body(self.ccx, i);
+        {
+            let tcx = self.ccx.tcx;
+            let def_id = tcx.map.local_def_id(i.id);
+            let _task = tcx.dep_graph.in_task(DepNode::TypeckItemBody(def_id));
+            tcx.dep_graph.read(DepNode::Hir(def_id));
+            check_item_body(self.ccx, i);
+        }
         visit::walk_item(self, i);
     }
 }
@@ -458,31 +466,33 @@ fn check_bare_fn<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
 {
     match raw_fty.sty {
         ty::TyBareFn(_, ref fn_ty) => {
-            let tables = RefCell::new(ty::Tables::empty());
-            let inh = Inherited::new(ccx.tcx, &tables, param_env);
-
-            // Compute the fty from point of view of inside fn.
-            let fn_scope = ccx.tcx.region_maps.item_extent(body.id);
-            let fn_sig =
-                fn_ty.sig.subst(ccx.tcx, &inh.infcx.parameter_environment.free_substs);
-            let fn_sig =
-                ccx.tcx.liberate_late_bound_regions(fn_scope, &fn_sig);
-            let fn_sig =
-                inh.normalize_associated_types_in(body.span,
-                                                  body.id,
-                                                  &fn_sig);
-
-            let fcx = check_fn(ccx, fn_ty.unsafety, fn_id, &fn_sig,
-                               decl, fn_id, body, &inh);
-
-            fcx.select_all_obligations_and_apply_defaults();
-            upvar::closure_analyze_fn(&fcx, fn_id, decl, body);
-            fcx.select_obligations_where_possible();
-            fcx.check_casts();
-            fcx.select_all_obligations_or_error(); // Casts can introduce new obligations.
-
-            regionck::regionck_fn(&fcx, fn_id, fn_span, decl, body);
-            writeback::resolve_type_vars_in_fn(&fcx, decl, body);
+            profiling::profile_item("typeck", || ccx.tcx.map.path_to_string(fn_id), || {
+                let tables = RefCell::new(ty::Tables::empty());
+                let inh = Inherited::new(ccx.tcx, &tables, param_env);
+
+                // Compute the fty from point of view of inside fn.
+                let fn_scope = ccx.tcx.region_maps.item_extent(body.id);
+                let fn_sig =
+                    fn_ty.sig.subst(ccx.tcx, &inh.infcx.parameter_environment.free_substs);
+                let fn_sig =
+                    ccx.tcx.liberate_late_bound_regions(fn_scope, &fn_sig);
+                let fn_sig =
+                    inh.normalize_associated_types_in(body.span,
+                                                      body.id,
+                                                      &fn_sig);
+
+                let fcx = check_fn(ccx, fn_ty.unsafety, fn_id, &fn_sig,
+                                   decl, fn_id, body, &inh);
+
+                fcx.select_all_obligations_and_apply_defaults();
+                upvar::closure_analyze_fn(&fcx, fn_id, decl, body);
+                fcx.select_obligations_where_possible();
+                fcx.check_casts();
+                fcx.select_all_obligations_or_error(); // Casts can introduce new obligations.
+
+                regionck::regionck_fn(&fcx, fn_id, fn_span, decl, body);
+                writeback::resolve_type_vars_in_fn(&fcx, decl, body);
+            })
         }
         _ => ccx.tcx.sess.impossible_case(body.span,
                                  "check_bare_fn: function type expected")
@@ -65,6 +65,7 @@ There are some shortcomings in this design:
 */
 
 use astconv::{self, AstConv, ty_of_arg, ast_ty_to_ty, ast_region_to_region};
+use dep_graph::DepNode;
 use middle::def;
 use middle::def_id::DefId;
 use constrained_type_params as ctp;
@@ -95,6 +96,7 @@ use syntax::abi;
 use syntax::ast;
 use syntax::attr;
 use syntax::codemap::Span;
+use syntax::diagnostic::SpanLabel;
 use syntax::parse::token::special_idents;
 use syntax::ptr::P;
 use rustc_front::hir;
@@ -180,7 +182,14 @@ struct CollectItemTypesVisitor<'a, 'tcx: 'a> {
 
 impl<'a, 'tcx, 'v> visit::Visitor<'v> for CollectItemTypesVisitor<'a, 'tcx> {
     fn visit_item(&mut self, i: &hir::Item) {
-        convert_item(self.ccx, i);
+        {
+            let tcx = self.ccx.tcx;
+            let def_id = tcx.map.local_def_id(i.id);
+            let _task = tcx.dep_graph.in_task(DepNode::ItemSignature(def_id));
+            tcx.dep_graph.read(DepNode::Hir(def_id));
+            tcx.dep_graph.write(DepNode::Predicates(def_id));
+            convert_item(self.ccx, i);
+        }
         visit::walk_item(self, i);
     }
     fn visit_foreign_item(&mut self, i: &hir::ForeignItem) {
@@ -1095,10 +1104,14 @@ fn convert_struct_variant<'tcx>(tcx: &ty::ctxt<'tcx>,
             hir::NamedField(name, vis) => {
                 let dup_span = seen_fields.get(&name).cloned();
                 if let Some(prev_span) = dup_span {
-                    span_err!(tcx.sess, f.span, E0124,
-                              "field `{}` is already declared",
-                              name);
-                    span_note!(tcx.sess, prev_span, "previously declared here");
+                    let spans = [
+                        SpanLabel::primary(f.span, Some("field already declared".to_string())),
+                        SpanLabel::secondary(prev_span,
+                                             Some(format!("`{}` first declared here", name))),
+                    ];
+                    labelled_span_err!(tcx.sess, &spans, E0124,
+                                       "field `{}` is already declared",
+                                       name);
                 } else {
                     seen_fields.insert(name, f.span);
                 }
@@ -64,8 +64,9 @@ use std::sync::mpsc::channel;
 use externalfiles::ExternalHtml;
 use serialize::Decodable;
 use serialize::json::{self, Json};
+use rustc::session::config::ErrorOutputType;
 use rustc::session::search_paths::SearchPaths;
-use syntax::diagnostic;
+use syntax::diagnostic::ColorConfig;
 
 // reexported from `clean` so it can be easily updated with the mod itself
 pub use clean::SCHEMA_VERSION;
@@ -163,6 +164,8 @@ pub fn opts() -> Vec<getopts::OptGroup> {
         optmulti("", "test-args", "arguments to pass to the test runner",
                  "ARGS"),
         optopt("", "target", "target triple to document", "TRIPLE"),
+        optopt("", "error-format", "how errors are reported while compiling the crate",
+               "[human|json]"),
         optmulti("", "markdown-css", "CSS files to include via <link> in a rendered Markdown file",
                  "FILES"),
         optmulti("", "html-in-header",
@@ -226,9 +229,17 @@ pub fn main_args(args: &[String]) -> isize {
     }
     let input = &matches.free[0];
 
+    let error_format = match parse_error_format(&matches) {
+        Ok(format) => format,
+        Err(err) => {
+            println!("{}", err);
+            return 1;
+        }
+    };
+
     let mut libs = SearchPaths::new();
     for s in &matches.opt_strs("L") {
-        libs.add_path(s, diagnostic::Auto);
+        libs.add_path(s, error_format);
     }
     let externs = match parse_externs(&matches) {
         Ok(ex) => ex,
@@ -264,7 +275,8 @@ pub fn main_args(args: &[String]) -> isize {
             return markdown::test(input, libs, externs, test_args)
         }
         (true, false) => {
-            return test::run(input, cfgs, libs, externs, test_args, crate_name)
+            return test::run(input, cfgs, libs, externs, test_args, crate_name,
+                             error_format)
         }
         (false, true) => return markdown::render(input,
                                                  output.unwrap_or(PathBuf::from("doc")),
@@ -272,7 +284,7 @@ pub fn main_args(args: &[String]) -> isize {
                                                  !matches.opt_present("markdown-no-toc")),
         (false, false) => {}
     }
-    let out = match acquire_input(input, externs, &matches) {
+    let out = match acquire_input(input, externs, error_format, &matches) {
         Ok(out) => out,
         Err(s) => {
             println!("input error: {}", s);
@@ -310,16 +322,17 @@ pub fn main_args(args: &[String]) -> isize {
 /// and files and then generates the necessary rustdoc output for formatting.
 fn acquire_input(input: &str,
                  externs: core::Externs,
+                 error_format: ErrorOutputType,
                  matches: &getopts::Matches) -> Result<Output, String> {
     match matches.opt_str("r").as_ref().map(|s| &**s) {
-        Some("rust") => Ok(rust_input(input, externs, matches)),
+        Some("rust") => Ok(rust_input(input, externs, error_format, matches)),
         Some("json") => json_input(input),
         Some(s) => Err(format!("unknown input format: {}", s)),
         None => {
             if input.ends_with(".json") {
                 json_input(input)
             } else {
-                Ok(rust_input(input, externs, matches))
+                Ok(rust_input(input, externs, error_format, matches))
             }
         }
     }
@@ -350,12 +363,24 @@ fn parse_externs(matches: &getopts::Matches) -> Result<core::Externs, String> {
     Ok(externs)
 }
 
+/// Extracts `--error-format` from `matches`, defaulting to human readable
+/// output, or else returns an error message.
+fn parse_error_format(matches: &getopts::Matches) -> Result<ErrorOutputType, String> {
+    match matches.opt_str("error-format").as_ref().map(|s| &s[..]) {
+        Some("human") | None => Ok(ErrorOutputType::HumanReadable(ColorConfig::Auto)),
+        Some("json") => Ok(ErrorOutputType::Json),
+        Some(arg) => Err(format!("argument for --error-format must be human or json \
+                                  (instead was `{}`)", arg)),
+    }
+}
+
 /// Interprets the input file as a rust source file, passing it through the
 /// compiler all the way through the analysis passes. The rustdoc output is then
 /// generated from the cleaned AST of the crate.
 ///
 /// This form of input will run all of the plug/cleaning passes
-fn rust_input(cratefile: &str, externs: core::Externs, matches: &getopts::Matches) -> Output {
+fn rust_input(cratefile: &str, externs: core::Externs, error_format: ErrorOutputType,
+              matches: &getopts::Matches) -> Output {
     let mut default_passes = !matches.opt_present("no-defaults");
     let mut passes = matches.opt_strs("passes");
     let mut plugins = matches.opt_strs("plugins");
@@ -363,7 +388,7 @@ fn rust_input(cratefile: &str, externs: core::Externs, matches: &getopts::Matche
     // First, parse the crate and extract all relevant information.
     let mut paths = SearchPaths::new();
     for s in &matches.opt_strs("L") {
-        paths.add_path(s, diagnostic::Auto);
+        paths.add_path(s, error_format);
     }
     let cfgs = matches.opt_strs("cfg");
     let triple = matches.opt_str("target");
@@ -376,7 +401,7 @@ fn rust_input(cratefile: &str, externs: core::Externs, matches: &getopts::Matche
         use rustc::session::config::Input;
 
         tx.send(core::run_core(paths, cfgs, externs, Input::File(cr),
-                               triple)).unwrap();
+                               triple, error_format)).unwrap();
     });
     let (mut krate, analysis) = rx.recv().unwrap();
     info!("finished with rustc");
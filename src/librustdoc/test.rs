@@ -53,7 +53,8 @@ pub fn run(input: &str,
            libs: SearchPaths,
            externs: core::Externs,
            mut test_args: Vec<String>,
-           crate_name: Option<String>)
+           crate_name: Option<String>,
+           error_format: config::ErrorOutputType)
            -> isize {
     let input_path = PathBuf::from(input);
     let input = config::Input::File(input_path.clone());
@@ -65,11 +66,13 @@ pub fn run(input: &str,
         crate_types: vec!(config::CrateTypeDylib),
         externs: externs.clone(),
         unstable_features: get_unstable_features_setting(),
+        error_format: error_format,
         ..config::basic_options().clone()
     };
 
     let codemap = CodeMap::new();
-    let diagnostic_handler = diagnostic::Handler::new(diagnostic::Auto, None, true);
+    let emitter = session::build_emitter(error_format, None);
+    let diagnostic_handler = diagnostic::Handler::with_emitter(true, emitter);
     let span_diagnostic_handler =
     diagnostic::SpanHandler::new(diagnostic_handler, codemap);
 
@@ -386,6 +389,7 @@ impl Collector {
                 ignore: should_ignore,
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
+                timeout: None,
             },
             testfn: testing::DynTestFn(Box::new(move|| {
                 runtest(&test,
@@ -24,6 +24,7 @@ use path::{Path, PathBuf};
 use sys::fs as fs_imp;
 use sys_common::io::read_to_end_uninitialized;
 use sys_common::{AsInnerMut, FromInner, AsInner, IntoInner};
+use time::SystemTime;
 use vec::Vec;
 
 /// A reference to an open file on the filesystem.
@@ -308,6 +309,83 @@ impl File {
     pub fn metadata(&self) -> io::Result<Metadata> {
         self.inner.file_attr().map(Metadata)
     }
+
+    /// Acquires an exclusive lock on the file, blocking until it can be
+    /// acquired.
+    ///
+    /// At most one handle can hold an exclusive lock on a file at a time, and
+    /// no handle can hold a shared lock on it at the same time. The lock is
+    /// released by `unlock`, or when the file is closed.
+    ///
+    /// # Platform behavior
+    ///
+    /// This corresponds to `flock` with `LOCK_EX` on Unix, and to
+    /// `LockFileEx` with `LOCKFILE_EXCLUSIVE_LOCK` on Windows. What happens
+    /// when the handle already holds a lock on the file is platform-specific:
+    /// Unix replaces the existing lock, while Windows acquires another one.
+    ///
+    /// On Unix the lock is advisory: it doesn't stop anybody from reading or
+    /// writing the file, only from acquiring a conflicting lock. On Windows
+    /// it is a mandatory lock of the whole file: while it is held, reading
+    /// and writing the file through other handles fails with
+    /// `ERROR_LOCK_VIOLATION`, and so does writing it through other handles
+    /// while a shared lock is held. To behave the same everywhere, lock a
+    /// separate file guarding the data, as in the example below.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = try!(File::create("foo.lock"));
+    /// try!(f.lock());
+    /// // ... exclusive access to whatever foo.lock guards ...
+    /// try!(f.unlock());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_lock", reason = "recently added", issue = "0")]
+    pub fn lock(&self) -> io::Result<()> {
+        self.inner.lock(true)
+    }
+
+    /// Acquires a shared lock on the file, blocking until it can be acquired.
+    ///
+    /// Any number of handles can hold a shared lock on a file at a time, as
+    /// long as none holds an exclusive lock on it. See `lock` for how locks
+    /// behave on each platform.
+    #[unstable(feature = "file_lock", reason = "recently added", issue = "0")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock(false)
+    }
+
+    /// Attempts to acquire an exclusive lock on the file without blocking.
+    ///
+    /// Returns `Ok(false)` if another handle holds a conflicting lock, and
+    /// `Ok(true)` if the lock was acquired. See `lock` for how locks behave on
+    /// each platform.
+    #[unstable(feature = "file_lock", reason = "recently added", issue = "0")]
+    pub fn try_lock(&self) -> io::Result<bool> {
+        self.inner.try_lock(true)
+    }
+
+    /// Attempts to acquire a shared lock on the file without blocking.
+    ///
+    /// Returns `Ok(false)` if another handle holds an exclusive lock, and
+    /// `Ok(true)` if the lock was acquired. See `lock` for how locks behave on
+    /// each platform.
+    #[unstable(feature = "file_lock", reason = "recently added", issue = "0")]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.inner.try_lock(false)
+    }
+
+    /// Releases the lock held by this handle on the file.
+    #[unstable(feature = "file_lock", reason = "recently added", issue = "0")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
 }
 
 impl AsInner<fs_imp::File> for File {
@@ -584,6 +662,52 @@ impl Metadata {
     pub fn permissions(&self) -> Permissions {
         Permissions(self.0.perm())
     }
+
+    /// Returns the last modification time listed in this metadata.
+    ///
+    /// The returned value corresponds to the `mtime` field of `stat` on Unix
+    /// platforms and the `ftLastWriteTime` field on Windows platforms.
+    ///
+    /// # Errors
+    ///
+    /// This field may not be available on all platforms, and will return an
+    /// `Err` on platforms where it is not available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(time2)]
+    /// # fn foo() -> std::io::Result<()> {
+    /// use std::fs;
+    ///
+    /// let metadata = try!(fs::metadata("foo.txt"));
+    /// let mtime = try!(metadata.modified());
+    /// println!("{:?}", mtime.elapsed());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "time2", reason = "recently added", issue = "0")]
+    pub fn modified(&self) -> io::Result<SystemTime> {
+        self.0.modified().map(FromInner::from_inner)
+    }
+
+    /// Returns the last access time of this metadata.
+    ///
+    /// The returned value corresponds to the `atime` field of `stat` on Unix
+    /// platforms and the `ftLastAccessTime` field on Windows platforms.
+    ///
+    /// Note that not all platforms will keep this field update in a file's
+    /// metadata, for example Windows has an option to disable updating this
+    /// time when files are accessed and Linux similarly has `noatime`.
+    ///
+    /// # Errors
+    ///
+    /// This field may not be available on all platforms, and will return an
+    /// `Err` on platforms where it is not available.
+    #[unstable(feature = "time2", reason = "recently added", issue = "0")]
+    pub fn accessed(&self) -> io::Result<SystemTime> {
+        self.0.accessed().map(FromInner::from_inner)
+    }
 }
 
 impl AsInner<fs_imp::FileAttr> for Metadata {
@@ -859,6 +983,12 @@ pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()>
 ///
 /// On success, the total number of bytes copied is returned.
 ///
+/// # Platform behavior
+///
+/// On Linux the copy is done in the kernel with `copy_file_range` when the
+/// two files allow it, falling back to reading and writing otherwise. On
+/// Windows this uses `CopyFileEx`.
+///
 /// # Errors
 ///
 /// This function will return an error in the following situations, but is not
@@ -1339,6 +1469,7 @@ mod tests {
     use os;
     use rand::{self, StdRng, Rng};
     use str;
+    use time::{Duration, SystemTime};
 
     macro_rules! check { ($e:expr) => (
         match $e {
@@ -1576,6 +1707,21 @@ mod tests {
         check!(fs::remove_dir(filename));
     }
 
+    #[test]
+    fn file_test_stat_times() {
+        let tmpdir = tmpdir();
+        let filename = &tmpdir.join("file_stat_times.txt");
+        let before = SystemTime::now() - Duration::new(2, 0);
+        check!(File::create(filename));
+        let after = SystemTime::now() + Duration::new(2, 0);
+
+        let metadata = check!(fs::metadata(filename));
+        let modified = check!(metadata.modified());
+        assert!(before <= modified && modified <= after);
+        let accessed = check!(metadata.accessed());
+        assert!(before <= accessed && accessed <= after);
+    }
+
     #[test]
     fn file_test_fileinfo_false_when_checking_is_file_on_a_directory() {
         let tmpdir = tmpdir();
@@ -1799,6 +1945,24 @@ mod tests {
                    check!(out.metadata()).permissions());
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn copy_file_procfs() {
+        // procfs files report a size of zero despite having contents.
+        let tmpdir = tmpdir();
+        let out = tmpdir.join("version");
+
+        let copied = check!(fs::copy("/proc/version", &out));
+        let mut v = Vec::new();
+        check!(check!(File::open(&out)).read_to_end(&mut v));
+        assert!(!v.is_empty());
+        assert_eq!(copied, v.len() as u64);
+
+        let mut expected = Vec::new();
+        check!(check!(File::open("/proc/version")).read_to_end(&mut expected));
+        assert_eq!(v, expected);
+    }
+
     #[test]
     fn copy_file_dst_dir() {
         let tmpdir = tmpdir();
@@ -1852,6 +2016,132 @@ mod tests {
         check!(fs::set_permissions(&out, attr.permissions()));
     }
 
+    #[test]
+    fn copy_file_large() {
+        let tmpdir = tmpdir();
+        let input = tmpdir.join("in.txt");
+        let out = tmpdir.join("out.txt");
+
+        let data = (0..100_000).map(|i| i as u8).collect::<Vec<u8>>();
+        check!(check!(File::create(&input)).write_all(&data));
+        assert_eq!(check!(fs::copy(&input, &out)), data.len() as u64);
+        let mut v = Vec::new();
+        check!(check!(File::open(&out)).read_to_end(&mut v));
+        assert!(v == data);
+    }
+
+    #[test]
+    fn file_lock() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock");
+        let a = check!(File::create(&path));
+        let b = check!(File::open(&path));
+
+        check!(a.lock());
+        assert!(!check!(b.try_lock()));
+        assert!(!check!(b.try_lock_shared()));
+        check!(a.unlock());
+
+        assert!(check!(b.try_lock_shared()));
+        assert!(check!(a.try_lock_shared()));
+        check!(a.unlock());
+        assert!(!check!(a.try_lock()));
+        check!(b.unlock());
+        assert!(check!(a.try_lock()));
+        check!(a.unlock());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn file_lock_is_mandatory() {
+        use sys::c;
+
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock");
+        let mut a = check!(File::create(&path));
+        check!(a.write(b"locked"));
+        let mut b = check!(OpenOptions::new().read(true).write(true).open(&path));
+        let mut buf = [0; 6];
+
+        check!(a.lock());
+        let e = b.read(&mut buf).err().expect("read through another handle");
+        assert_eq!(e.raw_os_error(), Some(c::ERROR_LOCK_VIOLATION as i32));
+        let e = b.write(b"denied").err().expect("write through another handle");
+        assert_eq!(e.raw_os_error(), Some(c::ERROR_LOCK_VIOLATION as i32));
+        check!(a.unlock());
+
+        check!(a.lock_shared());
+        check!(b.read(&mut buf));
+        assert_eq!(&buf, b"locked");
+        let e = b.write(b"denied").err().expect("write through another handle");
+        assert_eq!(e.raw_os_error(), Some(c::ERROR_LOCK_VIOLATION as i32));
+        check!(a.unlock());
+        check!(b.write(b"denied"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_ext_positional_io() {
+        use os::unix::fs::FileExt;
+
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("file");
+        let mut f = check!(OpenOptions::new().read(true).write(true)
+                                             .create(true).open(&path));
+        check!(f.write_all(b"0123456789"));
+        assert_eq!(check!(f.write_at(b"ab", 2)), 2);
+        assert_eq!(check!(f.write_at(b"cd", 12)), 2);
+        assert_eq!(check!(f.seek(SeekFrom::Current(0))), 10);
+
+        let mut buf = [0; 4];
+        assert_eq!(check!(f.read_at(&mut buf, 0)), 4);
+        assert_eq!(&buf, b"01ab");
+        assert_eq!(check!(f.read_at(&mut buf, 10)), 4);
+        assert_eq!(&buf, b"\0\0cd");
+        assert_eq!(check!(f.read_at(&mut buf, 14)), 0);
+        assert_eq!(check!(f.seek(SeekFrom::Current(0))), 10);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_ext_copy_range() {
+        use os::unix::fs::FileExt;
+
+        let tmpdir = tmpdir();
+        let input = tmpdir.join("in.txt");
+        let out = tmpdir.join("out.txt");
+        check!(check!(File::create(&input)).write_all(b"hello world"));
+        let src = check!(File::open(&input));
+        let dst = check!(File::create(&out));
+
+        let mut copied = 0;
+        while copied < 5 {
+            let n = check!(src.copy_range(6 + copied, &dst, copied, 5 - copied));
+            assert!(n > 0);
+            copied += n;
+        }
+        assert_eq!(check!(src.copy_range(11, &dst, 5, 5)), 0);
+        let mut v = Vec::new();
+        check!(check!(File::open(&out)).read_to_end(&mut v));
+        assert_eq!(v, b"world");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn file_ext_allocate() {
+        use libc;
+        use os::unix::fs::FileExt;
+
+        let tmpdir = tmpdir();
+        let f = check!(File::create(&tmpdir.join("file")));
+        match f.allocate(0, 4096) {
+            Ok(()) => assert!(check!(f.metadata()).len() >= 4096),
+            // Not every filesystem supports preallocation
+            Err(ref e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => {}
+            Err(e) => panic!("allocate failed with: {}", e),
+        }
+    }
+
     #[cfg(windows)]
     #[test]
     fn copy_file_preserves_streams() {
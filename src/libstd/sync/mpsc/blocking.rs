@@ -16,6 +16,7 @@ use sync::Arc;
 use marker::{Sync, Send};
 use mem;
 use clone::Clone;
+use time::Instant;
 
 struct Inner {
     thread: Thread,
@@ -83,4 +84,17 @@ impl WaitToken {
             thread::park()
         }
     }
+
+    /// Like `wait`, but gives up once `end` has passed. Returns `true` if the
+    /// token was signalled and `false` if it timed out.
+    pub fn wait_max_until(self, end: Instant) -> bool {
+        while !self.inner.woken.load(Ordering::SeqCst) {
+            let now = Instant::now();
+            if now >= end {
+                return false;
+            }
+            thread::park_timeout(end.duration_since(now))
+        }
+        true
+    }
 }
@@ -62,6 +62,8 @@ use core::cell::{Cell, UnsafeCell};
 use core::marker;
 use core::ptr;
 use core::usize;
+use time::{Duration, Instant};
+use vec::Vec;
 
 use sync::mpsc::{Receiver, RecvError};
 use sync::mpsc::blocking::{self, SignalToken};
@@ -166,94 +168,62 @@ impl Select {
         self.wait2(true)
     }
 
-    /// Helper method for skipping the preflight checks during testing
-    fn wait2(&self, do_preflight_checks: bool) -> usize {
-        // Note that this is currently an inefficient implementation. We in
-        // theory have knowledge about all receivers in the set ahead of time,
-        // so this method shouldn't really have to iterate over all of them yet
-        // again. The idea with this "receiver set" interface is to get the
-        // interface right this time around, and later this implementation can
-        // be optimized.
-        //
-        // This implementation can be summarized by:
-        //
-        //      fn select(receivers) {
-        //          if any receiver ready { return ready index }
-        //          deschedule {
-        //              block on all receivers
-        //          }
-        //          unblock on all receivers
-        //          return ready index
-        //      }
-        //
-        // Most notably, the iterations over all of the receivers shouldn't be
-        // necessary.
-        unsafe {
-            // Stage 1: preflight checks. Look for any packets ready to receive
-            if do_preflight_checks {
-                for handle in self.iter() {
-                    if (*handle).packet.can_recv() {
-                        return (*handle).id();
-                    }
-                }
-            }
+    /// Waits for an event on this receiver set, giving up once `timeout` has
+    /// elapsed.
+    ///
+    /// This is the same as `wait`, except that `None` is returned if none of
+    /// the receivers became ready in time. `ReceiverSet` offers the same
+    /// waits without `unsafe` code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    ///
+    /// use std::sync::mpsc::{channel, Select};
+    /// use std::time::Duration;
+    ///
+    /// let (tx1, rx1) = channel::<i32>();
+    /// let (tx2, rx2) = channel::<i32>();
+    ///
+    /// let sel = Select::new();
+    /// let mut h1 = sel.handle(&rx1);
+    /// let mut h2 = sel.handle(&rx2);
+    /// unsafe { h1.add(); h2.add(); }
+    ///
+    /// assert_eq!(sel.wait_timeout(Duration::from_millis(10)), None);
+    ///
+    /// tx2.send(2).unwrap();
+    /// assert_eq!(sel.wait_timeout(Duration::from_millis(10)), Some(h2.id()));
+    /// assert_eq!(h2.recv(), Ok(2));
+    /// # drop(tx1);
+    /// ```
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<usize> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait_deadline(deadline),
+            None => Some(self.wait()),
+        }
+    }
 
-            // Stage 2: begin the blocking process
-            //
-            // Create a number of signal tokens, and install each one
-            // sequentially until one fails. If one fails, then abort the
-            // selection on the already-installed tokens.
-            let (wait_token, signal_token) = blocking::tokens();
-            for (i, handle) in self.iter().enumerate() {
-                match (*handle).packet.start_selection(signal_token.clone()) {
-                    StartResult::Installed => {}
-                    StartResult::Abort => {
-                        // Go back and abort the already-begun selections
-                        for handle in self.iter().take(i) {
-                            (*handle).packet.abort_selection();
-                        }
-                        return (*handle).id;
-                    }
-                }
-            }
+    /// Waits for an event on this receiver set, giving up once `deadline` has
+    /// been reached.
+    ///
+    /// This is the same as `wait_timeout`, but with the time limit given as an
+    /// absolute point in time.
+    pub fn wait_deadline(&self, deadline: Instant) -> Option<usize> {
+        self.wait_impl(true, Some(deadline))
+    }
 
-            // Stage 3: no messages available, actually block
-            wait_token.wait();
-
-            // Stage 4: there *must* be message available; find it.
-            //
-            // Abort the selection process on each receiver. If the abort
-            // process returns `true`, then that means that the receiver is
-            // ready to receive some data. Note that this also means that the
-            // receiver may have yet to have fully read the `to_wake` field and
-            // woken us up (although the wakeup is guaranteed to fail).
-            //
-            // This situation happens in the window of where a sender invokes
-            // increment(), sees -1, and then decides to wake up the thread. After
-            // all this is done, the sending thread will set `selecting` to
-            // `false`. Until this is done, we cannot return. If we were to
-            // return, then a sender could wake up a receiver which has gone
-            // back to sleep after this call to `select`.
-            //
-            // Note that it is a "fairly small window" in which an increment()
-            // views that it should wake a thread up until the `selecting` bit
-            // is set to false. For now, the implementation currently just spins
-            // in a yield loop. This is very distasteful, but this
-            // implementation is already nowhere near what it should ideally be.
-            // A rewrite should focus on avoiding a yield loop, and for now this
-            // implementation is tying us over to a more efficient "don't
-            // iterate over everything every time" implementation.
-            let mut ready_id = usize::MAX;
-            for handle in self.iter() {
-                if (*handle).packet.abort_selection() {
-                    ready_id = (*handle).id;
-                }
-            }
+    /// Helper method for skipping the preflight checks during testing
+    fn wait2(&self, do_preflight_checks: bool) -> usize {
+        // Without a deadline the wait can only end with a ready receiver.
+        self.wait_impl(do_preflight_checks, None).unwrap()
+    }
 
-            // We must have found a ready receiver
-            assert!(ready_id != usize::MAX);
-            return ready_id;
-        }
+    fn wait_impl(&self, do_preflight_checks: bool,
+                 deadline: Option<Instant>) -> Option<usize> {
+        let packets = || self.iter().map(|handle| unsafe { ((*handle).id, (*handle).packet) });
+        wait_packets(packets, do_preflight_checks, deadline)
     }
 
     fn iter(&self) -> Packets { Packets { cur: unsafe { &*self.inner.get() }.head } }
@@ -321,6 +291,204 @@ impl<'rx, T: Send> Handle<'rx, T> {
     }
 }
 
+/// A set of receivers which can be waited on at once, like with `Select`,
+/// but without its `unsafe` handles.
+///
+/// The set borrows the receivers added to it, which are identified by the
+/// index `add` returns. When a wait returns the index of a receiver, that
+/// receiver has a message available or its channel has been closed, so
+/// receiving from it will not block.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpsc_receiver_set)]
+///
+/// use std::sync::mpsc::{channel, ReceiverSet};
+/// use std::time::Duration;
+///
+/// let (tx1, rx1) = channel::<i32>();
+/// let (tx2, rx2) = channel::<String>();
+///
+/// let mut set = ReceiverSet::new();
+/// set.add(&rx1);
+/// let index2 = set.add(&rx2);
+///
+/// assert_eq!(set.wait_timeout(Duration::from_millis(10)), None);
+///
+/// tx2.send("hello".to_string()).unwrap();
+/// assert_eq!(set.wait(), index2);
+/// assert_eq!(rx2.recv().unwrap(), "hello");
+/// # drop(tx1);
+/// ```
+#[unstable(feature = "mpsc_receiver_set", reason = "recently added", issue = "0")]
+pub struct ReceiverSet<'rx> {
+    packets: Vec<&'rx (Packet + 'rx)>,
+}
+
+#[unstable(feature = "mpsc_receiver_set", reason = "recently added", issue = "0")]
+impl<'rx> ReceiverSet<'rx> {
+    /// Creates a new, empty set of receivers.
+    pub fn new() -> ReceiverSet<'rx> {
+        ReceiverSet { packets: Vec::new() }
+    }
+
+    /// Adds `rx` to this set, and returns the index by which the waits of
+    /// the set refer to it. Receivers are numbered from 0 in the order they
+    /// are added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rx` is already in this set, as a receiver can only be
+    /// waited on once at a time.
+    pub fn add<T>(&mut self, rx: &'rx Receiver<T>) -> usize {
+        let packet: &'rx (Packet + 'rx) = rx;
+        let ptr = packet as *const _ as *const ();
+        assert!(!self.packets.iter().any(|&p| p as *const _ as *const () == ptr),
+                "receiver added to a `ReceiverSet` twice");
+        self.packets.push(packet);
+        self.packets.len() - 1
+    }
+
+    /// Blocks until one of the receivers of this set is ready, and returns
+    /// its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the set is empty, as the wait would never end.
+    pub fn wait(&self) -> usize {
+        assert!(!self.packets.is_empty(), "waiting on an empty `ReceiverSet`");
+        self.wait_impl(None).unwrap()
+    }
+
+    /// Waits for one of the receivers of this set to be ready, giving up
+    /// once `timeout` has elapsed.
+    ///
+    /// This is the same as `wait`, except that `None` is returned if none of
+    /// the receivers became ready in time. An empty set always times out.
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<usize> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait_deadline(deadline),
+            None => Some(self.wait()),
+        }
+    }
+
+    /// Waits for one of the receivers of this set to be ready, giving up
+    /// once `deadline` has been reached.
+    ///
+    /// This is the same as `wait_timeout`, but with the time limit given as
+    /// an absolute point in time.
+    pub fn wait_deadline(&self, deadline: Instant) -> Option<usize> {
+        self.wait_impl(Some(deadline))
+    }
+
+    fn wait_impl(&self, deadline: Option<Instant>) -> Option<usize> {
+        wait_packets(|| self.packets.iter().cloned().enumerate(), true, deadline)
+    }
+}
+
+/// Waits until one of the packets yielded by `packets()` is ready, and
+/// returns the id it was yielded with, or `None` once `deadline` has been
+/// reached. `packets` is called for every pass over the packets.
+fn wait_packets<'a, F, I>(packets: F, do_preflight_checks: bool,
+                          deadline: Option<Instant>) -> Option<usize>
+    where F: Fn() -> I, I: Iterator<Item=(usize, &'a (Packet + 'a))>
+{
+    // Note that this is currently an inefficient implementation. We in
+    // theory have knowledge about all receivers in the set ahead of time,
+    // so this method shouldn't really have to iterate over all of them yet
+    // again. The idea with this "receiver set" interface is to get the
+    // interface right this time around, and later this implementation can
+    // be optimized.
+    //
+    // This implementation can be summarized by:
+    //
+    //      fn select(receivers) {
+    //          if any receiver ready { return ready index }
+    //          deschedule {
+    //              block on all receivers
+    //          }
+    //          unblock on all receivers
+    //          return ready index
+    //      }
+    //
+    // Most notably, the iterations over all of the receivers shouldn't be
+    // necessary.
+
+    // Stage 1: preflight checks. Look for any packets ready to receive
+    if do_preflight_checks {
+        for (id, packet) in packets() {
+            if packet.can_recv() {
+                return Some(id);
+            }
+        }
+    }
+
+    // Stage 2: begin the blocking process
+    //
+    // Create a number of signal tokens, and install each one
+    // sequentially until one fails. If one fails, then abort the
+    // selection on the already-installed tokens.
+    let (wait_token, signal_token) = blocking::tokens();
+    for (i, (id, packet)) in packets().enumerate() {
+        match packet.start_selection(signal_token.clone()) {
+            StartResult::Installed => {}
+            StartResult::Abort => {
+                // Go back and abort the already-begun selections
+                for (_, packet) in packets().take(i) {
+                    packet.abort_selection();
+                }
+                return Some(id);
+            }
+        }
+    }
+
+    // Stage 3: no messages available, actually block
+    let timed_out = match deadline {
+        Some(deadline) => !wait_token.wait_max_until(deadline),
+        None => { wait_token.wait(); false }
+    };
+
+    // Stage 4: there *must* be message available; find it.
+    //
+    // Abort the selection process on each receiver. If the abort
+    // process returns `true`, then that means that the receiver is
+    // ready to receive some data. Note that this also means that the
+    // receiver may have yet to have fully read the `to_wake` field and
+    // woken us up (although the wakeup is guaranteed to fail).
+    //
+    // This situation happens in the window of where a sender invokes
+    // increment(), sees -1, and then decides to wake up the thread. After
+    // all this is done, the sending thread will set `selecting` to
+    // `false`. Until this is done, we cannot return. If we were to
+    // return, then a sender could wake up a receiver which has gone
+    // back to sleep after this call to `select`.
+    //
+    // Note that it is a "fairly small window" in which an increment()
+    // views that it should wake a thread up until the `selecting` bit
+    // is set to false. For now, the implementation currently just spins
+    // in a yield loop. This is very distasteful, but this
+    // implementation is already nowhere near what it should ideally be.
+    // A rewrite should focus on avoiding a yield loop, and for now this
+    // implementation is tying us over to a more efficient "don't
+    // iterate over everything every time" implementation.
+    let mut ready_id = usize::MAX;
+    for (id, packet) in packets() {
+        if packet.abort_selection() {
+            ready_id = id;
+        }
+    }
+
+    // We must have found a ready receiver, unless we gave up waiting.
+    // Even then a message may have raced in while the selection was
+    // being aborted, in which case it's reported as usual.
+    if ready_id == usize::MAX {
+        assert!(timed_out);
+        return None;
+    }
+    return Some(ready_id);
+}
+
 impl Drop for Select {
     fn drop(&mut self) {
         unsafe {
@@ -357,6 +525,7 @@ mod tests {
 
     use thread;
     use sync::mpsc::*;
+    use time::Duration;
 
     // Don't use the libstd version so we can pull in the right Select structure
     // (std::comm points at the wrong one)
@@ -762,4 +931,96 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn wait_timeout() {
+        let (tx1, rx1) = channel::<i32>();
+        let (tx2, rx2) = sync_channel::<i32>(1);
+        let sel = Select::new();
+        let mut h1 = sel.handle(&rx1);
+        let mut h2 = sel.handle(&rx2);
+        unsafe { h1.add(); h2.add(); }
+
+        assert_eq!(sel.wait_timeout(Duration::from_millis(1)), None);
+        tx2.send(2).unwrap();
+        assert_eq!(sel.wait_timeout(Duration::from_millis(1)), Some(h2.id()));
+        assert_eq!(h2.recv().unwrap(), 2);
+        assert_eq!(sel.wait_timeout(Duration::from_millis(1)), None);
+
+        let _t = thread::spawn(move|| {
+            thread::sleep(Duration::from_millis(10));
+            tx1.send(1).unwrap();
+        });
+        assert_eq!(sel.wait_timeout(Duration::from_secs(60)), Some(h1.id()));
+        assert_eq!(h1.recv().unwrap(), 1);
+    }
+    #[test]
+    fn receiver_set() {
+        let (tx1, rx1) = channel::<i32>();
+        let (tx2, rx2) = sync_channel::<String>(1);
+        let mut set = ReceiverSet::new();
+        assert_eq!(set.add(&rx1), 0);
+        assert_eq!(set.add(&rx2), 1);
+
+        tx2.send("hello".to_string()).unwrap();
+        assert_eq!(set.wait(), 1);
+        assert_eq!(rx2.recv().unwrap(), "hello");
+
+        drop(tx1);
+        assert_eq!(set.wait(), 0);
+        assert!(rx1.recv().is_err());
+    }
+
+    #[test]
+    fn receiver_set_unblocks() {
+        let (tx1, rx1) = channel::<i32>();
+        let (_tx2, rx2) = channel::<i32>();
+        let mut set = ReceiverSet::new();
+        set.add(&rx1);
+        set.add(&rx2);
+
+        let _t = thread::spawn(move|| {
+            for _ in 0..20 { thread::yield_now(); }
+            tx1.send(1).unwrap();
+        });
+        assert_eq!(set.wait(), 0);
+        assert_eq!(rx1.recv().unwrap(), 1);
+    }
+
+    #[test]
+    fn receiver_set_wait_timeout() {
+        let (tx1, rx1) = channel::<i32>();
+        let (tx2, rx2) = sync_channel::<i32>(0);
+        let mut set = ReceiverSet::new();
+        set.add(&rx1);
+        set.add(&rx2);
+
+        assert_eq!(set.wait_timeout(Duration::from_millis(1)), None);
+        tx1.send(1).unwrap();
+        assert_eq!(set.wait_timeout(Duration::from_millis(1)), Some(0));
+        assert_eq!(rx1.recv().unwrap(), 1);
+        assert_eq!(set.wait_timeout(Duration::from_millis(1)), None);
+
+        let _t = thread::spawn(move|| {
+            thread::sleep(Duration::from_millis(10));
+            tx2.send(2).unwrap();
+        });
+        assert_eq!(set.wait_timeout(Duration::from_secs(60)), Some(1));
+        assert_eq!(rx2.recv().unwrap(), 2);
+    }
+
+    #[test]
+    fn empty_receiver_set_times_out() {
+        let set = ReceiverSet::new();
+        assert_eq!(set.wait_timeout(Duration::from_millis(1)), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn receiver_set_rejects_duplicates() {
+        let (_tx, rx) = channel::<i32>();
+        let mut set = ReceiverSet::new();
+        set.add(&rx);
+        set.add(&rx);
+    }
 }
@@ -202,6 +202,89 @@ impl FileTypeExt for fs::FileType {
     fn is_socket(&self) -> bool { self.as_inner().is(libc::S_IFSOCK) }
 }
 
+/// Unix-specific extensions to `fs::File`
+#[unstable(feature = "unix_file_ext", reason = "recently added", issue = "0")]
+pub trait FileExt {
+    /// Reads a number of bytes starting from a given offset.
+    ///
+    /// Returns the number of bytes read. The offset is relative to the start
+    /// of the file, and unlike `read` this doesn't use or move the file's
+    /// cursor, so it can be used from several threads at once.
+    ///
+    /// This corresponds to `pread`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_file_ext)]
+    /// use std::fs::File;
+    /// use std::os::unix::fs::FileExt;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let mut buf = [0u8; 8];
+    /// let file = try!(File::open("foo.txt"));
+    ///
+    /// // We now read 8 bytes from the offset 10.
+    /// let num_bytes_read = try!(file.read_at(&mut buf, 10));
+    /// println!("read {} bytes: {:?}", num_bytes_read, buf);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+
+    /// Writes a number of bytes starting from a given offset.
+    ///
+    /// Returns the number of bytes written. The offset is relative to the
+    /// start of the file, and unlike `write` this doesn't use or move the
+    /// file's cursor.
+    ///
+    /// When writing beyond the end of the file, the file is extended and the
+    /// intermediate bytes are filled with zeros. Note that on Linux, if the
+    /// file was opened in append mode, the data is appended regardless of
+    /// `offset`.
+    ///
+    /// This corresponds to `pwrite`.
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize>;
+
+    /// Allocates disk space for the `len` bytes starting at `offset`.
+    ///
+    /// Once this succeeds, writes to that range won't fail for lack of space,
+    /// and the file is at least `offset + len` bytes long.
+    ///
+    /// This corresponds to `fallocate` on Linux, `posix_fallocate` on
+    /// FreeBSD and `F_PREALLOCATE` on OS X. Other platforms return an error.
+    fn allocate(&self, offset: u64, len: u64) -> io::Result<()>;
+
+    /// Copies up to `len` bytes starting at `offset` in this file to `dst`,
+    /// starting at `dst_offset`.
+    ///
+    /// Returns the number of bytes copied, which may be less than `len`, and
+    /// is 0 at the end of this file. Neither file's cursor is used or moved.
+    ///
+    /// On Linux the data is copied by the kernel with `copy_file_range` when
+    /// the two files allow it. Otherwise it goes through a buffer, as with
+    /// `read_at` and `write_at`.
+    fn copy_range(&self, offset: u64, dst: &fs::File, dst_offset: u64,
+                  len: u64) -> io::Result<u64>;
+}
+
+#[unstable(feature = "unix_file_ext", reason = "recently added", issue = "0")]
+impl FileExt for fs::File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        self.as_inner().read_at(buf, offset)
+    }
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        self.as_inner().write_at(buf, offset)
+    }
+    fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        self.as_inner().allocate(offset, len)
+    }
+    fn copy_range(&self, offset: u64, dst: &fs::File, dst_offset: u64,
+                  len: u64) -> io::Result<u64> {
+        self.as_inner().copy_range(offset, dst.as_inner(), dst_offset, len)
+    }
+}
+
 /// Unix-specific extension methods for `fs::DirEntry`
 #[stable(feature = "dir_entry_ext", since = "1.1.0")]
 pub trait DirEntryExt {
@@ -12,7 +12,10 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
-use os::unix::raw::{uid_t, gid_t};
+use prelude::v1::*;
+
+use io;
+use os::unix::raw::{uid_t, gid_t, pid_t};
 use os::unix::io::{FromRawFd, RawFd, AsRawFd, IntoRawFd};
 use process;
 use sys;
@@ -44,6 +47,71 @@ pub trait CommandExt {
     #[unstable(feature = "process_session_leader", reason = "recently added",
                issue = "27811")]
     fn session_leader(&mut self, on: bool) -> &mut process::Command;
+
+    /// Schedules a closure to be run just before the `exec` function is
+    /// invoked.
+    ///
+    /// The closure is allowed to return an I/O error whose OS error code will
+    /// be communicated back to the parent and returned as an error from when
+    /// the spawn was requested.
+    ///
+    /// Multiple closures can be registered and they will be called in order of
+    /// their registration. If a closure returns `Err` then no further closures
+    /// will be called and the spawn operation will immediately return with a
+    /// failure.
+    ///
+    /// # Notes
+    ///
+    /// This closure will be run in the context of the child process after a
+    /// `fork`. This primarily means that any modificatons made to memory on
+    /// behalf of this closure will **not** be visible to the parent process.
+    /// This is often a very constrained environment where normal operations
+    /// like `malloc` or acquiring a mutex are not guaranteed to work (due to
+    /// other threads perhaps still running when the `fork` was run).
+    ///
+    /// When this closure is run, aspects such as the stdio file descriptors and
+    /// working directory have successfully been changed, so output to these
+    /// locations may not appear where intended.
+    #[unstable(feature = "process_exec", reason = "recently added",
+               issue = "0")]
+    fn before_exec<F>(&mut self, f: F) -> &mut process::Command
+        where F: FnMut() -> io::Result<()> + Send + Sync + 'static;
+
+    /// Puts the child process into the process group `pgroup` (cf.
+    /// `setpgid(2)`).
+    ///
+    /// A `pgroup` of 0 creates a new process group whose id is the child's
+    /// process id, so that the child and its descendants can be signalled as
+    /// a group with a negative pid.
+    #[unstable(feature = "process_exec", reason = "recently added",
+               issue = "0")]
+    fn process_group(&mut self, pgroup: pid_t) -> &mut process::Command;
+
+    /// Performs all the required setup by this `Command`, followed by calling
+    /// the `execvp` syscall.
+    ///
+    /// On success this function will not return, and otherwise it will return
+    /// an error indicating why the exec (or another part of the setup of the
+    /// `Command`) failed.
+    ///
+    /// The standard streams of the new program are those of the current
+    /// process: the `stdin`, `stdout` and `stderr` settings of the `Command`
+    /// are not used.
+    ///
+    /// This function, unlike `spawn`, will **not** `fork` the process to
+    /// create a new child.
+    ///
+    /// # Notes
+    ///
+    /// The process may be in a "broken state" if this function returns in
+    /// error. For example the working directory, environment variables,
+    /// signal handling settings, various user/group information, or aspects
+    /// of stdio file descriptors may have changed. If a "transactional spawn"
+    /// is required to gracefully handle errors it is recommended to use the
+    /// cross-platform `spawn` instead.
+    #[unstable(feature = "process_exec", reason = "recently added",
+               issue = "0")]
+    fn exec(&mut self) -> io::Error;
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -62,6 +130,49 @@ impl CommandExt for process::Command {
         self.as_inner_mut().session_leader = on;
         self
     }
+
+    fn before_exec<F>(&mut self, f: F) -> &mut process::Command
+        where F: FnMut() -> io::Result<()> + Send + Sync + 'static
+    {
+        self.as_inner_mut().closures.push(Box::new(f));
+        self
+    }
+
+    fn process_group(&mut self, pgroup: pid_t) -> &mut process::Command {
+        self.as_inner_mut().pgroup = Some(pgroup);
+        self
+    }
+
+    fn exec(&mut self) -> io::Error {
+        use sys::process::{Process, Stdio};
+
+        Process::exec(self.as_inner_mut(), Stdio::Inherit, Stdio::Inherit,
+                      Stdio::Inherit)
+    }
+}
+
+/// Unix-specific extensions to `std::process::Child`
+#[unstable(feature = "process_exec", reason = "recently added",
+           issue = "0")]
+pub trait ChildExt {
+    /// Sends the signal `signal` to the child, as `kill(2)` does.
+    ///
+    /// Like `Child::kill`, this fails without sending anything if the child
+    /// has already exited, so that a process which reused its id isn't
+    /// signalled instead.
+    fn signal(&mut self, signal: i32) -> io::Result<()>;
+}
+
+#[unstable(feature = "process_exec", reason = "recently added",
+           issue = "0")]
+impl ChildExt for process::Child {
+    fn signal(&mut self, signal: i32) -> io::Result<()> {
+        if try!(self.try_wait()).is_some() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "invalid argument: can't signal an exited process"))
+        }
+        unsafe { self.as_inner().signal(signal) }
+    }
 }
 
 /// Unix-specific extensions to `std::process::ExitStatus`
@@ -65,6 +65,13 @@ impl FileDesc {
             debug_assert_eq!(ret, 0);
         }
     }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        unsafe {
+            let mut nonblocking = nonblocking as c_int;
+            cvt(libc::ioctl(self.fd, libc::FIONBIO, &mut nonblocking)).map(|_| ())
+        }
+    }
 }
 
 impl AsInner<c_int> for FileDesc {
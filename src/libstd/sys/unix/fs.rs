@@ -11,6 +11,7 @@
 use io::prelude::*;
 use os::unix::prelude::*;
 
+use cmp;
 use ffi::{CString, CStr, OsString, OsStr};
 use fmt;
 use io::{self, Error, ErrorKind, SeekFrom};
@@ -21,6 +22,7 @@ use ptr;
 use sync::Arc;
 use sys::fd::FileDesc;
 use sys::platform::raw;
+use sys::time::SystemTime;
 use sys::{cvt, cvt_r};
 use sys_common::{AsInner, FromInner};
 use vec::Vec;
@@ -72,6 +74,20 @@ impl FileAttr {
     pub fn file_type(&self) -> FileType {
         FileType { mode: self.stat.st_mode as mode_t }
     }
+
+    pub fn modified(&self) -> io::Result<SystemTime> {
+        Ok(SystemTime::from(libc::timespec {
+            tv_sec: self.stat.st_mtime as libc::time_t,
+            tv_nsec: self.stat.st_mtime_nsec as libc::c_long,
+        }))
+    }
+
+    pub fn accessed(&self) -> io::Result<SystemTime> {
+        Ok(SystemTime::from(libc::timespec {
+            tv_sec: self.stat.st_atime as libc::time_t,
+            tv_nsec: self.stat.st_atime_nsec as libc::c_long,
+        }))
+    }
 }
 
 impl AsInner<raw::stat> for FileAttr {
@@ -333,6 +349,158 @@ impl File {
         Ok(n as u64)
     }
 
+    pub fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        let ret = try!(cvt(unsafe {
+            libc::pread(self.0.raw(),
+                        buf.as_mut_ptr() as *mut libc::c_void,
+                        buf.len() as size_t,
+                        offset as off_t)
+        }));
+        Ok(ret as usize)
+    }
+
+    pub fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        let ret = try!(cvt(unsafe {
+            libc::pwrite(self.0.raw(),
+                         buf.as_ptr() as *const libc::c_void,
+                         buf.len() as size_t,
+                         offset as off_t)
+        }));
+        Ok(ret as usize)
+    }
+
+    pub fn lock(&self, exclusive: bool) -> io::Result<()> {
+        let op = if exclusive {libc::LOCK_EX} else {libc::LOCK_SH};
+        try!(cvt_r(|| unsafe { libc::flock(self.0.raw(), op) }));
+        Ok(())
+    }
+
+    pub fn try_lock(&self, exclusive: bool) -> io::Result<bool> {
+        let op = if exclusive {libc::LOCK_EX} else {libc::LOCK_SH};
+        match cvt_r(|| unsafe { libc::flock(self.0.raw(), op | libc::LOCK_NB) }) {
+            Ok(..) => Ok(true),
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        try!(cvt_r(|| unsafe { libc::flock(self.0.raw(), libc::LOCK_UN) }));
+        Ok(())
+    }
+
+    pub fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        return os_allocate(self, offset as off_t, len as off_t);
+
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        fn os_allocate(f: &File, offset: off_t, len: off_t) -> io::Result<()> {
+            try!(cvt_r(|| unsafe { libc::fallocate(f.0.raw(), 0, offset, len) }));
+            Ok(())
+        }
+        #[cfg(target_os = "freebsd")]
+        fn os_allocate(f: &File, offset: off_t, len: off_t) -> io::Result<()> {
+            // posix_fallocate returns the error instead of setting errno
+            match unsafe { libc::posix_fallocate(f.0.raw(), offset, len) } {
+                0 => Ok(()),
+                e => Err(Error::from_raw_os_error(e)),
+            }
+        }
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        fn os_allocate(f: &File, offset: off_t, len: off_t) -> io::Result<()> {
+            // F_PREALLOCATE only reserves space past the end of the file, so
+            // only the part of the range beyond it needs to be allocated,
+            // after which the file is extended to cover the whole range.
+            let size = try!(f.file_attr()).size() as off_t;
+            let end = offset + len;
+            if end <= size {
+                return Ok(())
+            }
+            let mut store = libc::fstore_t {
+                fst_flags: libc::F_ALLOCATECONTIG,
+                fst_posmode: libc::F_PEOFPOSMODE,
+                fst_offset: 0,
+                fst_length: end - size,
+                fst_bytesalloc: 0,
+            };
+            let ret = unsafe { libc::fcntl(f.0.raw(), libc::F_PREALLOCATE, &store) };
+            if ret == -1 {
+                // Fall back to a non-contiguous allocation
+                store.fst_flags = libc::F_ALLOCATEALL;
+                try!(cvt(unsafe {
+                    libc::fcntl(f.0.raw(), libc::F_PREALLOCATE, &store)
+                }));
+            }
+            f.truncate(end as u64)
+        }
+        #[cfg(not(any(target_os = "linux",
+                      target_os = "android",
+                      target_os = "freebsd",
+                      target_os = "macos",
+                      target_os = "ios")))]
+        fn os_allocate(_f: &File, _offset: off_t, _len: off_t) -> io::Result<()> {
+            Err(Error::new(ErrorKind::Other,
+                           "preallocation is not supported on this platform"))
+        }
+    }
+
+    // Copies up to `len` bytes starting at `offset` in this file to `dst` at
+    // `dst_offset`, without going through userspace. Neither file's cursor is
+    // moved.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn copy_file_range(&self, offset: u64, dst: &File, dst_offset: u64,
+                           len: u64) -> io::Result<u64> {
+        let mut off_in = offset as libc::loff_t;
+        let mut off_out = dst_offset as libc::loff_t;
+        // The kernel never copies more than this in one go, and capping it
+        // keeps the length representable in a `size_t` everywhere.
+        let len = cmp::min(len, 0x7fff_f000) as size_t;
+        let ret = try!(cvt(unsafe {
+            libc::syscall(libc::SYS_copy_file_range,
+                          self.0.raw(), &mut off_in,
+                          dst.0.raw(), &mut off_out,
+                          len, 0)
+        }));
+        Ok(ret as u64)
+    }
+
+    // Returns `None` if `copy_file_range` can't be used for these files.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn try_copy_file_range(&self, offset: u64, dst: &File, dst_offset: u64,
+                           len: u64) -> Option<io::Result<u64>> {
+        match self.copy_file_range(offset, dst, dst_offset, len) {
+            Err(ref e) if copy_file_range_unsupported(e) => None,
+            other => Some(other),
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn try_copy_file_range(&self, _offset: u64, _dst: &File, _dst_offset: u64,
+                           _len: u64) -> Option<io::Result<u64>> {
+        None
+    }
+
+    pub fn copy_range(&self, offset: u64, dst: &File, dst_offset: u64,
+                      len: u64) -> io::Result<u64> {
+        if let Some(ret) = self.try_copy_file_range(offset, dst, dst_offset, len) {
+            return ret
+        }
+
+        let mut buf = [0; 8 * 1024];
+        let amt = cmp::min(len, buf.len() as u64) as usize;
+        let n = try!(self.read_at(&mut buf[..amt], offset));
+        let mut written = 0;
+        while written < n {
+            match dst.write_at(&buf[written..n], dst_offset + written as u64) {
+                Ok(0) => return Err(Error::new(ErrorKind::WriteZero,
+                                               "failed to write whole buffer")),
+                Ok(m) => written += m,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(n as u64)
+    }
+
     pub fn fd(&self) -> &FileDesc { &self.0 }
 
     pub fn into_fd(self) -> FileDesc { self.0 }
@@ -553,7 +721,49 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     let mut writer = try!(File::create(to));
     let perm = try!(reader.metadata()).permissions();
 
-    let ret = try!(io::copy(&mut reader, &mut writer));
+    let ret = try!(copy_contents(&mut reader, &mut writer));
     try!(set_permissions(to, perm));
     Ok(ret)
 }
+
+// Copies all of `reader` into `writer`, having the kernel do it when it can.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn copy_contents(reader: &mut ::fs::File, writer: &mut ::fs::File) -> io::Result<u64> {
+    let mut written = 0;
+    loop {
+        let ret = reader.as_inner().copy_file_range(written, writer.as_inner(),
+                                                    written, 1 << 30);
+        match ret {
+            // Files such as those of procfs and sysfs report a size of zero,
+            // which makes `copy_file_range` copy nothing from them, so an
+            // empty first copy is retried by reading the file instead.
+            Ok(0) if written == 0 => break,
+            Ok(0) => return Ok(written),
+            Ok(n) => written += n,
+            // Nothing has been copied yet, so the cursors are still at the
+            // start of both files and the slow path can take over.
+            Err(ref e) if written == 0 && copy_file_range_unsupported(e) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    io::copy(reader, writer)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn copy_contents(reader: &mut ::fs::File, writer: &mut ::fs::File) -> io::Result<u64> {
+    io::copy(reader, writer)
+}
+
+// Whether `copy_file_range` failed because it can't be used for this pair of
+// files (or at all), rather than because of an I/O error.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn copy_file_range_unsupported(e: &io::Error) -> bool {
+    match e.raw_os_error() {
+        Some(libc::ENOSYS) |
+        Some(libc::EXDEV) |
+        Some(libc::EINVAL) |
+        Some(libc::EPERM) |
+        Some(libc::EOPNOTSUPP) => true,
+        _ => false,
+    }
+}
@@ -29,7 +29,6 @@ use sys::{self, cvt, cvt_r};
 // Command
 ////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Clone)]
 pub struct Command {
     pub program: CString,
     pub args: Vec<CString>,
@@ -38,6 +37,8 @@ pub struct Command {
     pub uid: Option<uid_t>,
     pub gid: Option<gid_t>,
     pub session_leader: bool,
+    pub pgroup: Option<pid_t>,
+    pub closures: Vec<Box<FnMut() -> io::Result<()> + Send + Sync>>,
 }
 
 impl Command {
@@ -50,6 +51,8 @@ impl Command {
             uid: None,
             gid: None,
             session_leader: false,
+            pgroup: None,
+            closures: Vec::new(),
         }
     }
 
@@ -163,11 +166,15 @@ const CLOEXEC_MSG_FOOTER: &'static [u8] = b"NOEX";
 
 impl Process {
     pub unsafe fn kill(&self) -> io::Result<()> {
-        try!(cvt(libc::kill(self.pid, libc::SIGKILL)));
+        self.signal(libc::SIGKILL)
+    }
+
+    pub unsafe fn signal(&self, signal: c_int) -> io::Result<()> {
+        try!(cvt(libc::kill(self.pid, signal)));
         Ok(())
     }
 
-    pub fn spawn(cfg: &Command,
+    pub fn spawn(cfg: &mut Command,
                  in_fd: Stdio,
                  out_fd: Stdio,
                  err_fd: Stdio) -> io::Result<Process> {
@@ -181,8 +188,9 @@ impl Process {
             match libc::fork() {
                 0 => {
                     drop(input);
-                    Process::child_after_fork(cfg, output, argv, envp, dirp,
-                                              in_fd, out_fd, err_fd)
+                    let err = Process::do_exec(cfg, argv, envp, dirp,
+                                               in_fd, out_fd, err_fd);
+                    Process::report_exec_error(output, err)
                 }
                 n if n < 0 => return Err(Error::last_os_error()),
                 n => n,
@@ -259,31 +267,54 @@ impl Process {
     // allocation). Instead we just close it manually. This will never
     // have the drop glue anyway because this code never returns (the
     // child will either exec() or invoke libc::exit)
-    unsafe fn child_after_fork(cfg: &Command,
-                               mut output: AnonPipe,
-                               argv: *const *const libc::c_char,
-                               envp: *const libc::c_void,
-                               dirp: *const libc::c_char,
-                               in_fd: Stdio,
-                               out_fd: Stdio,
-                               err_fd: Stdio) -> ! {
-        fn fail(output: &mut AnonPipe) -> ! {
-            let errno = sys::os::errno() as u32;
-            let bytes = [
-                (errno >> 24) as u8,
-                (errno >> 16) as u8,
-                (errno >>  8) as u8,
-                (errno >>  0) as u8,
-                CLOEXEC_MSG_FOOTER[0], CLOEXEC_MSG_FOOTER[1],
-                CLOEXEC_MSG_FOOTER[2], CLOEXEC_MSG_FOOTER[3]
-            ];
-            // pipe I/O up to PIPE_BUF bytes should be atomic, and then we want
-            // to be sure we *don't* run at_exit destructors as we're being torn
-            // down regardless
-            assert!(output.write(&bytes).is_ok());
-            unsafe { libc::_exit(1) }
+    //
+    // The closures registered through `CommandExt::before_exec` are the one
+    // exception: they run here and may do whatever they like, at their own
+    // risk.
+    unsafe fn report_exec_error(mut output: AnonPipe, err: io::Error) -> ! {
+        let errno = err.raw_os_error().unwrap_or(libc::EINVAL) as u32;
+        let bytes = [
+            (errno >> 24) as u8,
+            (errno >> 16) as u8,
+            (errno >>  8) as u8,
+            (errno >>  0) as u8,
+            CLOEXEC_MSG_FOOTER[0], CLOEXEC_MSG_FOOTER[1],
+            CLOEXEC_MSG_FOOTER[2], CLOEXEC_MSG_FOOTER[3]
+        ];
+        // pipe I/O up to PIPE_BUF bytes should be atomic, and then we want
+        // to be sure we *don't* run at_exit destructors as we're being torn
+        // down regardless
+        assert!(output.write(&bytes).is_ok());
+        libc::_exit(1)
+    }
+
+    /// Replaces the current process image with the one described by `cfg`,
+    /// returning the error if that fails.
+    pub fn exec(cfg: &mut Command, in_fd: Stdio, out_fd: Stdio,
+                err_fd: Stdio) -> io::Error {
+        let dirp = cfg.cwd.as_ref().map(|c| c.as_ptr()).unwrap_or(ptr::null());
+
+        let (envp, _a, _b) = make_envp(cfg.env.as_ref());
+        let (argv, _a) = make_argv(&cfg.program, &cfg.args);
+
+        unsafe {
+            // `do_exec` points `environ` at `envp`, which is freed when this
+            // function returns, so put the old environment back if the exec
+            // didn't happen.
+            let old_environ = *sys::os::environ();
+            let err = Process::do_exec(cfg, argv, envp, dirp, in_fd, out_fd, err_fd);
+            *sys::os::environ() = old_environ;
+            err
         }
+    }
 
+    unsafe fn do_exec(cfg: &mut Command,
+                      argv: *const *const libc::c_char,
+                      envp: *const libc::c_void,
+                      dirp: *const libc::c_char,
+                      in_fd: Stdio,
+                      out_fd: Stdio,
+                      err_fd: Stdio) -> io::Error {
         let setup = |src: Stdio, dst: c_int| {
             match src {
                 Stdio::Inherit => true,
@@ -309,13 +340,13 @@ impl Process {
             }
         };
 
-        if !setup(in_fd, libc::STDIN_FILENO) { fail(&mut output) }
-        if !setup(out_fd, libc::STDOUT_FILENO) { fail(&mut output) }
-        if !setup(err_fd, libc::STDERR_FILENO) { fail(&mut output) }
+        if !setup(in_fd, libc::STDIN_FILENO) { return io::Error::last_os_error() }
+        if !setup(out_fd, libc::STDOUT_FILENO) { return io::Error::last_os_error() }
+        if !setup(err_fd, libc::STDERR_FILENO) { return io::Error::last_os_error() }
 
         if let Some(u) = cfg.gid {
             if libc::setgid(u as libc::gid_t) != 0 {
-                fail(&mut output);
+                return io::Error::last_os_error();
             }
         }
         if let Some(u) = cfg.uid {
@@ -329,7 +360,7 @@ impl Process {
             let _ = libc::setgroups(0, ptr::null());
 
             if libc::setuid(u as libc::uid_t) != 0 {
-                fail(&mut output);
+                return io::Error::last_os_error();
             }
         }
         if cfg.session_leader {
@@ -338,15 +369,20 @@ impl Process {
             // error, but ignore it anyway.
             let _ = libc::setsid();
         }
+        if let Some(pgroup) = cfg.pgroup {
+            if libc::setpgid(0, pgroup) != 0 {
+                return io::Error::last_os_error();
+            }
+        }
         if !dirp.is_null() && libc::chdir(dirp) == -1 {
-            fail(&mut output);
+            return io::Error::last_os_error();
         }
         if !envp.is_null() {
             *sys::os::environ() = envp as *const _;
         }
 
         #[cfg(not(target_os = "nacl"))]
-        unsafe fn reset_signal_handling(output: &mut AnonPipe) {
+        unsafe fn reset_signal_handling() -> io::Result<()> {
             use mem;
             // Reset signal handling so the child process starts in a
             // standardized state. libstd ignores SIGPIPE, and signal-handling
@@ -362,17 +398,27 @@ impl Process {
                     libc::SIGPIPE, mem::transmute(libc::SIG_DFL)
                         ) == mem::transmute(libc::SIG_ERR)
             {
-                fail(output);
+                return Err(io::Error::last_os_error());
             }
+            Ok(())
         }
         #[cfg(target_os = "nacl")]
-        unsafe fn reset_signal_handling(_output: &mut AnonPipe) {
+        unsafe fn reset_signal_handling() -> io::Result<()> {
             // NaCl has no signal support.
+            Ok(())
+        }
+        if let Err(e) = reset_signal_handling() {
+            return e;
+        }
+
+        for callback in cfg.closures.iter_mut() {
+            if let Err(e) = callback() {
+                return e;
+            }
         }
-        reset_signal_handling(&mut output);
 
         let _ = libc::execvp(*argv, argv);
-        fail(&mut output)
+        io::Error::last_os_error()
     }
 
     pub fn id(&self) -> u32 {
@@ -385,15 +431,15 @@ impl Process {
         Ok(ExitStatus(status))
     }
 
-    pub fn try_wait(&self) -> Option<ExitStatus> {
+    pub fn try_wait(&self) -> io::Result<Option<ExitStatus>> {
         let mut status = 0 as c_int;
-        match cvt_r(|| unsafe {
+        let pid = try!(cvt_r(|| unsafe {
             libc::waitpid(self.pid, &mut status, libc::WNOHANG)
-        }) {
-            Ok(0) => None,
-            Ok(n) if n == self.pid => Some(ExitStatus(status)),
-            Ok(n) => panic!("unknown pid: {}", n),
-            Err(e) => panic!("unknown waitpid error: {}", e),
+        }));
+        if pid == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(ExitStatus(status)))
         }
     }
 }
@@ -493,7 +539,7 @@ mod tests {
     fn test_process_mask() {
         unsafe {
             // Test to make sure that a signal mask does not get inherited.
-            let cmd = Command::new(OsStr::new("cat"));
+            let mut cmd = Command::new(OsStr::new("cat"));
             let (stdin_read, stdin_write) = t!(sys::pipe::anon_pipe());
             let (stdout_read, stdout_write) = t!(sys::pipe::anon_pipe());
 
@@ -503,7 +549,7 @@ mod tests {
             t!(cvt(sigaddset(&mut set, libc::SIGINT)));
             t!(cvt(libc::pthread_sigmask(libc::SIG_SETMASK, &set, &mut old_set)));
 
-            let cat = t!(Process::spawn(&cmd, Stdio::Raw(stdin_read.raw()),
+            let cat = t!(Process::spawn(&mut cmd, Stdio::Raw(stdin_read.raw()),
                                               Stdio::Raw(stdout_write.raw()),
                                               Stdio::None));
             drop(stdin_read);
@@ -107,9 +107,9 @@ pub trait MetadataExt {
 #[stable(feature = "metadata_ext", since = "1.1.0")]
 impl MetadataExt for Metadata {
     fn file_attributes(&self) -> u32 { self.as_inner().attrs() }
-    fn creation_time(&self) -> u64 { self.as_inner().created() }
-    fn last_access_time(&self) -> u64 { self.as_inner().accessed() }
-    fn last_write_time(&self) -> u64 { self.as_inner().modified() }
+    fn creation_time(&self) -> u64 { self.as_inner().created_u64() }
+    fn last_access_time(&self) -> u64 { self.as_inner().accessed_u64() }
+    fn last_write_time(&self) -> u64 { self.as_inner().modified_u64() }
     fn file_size(&self) -> u64 { self.as_inner().size() }
 }
 
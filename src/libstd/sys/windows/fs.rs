@@ -20,6 +20,7 @@ use ptr;
 use slice;
 use sync::Arc;
 use sys::handle::Handle;
+use sys::time::SystemTime;
 use sys::{c, cvt};
 use sys_common::FromInner;
 use vec::Vec;
@@ -315,6 +316,41 @@ impl File {
         Ok(newpos as u64)
     }
 
+    pub fn lock(&self, exclusive: bool) -> io::Result<()> {
+        let flags = if exclusive {c::LOCKFILE_EXCLUSIVE_LOCK} else {0};
+        self.lock_file(flags)
+    }
+
+    pub fn try_lock(&self, exclusive: bool) -> io::Result<bool> {
+        let flags = if exclusive {c::LOCKFILE_EXCLUSIVE_LOCK} else {0};
+        match self.lock_file(flags | c::LOCKFILE_FAIL_IMMEDIATELY) {
+            Ok(()) => Ok(true),
+            Err(ref e) if e.raw_os_error() ==
+                          Some(c::ERROR_LOCK_VIOLATION as i32) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        unsafe {
+            let mut overlapped: c::OVERLAPPED = mem::zeroed();
+            try!(cvt(c::UnlockFileEx(self.handle.raw(), 0, !0, !0,
+                                     &mut overlapped)));
+        }
+        Ok(())
+    }
+
+    // Locks the whole file, which is expressed as the largest possible range
+    // starting at offset 0.
+    fn lock_file(&self, flags: c::DWORD) -> io::Result<()> {
+        unsafe {
+            let mut overlapped: c::OVERLAPPED = mem::zeroed();
+            try!(cvt(c::LockFileEx(self.handle.raw(), flags, 0, !0, !0,
+                                   &mut overlapped)));
+        }
+        Ok(())
+    }
+
     pub fn handle(&self) -> &Handle { &self.handle }
 
     pub fn into_handle(self) -> Handle { self.handle }
@@ -396,9 +432,17 @@ impl FileAttr {
         FileType::new(self.data.dwFileAttributes, self.reparse_tag)
     }
 
-    pub fn created(&self) -> u64 { self.to_u64(&self.data.ftCreationTime) }
-    pub fn accessed(&self) -> u64 { self.to_u64(&self.data.ftLastAccessTime) }
-    pub fn modified(&self) -> u64 { self.to_u64(&self.data.ftLastWriteTime) }
+    pub fn modified(&self) -> io::Result<SystemTime> {
+        Ok(SystemTime::from(self.data.ftLastWriteTime))
+    }
+
+    pub fn accessed(&self) -> io::Result<SystemTime> {
+        Ok(SystemTime::from(self.data.ftLastAccessTime))
+    }
+
+    pub fn created_u64(&self) -> u64 { self.to_u64(&self.data.ftCreationTime) }
+    pub fn accessed_u64(&self) -> u64 { self.to_u64(&self.data.ftLastAccessTime) }
+    pub fn modified_u64(&self) -> u64 { self.to_u64(&self.data.ftLastWriteTime) }
 
     fn to_u64(&self, ft: &c::FILETIME) -> u64 {
         (ft.dwLowDateTime as u64) | ((ft.dwHighDateTime as u64) << 32)
@@ -210,6 +210,19 @@ impl Process {
         }
     }
 
+    pub fn try_wait(&self) -> io::Result<Option<ExitStatus>> {
+        unsafe {
+            match c::WaitForSingleObject(self.handle.raw(), 0) {
+                c::WAIT_OBJECT_0 => {}
+                c::WAIT_TIMEOUT => return Ok(None),
+                _ => return Err(Error::last_os_error()),
+            }
+            let mut status = 0;
+            try!(cvt(c::GetExitCodeProcess(self.handle.raw(), &mut status)));
+            Ok(Some(ExitStatus(status)))
+        }
+    }
+
     pub fn handle(&self) -> &Handle { &self.handle }
 
     pub fn into_handle(self) -> Handle { self.handle }
@@ -8,23 +8,178 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use ops::Sub;
+use cmp::Ordering;
+use fmt;
+use mem;
 use sync::Once;
 use sys::c;
+use sys::cvt;
 use time::Duration;
 
 const NANOS_PER_SEC: u64 = 1_000_000_000;
+const INTERVALS_PER_SEC: u64 = NANOS_PER_SEC / 100;
 
-pub struct SteadyTime {
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Instant {
     t: c::LARGE_INTEGER,
 }
 
-impl SteadyTime {
-    pub fn now() -> SteadyTime {
-        let mut t = SteadyTime { t: 0 };
-        unsafe { c::QueryPerformanceCounter(&mut t.t); }
+#[derive(Copy, Clone)]
+pub struct SystemTime {
+    t: c::FILETIME,
+}
+
+// A FILETIME counts 100ns intervals since 1601-01-01, and this is the
+// number of them up to 1970-01-01.
+const INTERVALS_TO_UNIX_EPOCH: u64 = 11_644_473_600 * INTERVALS_PER_SEC;
+
+pub const UNIX_EPOCH: SystemTime = SystemTime {
+    t: c::FILETIME {
+        dwLowDateTime: INTERVALS_TO_UNIX_EPOCH as u32,
+        dwHighDateTime: (INTERVALS_TO_UNIX_EPOCH >> 32) as u32,
+    },
+};
+
+impl Instant {
+    pub fn now() -> Instant {
+        let mut t = Instant { t: 0 };
+        cvt(unsafe {
+            c::QueryPerformanceCounter(&mut t.t)
+        }).unwrap();
         t
     }
+
+    pub fn sub_instant(&self, other: &Instant) -> Duration {
+        // Values which are +- 1 need to be considered as basically the same
+        // units in time due to various measurement oddities, according to
+        // Windows [1]
+        //
+        // [1]:
+        // https://msdn.microsoft.com/en-us/library/windows/desktop
+        //                           /dn553408%28v=vs.85%29.aspx#guidance
+        if other.t > self.t && other.t - self.t == 1 {
+            return Duration::new(0, 0)
+        }
+        let diff = (self.t as u64).checked_sub(other.t as u64)
+                                  .expect("specified instant was later than \
+                                           self");
+        let nanos = mul_div_u64(diff, NANOS_PER_SEC, frequency() as u64);
+        Duration::new(nanos / NANOS_PER_SEC, (nanos % NANOS_PER_SEC) as u32)
+    }
+
+    pub fn checked_add_duration(&self, other: &Duration) -> Option<Instant> {
+        dur2ticks(other).and_then(|ticks| (self.t as i64).checked_add(ticks))
+                        .map(|t| Instant { t: t as c::LARGE_INTEGER })
+    }
+
+    pub fn checked_sub_duration(&self, other: &Duration) -> Option<Instant> {
+        dur2ticks(other).and_then(|ticks| (self.t as i64).checked_sub(ticks))
+                        .map(|t| Instant { t: t as c::LARGE_INTEGER })
+    }
+}
+
+impl SystemTime {
+    pub fn now() -> SystemTime {
+        unsafe {
+            let mut t: SystemTime = mem::zeroed();
+            c::GetSystemTimeAsFileTime(&mut t.t);
+            t
+        }
+    }
+
+    fn from_intervals(intervals: i64) -> SystemTime {
+        SystemTime {
+            t: c::FILETIME {
+                dwLowDateTime: intervals as c::DWORD,
+                dwHighDateTime: (intervals >> 32) as c::DWORD,
+            }
+        }
+    }
+
+    fn intervals(&self) -> i64 {
+        (self.t.dwLowDateTime as i64) | ((self.t.dwHighDateTime as i64) << 32)
+    }
+
+    pub fn sub_time(&self, other: &SystemTime) -> Result<Duration, Duration> {
+        let me = self.intervals();
+        let other = other.intervals();
+        if me >= other {
+            Ok(intervals2dur((me - other) as u64))
+        } else {
+            Err(intervals2dur((other - me) as u64))
+        }
+    }
+
+    pub fn checked_add_duration(&self, other: &Duration) -> Option<SystemTime> {
+        dur2intervals(other).and_then(|d| self.intervals().checked_add(d))
+                            .map(SystemTime::from_intervals)
+    }
+
+    pub fn checked_sub_duration(&self, other: &Duration) -> Option<SystemTime> {
+        dur2intervals(other).and_then(|d| self.intervals().checked_sub(d))
+                            .map(SystemTime::from_intervals)
+    }
+}
+
+impl PartialEq for SystemTime {
+    fn eq(&self, other: &SystemTime) -> bool {
+        self.intervals() == other.intervals()
+    }
+}
+
+impl Eq for SystemTime {}
+
+impl PartialOrd for SystemTime {
+    fn partial_cmp(&self, other: &SystemTime) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SystemTime {
+    fn cmp(&self, other: &SystemTime) -> Ordering {
+        self.intervals().cmp(&other.intervals())
+    }
+}
+
+impl fmt::Debug for SystemTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SystemTime")
+         .field("intervals", &self.intervals())
+         .finish()
+    }
+}
+
+impl From<c::FILETIME> for SystemTime {
+    fn from(t: c::FILETIME) -> SystemTime {
+        SystemTime { t: t }
+    }
+}
+
+// Converts a duration to a number of 100ns intervals, or `None` on
+// overflow.
+fn dur2intervals(d: &Duration) -> Option<i64> {
+    d.as_secs().checked_mul(INTERVALS_PER_SEC).and_then(|i| {
+        i.checked_add(d.subsec_nanos() as u64 / 100)
+    }).and_then(|i| {
+        if i <= i64::max_value() as u64 { Some(i as i64) } else { None }
+    })
+}
+
+fn intervals2dur(intervals: u64) -> Duration {
+    Duration::new(intervals / INTERVALS_PER_SEC,
+                  ((intervals % INTERVALS_PER_SEC) * 100) as u32)
+}
+
+// Converts a duration to a number of performance counter ticks, or `None`
+// on overflow.
+fn dur2ticks(d: &Duration) -> Option<i64> {
+    d.as_secs().checked_mul(NANOS_PER_SEC).and_then(|nanos| {
+        nanos.checked_add(d.subsec_nanos() as u64)
+    }).map(|nanos| {
+        mul_div_u64(nanos, frequency() as u64, NANOS_PER_SEC)
+    }).and_then(|ticks| {
+        if ticks <= i64::max_value() as u64 { Some(ticks as i64) } else { None }
+    })
 }
 
 fn frequency() -> c::LARGE_INTEGER {
@@ -33,22 +188,12 @@ fn frequency() -> c::LARGE_INTEGER {
 
     unsafe {
         ONCE.call_once(|| {
-            c::QueryPerformanceFrequency(&mut FREQUENCY);
+            cvt(c::QueryPerformanceFrequency(&mut FREQUENCY)).unwrap();
         });
         FREQUENCY
     }
 }
 
-impl<'a> Sub for &'a SteadyTime {
-    type Output = Duration;
-
-    fn sub(self, other: &SteadyTime) -> Duration {
-        let diff = self.t as u64 - other.t as u64;
-        let nanos = mul_div_u64(diff, NANOS_PER_SEC, frequency() as u64);
-        Duration::new(nanos / NANOS_PER_SEC, (nanos % NANOS_PER_SEC) as u32)
-    }
-}
-
 // Computes (value*numer)/denom without overflow, as long as both
 // (numer*denom) and the overall result fit into i64 (which is the case
 // for our time conversions).
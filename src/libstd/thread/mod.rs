@@ -99,6 +99,25 @@
 //! });
 //! ```
 //!
+//! ## Scoped threads
+//!
+//! Threads started with `spawn` may outlive their parent, so they can only
+//! borrow `'static` data. The `scope` function creates a scope in which
+//! threads may borrow from the parent's stack instead; every thread spawned
+//! in it is joined before `scope` returns:
+//!
+//! ```rust
+//! #![feature(scoped_threads)]
+//! use std::thread;
+//!
+//! let data = vec![1, 2, 3];
+//! thread::scope(|s| {
+//!     for x in &data {
+//!         s.spawn(move || println!("{}", x));
+//!     }
+//! });
+//! ```
+//!
 //! ## Blocking support: park and unpark
 //!
 //! Every thread is equipped with some basic low-level blocking support, via the
@@ -166,6 +185,7 @@ use any::Any;
 use cell::UnsafeCell;
 use fmt;
 use io;
+use panic;
 use sync::{Mutex, Condvar, Arc};
 use sys::thread as imp;
 use sys_common::thread_info;
@@ -192,6 +212,16 @@ pub use self::scoped_tls::ScopedKey;
 #[doc(hidden)] pub use self::local::__KeyInner as __LocalKeyInner;
 #[doc(hidden)] pub use self::scoped_tls::__KeyInner as __ScopedKeyInner;
 
+////////////////////////////////////////////////////////////////////////////////
+// Scoped threads
+////////////////////////////////////////////////////////////////////////////////
+
+mod scoped;
+
+#[unstable(feature = "scoped_threads", reason = "recently added", issue = "0")]
+pub use self::scoped::{scope, Scope, ScopedJoinHandle};
+use self::scoped::ScopeData;
+
 ////////////////////////////////////////////////////////////////////////////////
 // Builder
 ////////////////////////////////////////////////////////////////////////////////
@@ -247,6 +277,17 @@ impl Builder {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn spawn<F, T>(self, f: F) -> io::Result<JoinHandle<T>> where
         F: FnOnce() -> T, F: Send + 'static, T: Send + 'static
+    {
+        unsafe { self.spawn_unchecked(f, None).map(JoinHandle) }
+    }
+
+    // Spawns a thread running `f`, which is allowed to borrow data that lives
+    // for `'a`. This is unsafe because the caller has to make sure that the
+    // thread is joined before `'a` ends. Threads spawned on behalf of a
+    // `Scope` report back to it through `scope` once they're finished.
+    unsafe fn spawn_unchecked<'a, F, T>(self, f: F, scope: Option<Arc<ScopeData>>)
+                                        -> io::Result<JoinInner<T>> where
+        F: FnOnce() -> T, F: Send + 'a, T: Send + 'a
     {
         let Builder { name, stack_size } = self;
 
@@ -255,34 +296,35 @@ impl Builder {
         let my_thread = Thread::new(name);
         let their_thread = my_thread.clone();
 
-        let my_packet : Arc<UnsafeCell<Option<Result<T>>>>
-            = Arc::new(UnsafeCell::new(None));
+        if let Some(ref scope) = scope {
+            scope.increment_num_running_threads();
+        }
+        let my_packet = Arc::new(Packet {
+            scope: scope,
+            result: UnsafeCell::new(None),
+        });
         let their_packet = my_packet.clone();
 
         let main = move || {
             if let Some(name) = their_thread.name() {
                 imp::Thread::set_name(name);
             }
-            unsafe {
-                thread_info::set(imp::guard::current(), their_thread);
-                let mut output = None;
-                let try_result = {
-                    let ptr = &mut output;
-                    unwind::try(move || *ptr = Some(f()))
-                };
-                *their_packet.get() = Some(try_result.map(|()| {
-                    output.unwrap()
-                }));
-            }
+            thread_info::set(imp::guard::current(), their_thread);
+            let mut output = None;
+            let try_result = {
+                let ptr = &mut output;
+                unwind::try(move || *ptr = Some(f()))
+            };
+            *their_packet.result.get() = Some(try_result.map(|()| {
+                output.unwrap()
+            }));
         };
 
-        Ok(JoinHandle(JoinInner {
-            native: unsafe {
-                Some(try!(imp::Thread::new(stack_size, Box::new(main))))
-            },
+        Ok(JoinInner {
+            native: Some(try!(imp::Thread::new(stack_size, Box::new(main)))),
             thread: my_thread,
-            packet: Packet(my_packet),
-        }))
+            packet: my_packet,
+        })
     }
 }
 
@@ -372,15 +414,11 @@ pub fn panicking() -> bool {
 /// ```
 #[unstable(feature = "catch_panic", reason = "recent API addition",
            issue = "27719")]
+#[deprecated(since = "1.6.0", reason = "replaced by `std::panic::catch_unwind`")]
 pub fn catch_panic<F, R>(f: F) -> Result<R>
     where F: FnOnce() -> R + Send + 'static
 {
-    let mut result = None;
-    unsafe {
-        let result = &mut result;
-        try!(unwind::try(move || *result = Some(f())))
-    }
-    Ok(result.unwrap())
+    panic::catch_unwind(panic::AssertUnwindSafe(f))
 }
 
 /// Puts the current thread to sleep for the specified amount of time.
@@ -560,34 +598,57 @@ impl thread_info::NewThread for Thread {
 pub type Result<T> = ::result::Result<T, Box<Any + Send + 'static>>;
 
 // This packet is used to communicate the return value between the child thread
-// and the parent thread. Memory is shared through the `Arc` within and there's
-// no need for a mutex here because synchronization happens with `join()` (the
-// parent thread never reads this packet until the child has exited).
+// and the parent thread. Memory is shared through the `Arc` that holds it and
+// there's no need for a mutex here because synchronization happens with
+// `join()` (the parent thread never reads this packet until the child has
+// exited).
 //
 // This packet itself is then stored into a `JoinInner` which in turns is placed
-// in `JoinHandle` and `JoinGuard`. Due to the usage of `UnsafeCell` we need to
-// manually worry about impls like Send and Sync. The type `T` should
-// already always be Send (otherwise the thread could not have been created) and
-// this type is inherently Sync because no methods take &self. Regardless,
-// however, we add inheriting impls for Send/Sync to this type to ensure it's
-// Send/Sync and that future modifications will still appropriately classify it.
-struct Packet<T>(Arc<UnsafeCell<Option<Result<T>>>>);
+// in `JoinHandle` and `ScopedJoinHandle`. Due to the usage of `UnsafeCell` we
+// need to manually worry about impls like Send and Sync. The type `T` should
+// already always be Send (otherwise the thread could not have been created),
+// and the packet is Sync because the result is only ever accessed by one
+// thread at a time: first the child writes it, then whoever joins the child
+// takes it.
+//
+// A thread spawned in a `Scope` also keeps the scope's data here. The scope
+// learns that the thread is done, and whether it ended with a panic that
+// nobody looked at, when the packet is dropped.
+struct Packet<T> {
+    scope: Option<Arc<ScopeData>>,
+    result: UnsafeCell<Option<Result<T>>>,
+}
 
 unsafe impl<T: Send> Send for Packet<T> {}
-unsafe impl<T: Sync> Sync for Packet<T> {}
+unsafe impl<T: Send> Sync for Packet<T> {}
+
+impl<T> Drop for Packet<T> {
+    fn drop(&mut self) {
+        let unhandled_panic = match unsafe { &*self.result.get() } {
+            &Some(Err(_)) => true,
+            _ => false,
+        };
+        // The result may borrow from the environment of a scope, so it has to
+        // go before the scope is told that this thread is done.
+        unsafe { *self.result.get() = None; }
+        if let Some(ref scope) = self.scope {
+            scope.decrement_num_running_threads(unhandled_panic);
+        }
+    }
+}
 
 /// Inner representation for JoinHandle
 struct JoinInner<T> {
     native: Option<imp::Thread>,
     thread: Thread,
-    packet: Packet<T>,
+    packet: Arc<Packet<T>>,
 }
 
 impl<T> JoinInner<T> {
     fn join(&mut self) -> Result<T> {
         self.native.take().unwrap().join();
         unsafe {
-            (*self.packet.0.get()).take().unwrap()
+            (*self.packet.result.get()).take().unwrap()
         }
     }
 }
@@ -0,0 +1,363 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Scoped threads, which may borrow from the stack of the thread that
+//! spawned them.
+
+use prelude::v1::*;
+
+use fmt;
+use io;
+use marker::PhantomData;
+use panic;
+use sync::Arc;
+use sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use super::{current, park, Builder, JoinInner, Result, Thread};
+
+/// A scope to spawn scoped threads in.
+///
+/// See `scope` for details.
+#[unstable(feature = "scoped_threads", reason = "recently added", issue = "0")]
+pub struct Scope<'env> {
+    data: Arc<ScopeData>,
+    // Invariant over `'env`, so that the scope can't be coerced into one that
+    // allows borrowing data which doesn't live as long.
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+pub struct ScopeData {
+    num_running_threads: AtomicUsize,
+    a_thread_panicked: AtomicBool,
+    main_thread: Thread,
+}
+
+impl ScopeData {
+    pub fn increment_num_running_threads(&self) {
+        // We check for 'overflow' with usize::MAX / 2, to make sure there's no
+        // chance it overflows to 0, which would result in unsoundness.
+        if self.num_running_threads.fetch_add(1, Ordering::Relaxed) > ::usize::MAX / 2 {
+            // This can only reasonably happen by mem::forget()'ing many many
+            // ScopedJoinHandles.
+            self.decrement_num_running_threads(false);
+            panic!("too many running threads in thread scope");
+        }
+    }
+
+    pub fn decrement_num_running_threads(&self, panic: bool) {
+        if panic {
+            self.a_thread_panicked.store(true, Ordering::Relaxed);
+        }
+        if self.num_running_threads.fetch_sub(1, Ordering::Release) == 1 {
+            self.main_thread.unpark();
+        }
+    }
+}
+
+/// Creates a scope for spawning scoped threads.
+///
+/// The function passed to `scope` will be provided a `Scope` object, through
+/// which scoped threads can be spawned.
+///
+/// Unlike non-scoped threads, scoped threads can borrow non-`'static` data,
+/// as the scope guarantees all threads will be joined at the end of the scope.
+///
+/// All threads spawned within the scope that haven't been manually joined
+/// will be automatically joined before this function returns, even if the
+/// function passed to it panics.
+///
+/// # Panics
+///
+/// If any of the automatically joined threads panicked, this function will
+/// panic. If the function passed to `scope` panics, that panic is propagated
+/// once all threads have been joined.
+///
+/// If you want to handle panics from spawned threads, `join` them before the
+/// end of the scope.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(scoped_threads)]
+///
+/// use std::thread;
+///
+/// let mut a = vec![1, 2, 3];
+/// let mut x = 0;
+///
+/// thread::scope(|s| {
+///     s.spawn(|| {
+///         println!("hello from the first scoped thread");
+///         // We can borrow `a` here.
+///         println!("{:?}", a);
+///     });
+///     s.spawn(|| {
+///         println!("hello from the second scoped thread");
+///         // We can even mutably borrow `x` here,
+///         // because no other threads are using it.
+///         x += a[0] + a[2];
+///     });
+///     println!("hello from the main thread");
+/// });
+///
+/// // After the scope, we can modify and access our variables again:
+/// a.push(4);
+/// assert_eq!(x, a.len());
+/// ```
+#[unstable(feature = "scoped_threads", reason = "recently added", issue = "0")]
+pub fn scope<'env, F, T>(f: F) -> T
+    where F: FnOnce(&Scope<'env>) -> T
+{
+    let scope = Scope {
+        data: Arc::new(ScopeData {
+            num_running_threads: AtomicUsize::new(0),
+            a_thread_panicked: AtomicBool::new(false),
+            main_thread: current(),
+        }),
+        env: PhantomData,
+    };
+
+    // Run `f`, but catch panics so we can make sure to wait for all the
+    // threads to join.
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| f(&scope)));
+
+    // Wait until all the threads are finished.
+    while scope.data.num_running_threads.load(Ordering::Acquire) != 0 {
+        park();
+    }
+
+    // Throw any panic from `f`, or the return value of `f` if no thread
+    // panicked.
+    match result {
+        Err(e) => panic::resume_unwind(e),
+        Ok(_) if scope.data.a_thread_panicked.load(Ordering::Relaxed) => {
+            panic!("a scoped thread panicked")
+        }
+        Ok(result) => result,
+    }
+}
+
+impl<'env> Scope<'env> {
+    /// Spawns a new thread within a scope, returning a `ScopedJoinHandle` for
+    /// it.
+    ///
+    /// Unlike non-scoped threads, threads spawned with this function may
+    /// borrow non-`'static` data from outside the scope. See `scope` for
+    /// details.
+    ///
+    /// The join handle provides a `join` method that can be used to join the
+    /// spawned thread. If the spawned thread panics, `join` will return an
+    /// `Err` containing the panic payload.
+    ///
+    /// If the join handle is dropped, the spawned thread will implicitly be
+    /// joined at the end of the scope. In that case, if the spawned thread
+    /// panics, `scope` will panic after all threads are joined.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OS fails to create a thread; use `Builder::spawn_scoped`
+    /// to recover from such errors.
+    #[unstable(feature = "scoped_threads", reason = "recently added", issue = "0")]
+    pub fn spawn<'scope, F, T>(&'scope self, f: F) -> ScopedJoinHandle<'scope, T>
+        where F: FnOnce() -> T, F: Send + 'env, T: Send + 'env
+    {
+        Builder::new().spawn_scoped(self, f).expect("failed to spawn thread")
+    }
+}
+
+impl Builder {
+    /// Spawns a new scoped thread using the settings set through this
+    /// `Builder`.
+    ///
+    /// Unlike `Scope::spawn`, this method yields an `io::Result` to capture
+    /// any failure to create the thread at the OS level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(scoped_threads)]
+    ///
+    /// use std::thread;
+    ///
+    /// let mut a = vec![1, 2, 3];
+    /// let mut x = 0;
+    ///
+    /// thread::scope(|s| {
+    ///     thread::Builder::new()
+    ///         .name("first".to_string())
+    ///         .spawn_scoped(s, || {
+    ///             println!("hello from the {:?} scoped thread",
+    ///                      thread::current().name());
+    ///             // We can borrow `a` here.
+    ///             println!("{:?}", a);
+    ///         })
+    ///         .unwrap();
+    ///     thread::Builder::new()
+    ///         .name("second".to_string())
+    ///         .spawn_scoped(s, || {
+    ///             println!("hello from the {:?} scoped thread",
+    ///                      thread::current().name());
+    ///             // We can even mutably borrow `x` here,
+    ///             // because no other threads are using it.
+    ///             x += a[0] + a[2];
+    ///         })
+    ///         .unwrap();
+    ///     println!("hello from the main thread");
+    /// });
+    ///
+    /// // After the scope, we can modify and access our variables again:
+    /// a.push(4);
+    /// assert_eq!(x, a.len());
+    /// ```
+    #[unstable(feature = "scoped_threads", reason = "recently added", issue = "0")]
+    pub fn spawn_scoped<'scope, 'env, F, T>(self, scope: &'scope Scope<'env>, f: F)
+                                            -> io::Result<ScopedJoinHandle<'scope, T>>
+        where F: FnOnce() -> T, F: Send + 'env, T: Send + 'env
+    {
+        // This is safe because `scope` doesn't return before every thread
+        // spawned in it has finished, and `'env` outlives that call.
+        let inner = try!(unsafe {
+            self.spawn_unchecked(f, Some(scope.data.clone()))
+        });
+        Ok(ScopedJoinHandle { inner: inner, scope: PhantomData })
+    }
+}
+
+/// An owned permission to join on a scoped thread (block on its termination).
+///
+/// See `Scope::spawn` for details.
+#[unstable(feature = "scoped_threads", reason = "recently added", issue = "0")]
+pub struct ScopedJoinHandle<'scope, T> {
+    inner: JoinInner<T>,
+    scope: PhantomData<&'scope ()>,
+}
+
+impl<'scope, T> ScopedJoinHandle<'scope, T> {
+    /// Extracts a handle to the underlying thread.
+    #[unstable(feature = "scoped_threads", reason = "recently added", issue = "0")]
+    pub fn thread(&self) -> &Thread {
+        &self.inner.thread
+    }
+
+    /// Waits for the associated thread to finish.
+    ///
+    /// If the associated thread panics, `Err` is returned with the panic
+    /// payload, and the panic is not propagated by `scope`.
+    #[unstable(feature = "scoped_threads", reason = "recently added", issue = "0")]
+    pub fn join(mut self) -> Result<T> {
+        self.inner.join()
+    }
+}
+
+#[unstable(feature = "scoped_threads", reason = "recently added", issue = "0")]
+impl<'env> fmt::Debug for Scope<'env> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Scope")
+         .field("num_running_threads",
+                &self.data.num_running_threads.load(Ordering::Relaxed))
+         .field("a_thread_panicked",
+                &self.data.a_thread_panicked.load(Ordering::Relaxed))
+         .field("main_thread", &self.data.main_thread)
+         .finish()
+    }
+}
+
+#[unstable(feature = "scoped_threads", reason = "recently added", issue = "0")]
+impl<'scope, T> fmt::Debug for ScopedJoinHandle<'scope, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ScopedJoinHandle").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude::v1::*;
+
+    use sync::atomic::{AtomicUsize, Ordering};
+    use sync::mpsc::channel;
+    use thread::{self, Builder};
+    use time::Duration;
+
+    #[test]
+    fn borrow_from_parent() {
+        let data = vec![1, 2, 3, 4];
+        let total = AtomicUsize::new(0);
+        thread::scope(|s| {
+            for x in &data {
+                let total = &total;
+                s.spawn(move || { total.fetch_add(*x, Ordering::SeqCst); });
+            }
+        });
+        assert_eq!(total.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn mutable_borrow() {
+        let mut a = 0;
+        let mut b = 0;
+        thread::scope(|s| {
+            s.spawn(|| a += 1);
+            s.spawn(|| b += 2);
+        });
+        assert_eq!((a, b), (1, 2));
+    }
+
+    #[test]
+    fn join_returns_value() {
+        let v = vec![1, 2, 3];
+        let sum = thread::scope(|s| {
+            let h = s.spawn(|| v.iter().fold(0, |a, b| a + b));
+            h.join().unwrap()
+        });
+        assert_eq!(sum, 6);
+    }
+
+    #[test]
+    fn joined_panic_is_handled() {
+        thread::scope(|s| {
+            let h = s.spawn(|| panic!("oops"));
+            assert!(h.join().is_err());
+        });
+    }
+
+    #[test]
+    fn unjoined_panic_propagates() {
+        let r = thread::spawn(|| {
+            thread::scope(|s| {
+                s.spawn(|| panic!("oops"));
+            });
+        }).join();
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn scope_waits_for_threads() {
+        let (tx, rx) = channel();
+        thread::scope(|s| {
+            s.spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                tx.send(()).unwrap();
+            });
+        });
+        rx.try_recv().unwrap();
+    }
+
+    #[test]
+    fn builder_spawn_scoped() {
+        let name = "scoped".to_string();
+        thread::scope(|s| {
+            let h = Builder::new().name(name.clone()).stack_size(64 * 1024)
+                                  .spawn_scoped(s, || {
+                assert_eq!(thread::current().name(), Some(&name[..]));
+            }).unwrap();
+            assert_eq!(h.thread().name(), Some("scoped"));
+            h.join().unwrap();
+        });
+    }
+}
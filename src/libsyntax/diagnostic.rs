@@ -17,9 +17,11 @@ use codemap::{self, COMMAND_LINE_SP, COMMAND_LINE_EXPN, Pos, Span};
 use diagnostics;
 
 use std::cell::{RefCell, Cell};
-use std::{cmp, error, fmt};
+use std::{cmp, error, fmt, mem};
 use std::io::prelude::*;
 use std::io;
+use std::iter::repeat;
+use std::rc::Rc;
 use term::{self, WriterWrapper};
 
 /// maximum number of lines we will print for each error; arbitrary.
@@ -62,6 +64,48 @@ impl RenderSpan {
     }
 }
 
+/// An edit of the source code which a diagnostic suggests: the text
+/// covered by `span` is to be replaced with `replacement`.
+///
+/// Every `RenderSpan::Suggestion` passed to a `Handler` is recorded as
+/// one of these, so that they can be applied to the source files (see
+/// `suggestions::apply_suggestions`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CodeSuggestion {
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// One of the spans of a diagnostic rendered as a snippet (see
+/// `Emitter::emit_snippet`), together with the label printed under it.
+///
+/// Primary spans are what the diagnostic is about and are underlined with
+/// `^`; the others give context, such as a previous borrow or an earlier
+/// definition, and are underlined with `-`.
+#[derive(Clone, Debug)]
+pub struct SpanLabel {
+    pub span: Span,
+    pub is_primary: bool,
+    pub label: Option<String>,
+}
+
+impl SpanLabel {
+    pub fn primary(span: Span, label: Option<String>) -> SpanLabel {
+        SpanLabel { span: span, is_primary: true, label: label }
+    }
+
+    pub fn secondary(span: Span, label: Option<String>) -> SpanLabel {
+        SpanLabel { span: span, is_primary: false, label: label }
+    }
+}
+
+/// The span a snippet diagnostic is reported at: its first primary span,
+/// or its first span if none is primary.
+pub fn primary_span(spans: &[SpanLabel]) -> Span {
+    assert!(!spans.is_empty());
+    spans.iter().find(|s| s.is_primary).unwrap_or(&spans[0]).span
+}
+
 #[derive(Clone, Copy)]
 pub enum ColorConfig {
     Auto,
@@ -74,6 +118,19 @@ pub trait Emitter {
             msg: &str, code: Option<&str>, lvl: Level);
     fn custom_emit(&mut self, cm: &codemap::CodeMap,
                    sp: RenderSpan, msg: &str, lvl: Level);
+
+    /// Emits a diagnostic pointing at several labelled spans at once.
+    /// Emitters which cannot show them together print the diagnostic at
+    /// its primary span, followed by a note for each label.
+    fn emit_snippet(&mut self, cm: &codemap::CodeMap, spans: &[SpanLabel],
+                    msg: &str, code: Option<&str>, lvl: Level) {
+        self.emit(Some((cm, primary_span(spans))), msg, code, lvl);
+        for span_label in spans {
+            if let Some(ref label) = span_label.label {
+                self.emit(Some((cm, span_label.span)), label, None, Note);
+            }
+        }
+    }
 }
 
 /// Used as a return value to signify a fatal error occurred. (It is also
@@ -164,6 +221,17 @@ impl SpanHandler {
     pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String) {
         self.handler.custom_emit(&self.cm, Suggestion(sp, suggestion), msg, Help);
     }
+    /// Reports an error pointing at all of `spans` at once.
+    ///
+    /// See `SpanLabel` for more information.
+    pub fn labelled_span_err(&self, spans: &[SpanLabel], msg: &str) {
+        self.handler.emit_snippet(&self.cm, spans, msg, None, Error);
+        self.handler.bump_err_count();
+    }
+    pub fn labelled_span_err_with_code(&self, spans: &[SpanLabel], msg: &str, code: &str) {
+        self.handler.emit_snippet(&self.cm, spans, msg, Some(code), Error);
+        self.handler.bump_err_count();
+    }
     pub fn fileline_note(&self, sp: Span, msg: &str) {
         self.handler.custom_emit(&self.cm, FileLine(sp), msg, Note);
     }
@@ -188,6 +256,7 @@ impl SpanHandler {
 pub struct Handler {
     err_count: Cell<usize>,
     emit: RefCell<Box<Emitter + Send>>,
+    suggestions: RefCell<Vec<CodeSuggestion>>,
     pub can_emit_warnings: bool
 }
 
@@ -202,6 +271,7 @@ impl Handler {
         Handler {
             err_count: Cell::new(0),
             emit: RefCell::new(e),
+            suggestions: RefCell::new(Vec::new()),
             can_emit_warnings: can_emit_warnings
         }
     }
@@ -269,8 +339,23 @@ impl Handler {
     pub fn custom_emit(&self, cm: &codemap::CodeMap,
                        sp: RenderSpan, msg: &str, lvl: Level) {
         if lvl == Warning && !self.can_emit_warnings { return }
+        if let Suggestion(span, ref replacement) = sp {
+            self.suggestions.borrow_mut().push(CodeSuggestion {
+                span: span,
+                replacement: replacement.clone(),
+            });
+        }
         self.emit.borrow_mut().custom_emit(cm, sp, msg, lvl);
     }
+    pub fn emit_snippet(&self, cm: &codemap::CodeMap,
+                        spans: &[SpanLabel], msg: &str, code: Option<&str>, lvl: Level) {
+        if lvl == Warning && !self.can_emit_warnings { return }
+        self.emit.borrow_mut().emit_snippet(cm, spans, msg, code, lvl);
+    }
+    /// Returns the suggestions made so far, and forgets them.
+    pub fn take_suggestions(&self) -> Vec<CodeSuggestion> {
+        mem::replace(&mut *self.suggestions.borrow_mut(), Vec::new())
+    }
 }
 
 #[derive(Copy, PartialEq, Clone, Debug)]
@@ -454,11 +539,15 @@ impl EmitterWriter {
             }
         }
 
+        self.print_explanation_help(&ss[..], code)
+    }
+
+    fn print_explanation_help(&mut self, topic: &str, code: Option<&str>) -> io::Result<()> {
         match code {
             Some(code) =>
                 match self.registry.as_ref().and_then(|registry| registry.find_description(code)) {
                     Some(_) => {
-                        try!(self.print_diagnostic(&ss[..], Help,
+                        try!(self.print_diagnostic(topic, Help,
                                                    &format!("run `rustc --explain {}` to see a \
                                                              detailed explanation", code), None));
                     }
@@ -469,6 +558,173 @@ impl EmitterWriter {
         Ok(())
     }
 
+    fn emit_snippet_(&mut self, cm: &codemap::CodeMap, spans: &[SpanLabel],
+                     msg: &str, code: Option<&str>, lvl: Level) -> io::Result<()> {
+        let sp = primary_span(spans);
+        let ss = if sp.expn_id == COMMAND_LINE_EXPN {
+            "<command line option>".to_string()
+        } else {
+            cm.span_to_string(sp)
+        };
+
+        try!(self.print_diagnostic(&ss[..], lvl, msg, code));
+        try!(self.render_snippet(cm, spans, lvl));
+        try!(self.print_macro_backtrace(cm, sp));
+        self.print_explanation_help(&ss[..], code)
+    }
+
+    /// Renders `spans` as an excerpt of each file they are in. The lines
+    /// the spans start on are printed after a gutter holding their line
+    /// numbers, and each span is underlined and followed by its label.
+    /// A span covering several lines is underlined up to the end of its
+    /// first line.
+    fn render_snippet(&mut self,
+                      cm: &codemap::CodeMap,
+                      spans: &[SpanLabel],
+                      lvl: Level)
+                      -> io::Result<()> {
+        // Group the annotations by file, in the order the files appear in.
+        let mut files: Vec<(Rc<codemap::FileMap>, Vec<Annotation>)> = Vec::new();
+        for span_label in spans {
+            let sp = span_label.span;
+            if sp.expn_id == COMMAND_LINE_EXPN || sp.lo > sp.hi {
+                continue;
+            }
+            let lo = cm.lookup_char_pos(sp.lo);
+            let hi = cm.lookup_char_pos(sp.hi);
+            let line_index = lo.line - 1;
+            let end_col = if hi.line == lo.line {
+                hi.col.to_usize()
+            } else {
+                lo.file.get_line(line_index).map(|l| l.chars().count()).unwrap_or(0)
+            };
+            let annotation = Annotation {
+                line_index: line_index,
+                start_col: lo.col.to_usize(),
+                // Empty spans are still pointed at.
+                end_col: cmp::max(end_col, lo.col.to_usize() + 1),
+                is_primary: span_label.is_primary,
+                label: span_label.label.clone(),
+            };
+            match files.iter().position(|&(ref fm, _)| fm.name == lo.file.name) {
+                Some(i) => files[i].1.push(annotation),
+                None => files.push((lo.file.clone(), vec![annotation])),
+            }
+        }
+
+        let max_line_num = files.iter()
+                                .flat_map(|&(_, ref annotations)| annotations.iter())
+                                .map(|a| a.line_index + 1)
+                                .max()
+                                .unwrap_or(0);
+        let width = max_line_num.to_string().len();
+        let gutter_style = term::attr::ForegroundColor(term::color::BRIGHT_BLUE);
+
+        for (i, &(ref fm, ref annotations)) in files.iter().enumerate() {
+            if i > 0 {
+                try!(println_maybe_styled!(self, gutter_style, "{:1$}:::", "", width));
+                try!(write!(&mut self.dst, " {}\n", fm.name));
+            }
+            try!(println_maybe_styled!(self, gutter_style, "{:1$} |", "", width));
+
+            let mut line_indices: Vec<usize> = annotations.iter().map(|a| a.line_index).collect();
+            line_indices.sort();
+            line_indices.dedup();
+
+            let mut prev_line_index = None;
+            for &line_index in &line_indices {
+                if let Some(prev) = prev_line_index {
+                    if line_index > prev + 1 {
+                        try!(println_maybe_styled!(self, gutter_style, "..."));
+                    }
+                }
+                prev_line_index = Some(line_index);
+
+                let source = fm.get_line(line_index).unwrap_or("");
+                try!(print_maybe_styled!(self, gutter_style, "{:>1$} | ", line_index + 1, width));
+                try!(write!(&mut self.dst, "{}\n", source));
+
+                let mut line_annotations: Vec<&Annotation> =
+                    annotations.iter().filter(|a| a.line_index == line_index).collect();
+                line_annotations.sort_by(|a, b| a.start_col.cmp(&b.start_col));
+                try!(self.render_annotations(source, &line_annotations, width, lvl));
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the underlines and labels of the annotations of a line,
+    /// which are sorted by column:
+    ///
+    /// ```text
+    ///   |     foo(&mut x, &x);
+    ///   |         ^^^^^^  -- immutable borrow occurs here
+    ///   |         |
+    ///   |         mutable borrow occurs here
+    /// ```
+    fn render_annotations(&mut self,
+                          source: &str,
+                          annotations: &[&Annotation],
+                          width: usize,
+                          lvl: Level)
+                          -> io::Result<()> {
+        let gutter_style = term::attr::ForegroundColor(term::color::BRIGHT_BLUE);
+        let style = |a: &Annotation| {
+            if a.is_primary {
+                term::attr::ForegroundColor(lvl.color())
+            } else {
+                gutter_style
+            }
+        };
+
+        // The underlines, with the label of the last annotation after them.
+        try!(print_maybe_styled!(self, gutter_style, "{:1$} | ", "", width));
+        let mut col = 0;
+        for a in annotations {
+            // Overlapping parts of spans are only underlined once.
+            let start_col = cmp::max(a.start_col, col);
+            if start_col >= a.end_col {
+                continue;
+            }
+            try!(write!(&mut self.dst, "{}", padding(source, col, start_col)));
+            let marker = if a.is_primary { "^" } else { "-" };
+            try!(print_maybe_styled!(self, style(*a), "{}",
+                                     repeat(marker).take(a.end_col - start_col)
+                                                   .collect::<String>()));
+            col = a.end_col;
+        }
+        let last = annotations.len() - 1;
+        if let Some(ref label) = annotations[last].label {
+            try!(print_maybe_styled!(self, style(annotations[last]), " {}", label));
+        }
+        try!(write!(&mut self.dst, "\n"));
+
+        // The other labels, from right to left, each hanging below the start
+        // of its span, with the spans still to be labelled to its left.
+        let pending: Vec<&Annotation> = annotations[..last].iter()
+                                                          .filter(|a| a.label.is_some())
+                                                          .cloned()
+                                                          .collect();
+        for i in (0..pending.len()).rev() {
+            for &is_label_line in &[false, true] {
+                try!(print_maybe_styled!(self, gutter_style, "{:1$} | ", "", width));
+                let mut col = 0;
+                for (j, a) in pending[..i + 1].iter().enumerate() {
+                    try!(write!(&mut self.dst, "{}", padding(source, col, a.start_col)));
+                    if j == i && is_label_line {
+                        try!(print_maybe_styled!(self, style(*a), "{}",
+                                                 a.label.as_ref().unwrap()));
+                    } else {
+                        try!(print_maybe_styled!(self, style(*a), "|"));
+                    }
+                    col = a.start_col + 1;
+                }
+                try!(write!(&mut self.dst, "\n"));
+            }
+        }
+        Ok(())
+    }
+
     fn highlight_suggestion(&mut self,
                             cm: &codemap::CodeMap,
                             sp: Span,
@@ -764,6 +1020,28 @@ impl EmitterWriter {
     }
 }
 
+/// A `SpanLabel` as placed on the line its span starts on.
+struct Annotation {
+    line_index: usize,
+    start_col: usize,
+    end_col: usize,
+    is_primary: bool,
+    label: Option<String>,
+}
+
+/// Returns the whitespace which moves from column `from` of `line` to
+/// column `to`, keeping the tabs of the line so that the result lines up
+/// with it whatever the tab width is.
+fn padding(line: &str, from: usize, to: usize) -> String {
+    let mut chars = line.chars().skip(from);
+    (from..to).map(|_| {
+        match chars.next() {
+            Some('\t') => '\t',
+            _ => ' ',
+        }
+    }).collect()
+}
+
 #[cfg(unix)]
 fn stderr_isatty() -> bool {
     use libc;
@@ -827,6 +1105,14 @@ impl Emitter for EmitterWriter {
             Err(e) => panic!("failed to print diagnostics: {:?}", e),
         }
     }
+
+    fn emit_snippet(&mut self, cm: &codemap::CodeMap, spans: &[SpanLabel],
+                    msg: &str, code: Option<&str>, lvl: Level) {
+        match self.emit_snippet_(cm, spans, msg, code, lvl) {
+            Ok(()) => {}
+            Err(e) => panic!("failed to print diagnostics: {:?}", e),
+        }
+    }
 }
 
 pub fn expect<T, M>(diag: &SpanHandler, opt: Option<T>, msg: M) -> T where
@@ -840,22 +1126,23 @@ pub fn expect<T, M>(diag: &SpanHandler, opt: Option<T>, msg: M) -> T where
 
 #[cfg(test)]
 mod test {
-    use super::{EmitterWriter, Level};
-    use codemap::{mk_sp, CodeMap};
+    use super::{EmitterWriter, Emitter, Level, SpanLabel};
+    use codemap::{mk_sp, BytePos, CodeMap};
     use std::sync::{Arc, Mutex};
     use std::io::{self, Write};
     use std::str::from_utf8;
 
+    struct Sink(Arc<Mutex<Vec<u8>>>);
+    impl Write for Sink {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            Write::write(&mut *self.0.lock().unwrap(), data)
+        }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
     // Diagnostic doesn't align properly in span where line number increases by one digit
     #[test]
     fn test_hilight_suggestion_issue_11715() {
-        struct Sink(Arc<Mutex<Vec<u8>>>);
-        impl Write for Sink {
-            fn write(&mut self, data: &[u8]) -> io::Result<usize> {
-                Write::write(&mut *self.0.lock().unwrap(), data)
-            }
-            fn flush(&mut self) -> io::Result<()> { Ok(()) }
-        }
         let data = Arc::new(Mutex::new(Vec::new()));
         let mut ew = EmitterWriter::new(Box::new(Sink(data.clone())), None);
         let cm = CodeMap::new();
@@ -893,4 +1180,30 @@ mod test {
                          dummy.txt:11         e-lä-vän\n\
                          dummy.txt:12         tolv\n");
     }
+
+    #[test]
+    fn test_snippet() {
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let mut ew = EmitterWriter::new(Box::new(Sink(data.clone())), None);
+        let cm = CodeMap::new();
+        let content = "fn main() {\n    let x = 1;\n    let y = x + x;\n}\n";
+        cm.new_filemap_and_lines("dummy.rs", content);
+        let span = |lo, hi| mk_sp(BytePos(lo), BytePos(hi));
+        let spans = [
+            SpanLabel::primary(span(43, 44), Some("second use".to_string())),
+            SpanLabel::secondary(span(20, 21), Some("defined here".to_string())),
+            SpanLabel::secondary(span(39, 40), Some("first use".to_string())),
+        ];
+        ew.emit_snippet(&cm, &spans, "test", None, Level::Error);
+        let vec = data.lock().unwrap().clone();
+        let str = from_utf8(&vec).unwrap();
+        assert_eq!(str, "dummy.rs:3:17: 3:18 error: test\n  \
+                         |\n\
+                         2 |     let x = 1;\n  \
+                         |         - defined here\n\
+                         3 |     let y = x + x;\n  \
+                         |             -   ^ second use\n  \
+                         |             |\n  \
+                         |             first use\n");
+    }
 }
@@ -30,6 +30,14 @@ macro_rules! span_err {
     })
 }
 
+#[macro_export]
+macro_rules! labelled_span_err {
+    ($session:expr, $spans:expr, $code:ident, $($message:tt)*) => ({
+        __diagnostic_used!($code);
+        $session.labelled_span_err_with_code($spans, &format!($($message)*), stringify!($code))
+    })
+}
+
 #[macro_export]
 macro_rules! span_err_or_warn {
     ($is_warning:expr, $session:expr, $span:expr, $code:ident, $($message:tt)*) => ({
@@ -0,0 +1,332 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A JSON emitter for errors.
+//!
+//! This works by converting errors to a simplified structural format (see the
+//! structs at the start of the file) and then serialising them. These should
+//! contain as much information about the error as possible.
+//!
+//! Notes and help messages are attached as `children` of the error or warning
+//! which precedes them, so each top-level JSON object corresponds to a single
+//! diagnostic together with everything the compiler had to say about it.
+//!
+//! The format of the JSON output should be considered *unstable*. For now the
+//! structs at the start of this file specify the error format.
+
+use codemap::{self, CodeMap, Span, COMMAND_LINE_EXPN};
+use diagnostic::{Emitter, Level, RenderSpan, FullSpan, EndSpan, Suggestion, FileLine};
+use diagnostic::{SpanLabel, primary_span};
+use diagnostic::{Bug, Fatal, Error, Warning, Note, Help};
+use diagnostics::registry::Registry;
+
+use std::io::{self, Write};
+use serialize::json::as_json;
+
+// The following data types are provided just for serialisation.
+
+#[derive(RustcEncodable)]
+struct Diagnostic {
+    /// The primary error message.
+    message: String,
+    code: Option<DiagnosticCode>,
+    /// "error: internal compiler error", "error", "warning", "note", "help".
+    level: String,
+    spans: Vec<DiagnosticSpan>,
+    /// Associated diagnostic messages (notes and help).
+    children: Vec<Diagnostic>,
+}
+
+#[derive(RustcEncodable)]
+struct DiagnosticSpan {
+    file_name: String,
+    byte_start: u32,
+    byte_end: u32,
+    /// 1-based.
+    line_start: usize,
+    line_end: usize,
+    /// 1-based, character offset.
+    column_start: usize,
+    column_end: usize,
+    /// Whether this is the span the diagnostic is about, as opposed to a
+    /// macro call site through which it was reached.
+    is_primary: bool,
+    /// If this span is the subject of a suggestion, the text that should
+    /// replace it.
+    suggested_replacement: Option<String>,
+    /// The label of this span, if the diagnostic points at several.
+    label: Option<String>,
+}
+
+#[derive(RustcEncodable)]
+struct DiagnosticCode {
+    /// The code itself.
+    code: String,
+    /// An explanation for the code.
+    explanation: Option<&'static str>,
+}
+
+pub struct JsonEmitter {
+    dst: Box<Write + Send>,
+    registry: Option<Registry>,
+    /// The most recent error or warning. It is held back until the next one
+    /// arrives so that any notes and help messages can be attached to it.
+    pending: Option<Diagnostic>,
+}
+
+impl JsonEmitter {
+    pub fn basic() -> JsonEmitter {
+        JsonEmitter::stderr(None)
+    }
+
+    pub fn stderr(registry: Option<Registry>) -> JsonEmitter {
+        JsonEmitter::new(Box::new(io::stderr()), registry)
+    }
+
+    pub fn new(dst: Box<Write + Send>, registry: Option<Registry>) -> JsonEmitter {
+        JsonEmitter {
+            dst: dst,
+            registry: registry,
+            pending: None,
+        }
+    }
+
+    fn push(&mut self, diag: Diagnostic, lvl: Level) {
+        match lvl {
+            Note | Help => {
+                if let Some(ref mut parent) = self.pending {
+                    parent.children.push(diag);
+                    return;
+                }
+                self.write(&diag);
+            }
+            Warning | Error => {
+                self.flush_pending();
+                self.pending = Some(diag);
+            }
+            // These are followed by a panic, so there is nothing to attach
+            // to them and they must not be held back.
+            Bug | Fatal => {
+                self.flush_pending();
+                self.write(&diag);
+            }
+        }
+    }
+
+    fn flush_pending(&mut self) {
+        if let Some(diag) = self.pending.take() {
+            self.write(&diag);
+        }
+    }
+
+    fn write(&mut self, diag: &Diagnostic) {
+        if let Err(e) = writeln!(&mut self.dst, "{}", as_json(diag)) {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+}
+
+impl Emitter for JsonEmitter {
+    fn emit(&mut self,
+            cmsp: Option<(&CodeMap, Span)>,
+            msg: &str,
+            code: Option<&str>,
+            lvl: Level) {
+        let spans = match cmsp {
+            Some((cm, sp)) => DiagnosticSpan::from_render_span(cm, &FullSpan(sp)),
+            None => vec![],
+        };
+        let diag = Diagnostic::new(msg, code, lvl, spans, self);
+        self.push(diag, lvl);
+    }
+
+    fn custom_emit(&mut self,
+                   cm: &CodeMap,
+                   sp: RenderSpan,
+                   msg: &str,
+                   lvl: Level) {
+        let spans = DiagnosticSpan::from_render_span(cm, &sp);
+        let diag = Diagnostic::new(msg, None, lvl, spans, self);
+        self.push(diag, lvl);
+    }
+
+    fn emit_snippet(&mut self,
+                    cm: &CodeMap,
+                    spans: &[SpanLabel],
+                    msg: &str,
+                    code: Option<&str>,
+                    lvl: Level) {
+        let primary = primary_span(spans);
+        let mut diag_spans = DiagnosticSpan::from_render_span(cm, &FullSpan(primary));
+        for span_label in spans {
+            if span_label.span.expn_id == COMMAND_LINE_EXPN {
+                continue;
+            }
+            if span_label.span == primary && !diag_spans.is_empty() {
+                diag_spans[0].label = span_label.label.clone();
+                continue;
+            }
+            let mut diag_span = DiagnosticSpan::from_span(cm, span_label.span,
+                                                          span_label.is_primary, None);
+            diag_span.label = span_label.label.clone();
+            diag_spans.push(diag_span);
+        }
+        let diag = Diagnostic::new(msg, code, lvl, diag_spans, self);
+        self.push(diag, lvl);
+    }
+}
+
+impl Drop for JsonEmitter {
+    fn drop(&mut self) {
+        self.flush_pending();
+    }
+}
+
+impl Diagnostic {
+    fn new(msg: &str,
+           code: Option<&str>,
+           lvl: Level,
+           spans: Vec<DiagnosticSpan>,
+           je: &JsonEmitter)
+           -> Diagnostic {
+        Diagnostic {
+            message: msg.to_owned(),
+            code: DiagnosticCode::map_opt_string(code.map(|c| c.to_owned()), je),
+            level: lvl.to_string(),
+            spans: spans,
+            children: vec![],
+        }
+    }
+}
+
+impl DiagnosticSpan {
+    fn from_render_span(cm: &CodeMap, rsp: &RenderSpan) -> Vec<DiagnosticSpan> {
+        let (sp, replacement) = match *rsp {
+            FullSpan(sp) | FileLine(sp) => (sp, None),
+            EndSpan(sp) => (Span { lo: sp.hi, hi: sp.hi, expn_id: sp.expn_id }, None),
+            Suggestion(sp, ref s) => (sp, Some(s.clone())),
+        };
+
+        // We cannot check equality directly with COMMAND_LINE_SP
+        // since PartialEq is manually implemented to ignore the ExpnId
+        if sp.expn_id == COMMAND_LINE_EXPN {
+            return vec![];
+        }
+
+        let mut spans = vec![DiagnosticSpan::from_span(cm, sp, true, replacement)];
+
+        // Record the chain of macro call sites as secondary spans.
+        let mut last_span = codemap::DUMMY_SP;
+        let mut sp_opt = Some(sp);
+        while let Some(sp) = sp_opt {
+            sp_opt = cm.with_expn_info(sp.expn_id, |expn_info| {
+                expn_info.map(|ei| ei.call_site)
+            });
+            if let Some(call_site) = sp_opt {
+                // Don't record recursive invocations
+                if call_site != last_span {
+                    last_span = call_site;
+                    spans.push(DiagnosticSpan::from_span(cm, call_site, false, None));
+                }
+            }
+        }
+
+        spans
+    }
+
+    fn from_span(cm: &CodeMap,
+                 sp: Span,
+                 is_primary: bool,
+                 suggested_replacement: Option<String>)
+                 -> DiagnosticSpan {
+        let start = cm.lookup_char_pos(sp.lo);
+        let end = cm.lookup_char_pos(sp.hi);
+        let byte_start = cm.lookup_byte_offset(sp.lo).pos;
+        let byte_end = cm.lookup_byte_offset(sp.hi).pos;
+        DiagnosticSpan {
+            file_name: start.file.name.clone(),
+            byte_start: byte_start.0,
+            byte_end: byte_end.0,
+            line_start: start.line,
+            line_end: end.line,
+            column_start: start.col.0 + 1,
+            column_end: end.col.0 + 1,
+            is_primary: is_primary,
+            suggested_replacement: suggested_replacement,
+            label: None,
+        }
+    }
+}
+
+impl DiagnosticCode {
+    fn map_opt_string(s: Option<String>, je: &JsonEmitter) -> Option<DiagnosticCode> {
+        s.map(|s| {
+            let explanation = je.registry
+                                .as_ref()
+                                .and_then(|registry| registry.find_description(&s));
+
+            DiagnosticCode {
+                code: s,
+                explanation: explanation,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::JsonEmitter;
+    use codemap::{mk_sp, CodeMap, BytePos};
+    use diagnostic::{Emitter, Level};
+    use serialize::json;
+    use std::sync::{Arc, Mutex};
+    use std::io::{self, Write};
+    use std::str::from_utf8;
+
+    struct Sink(Arc<Mutex<Vec<u8>>>);
+    impl Write for Sink {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            Write::write(&mut *self.0.lock().unwrap(), data)
+        }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn test_notes_attach_to_error() {
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let cm = CodeMap::new();
+        cm.new_filemap_and_lines("dummy.rs", "fn main() {\n    let x = 1;\n}\n");
+        {
+            let mut je = JsonEmitter::new(Box::new(Sink(data.clone())), None);
+            let sp = mk_sp(BytePos(20), BytePos(21));
+            je.emit(Some((&cm, sp)), "unused variable", Some("E0000"), Level::Warning);
+            je.emit(None, "consider removing it", None, Level::Help);
+            je.emit(None, "a second diagnostic", None, Level::Error);
+        }
+        let vec = data.lock().unwrap().clone();
+        let output = from_utf8(&vec).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first = json::from_str(lines[0]).unwrap();
+        assert_eq!(first.find("level").unwrap().as_string(), Some("warning"));
+        assert_eq!(first.find_path(&["code", "code"]).unwrap().as_string(), Some("E0000"));
+        let span = &first.find("spans").unwrap().as_array().unwrap()[0];
+        assert_eq!(span.find("line_start").unwrap().as_u64(), Some(2));
+        assert_eq!(span.find("column_start").unwrap().as_u64(), Some(9));
+        assert_eq!(span.find("byte_start").unwrap().as_u64(), Some(20));
+        let children = first.find("children").unwrap().as_array().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].find("level").unwrap().as_string(), Some("help"));
+
+        let second = json::from_str(lines[1]).unwrap();
+        assert_eq!(second.find("message").unwrap().as_string(), Some("a second diagnostic"));
+    }
+}
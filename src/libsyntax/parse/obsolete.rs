@@ -31,26 +31,29 @@ pub trait ParserObsoleteMethods {
               kind: ObsoleteSyntax,
               kind_str: &str,
               desc: &str,
+              replacement: Option<&str>,
               error: bool);
 }
 
 impl<'a> ParserObsoleteMethods for parser::Parser<'a> {
     /// Reports an obsolete syntax non-fatal error.
     fn obsolete(&mut self, sp: Span, kind: ObsoleteSyntax) {
-        let (kind_str, desc, error) = match kind {
+        let (kind_str, desc, replacement, error) = match kind {
             ObsoleteSyntax::ClosureKind => (
                 "`:`, `&mut:`, or `&:`",
                 "rely on inference instead",
+                Some(""),
                 true,
             ),
             ObsoleteSyntax::ExternCrateString => (
                 "\"crate-name\"",
                 "use an identifier not in quotes instead",
+                None,
                 false, // warning for now
             ),
         };
 
-        self.report(sp, kind, kind_str, desc, error);
+        self.report(sp, kind, kind_str, desc, replacement, error);
     }
 
     fn report(&mut self,
@@ -58,6 +61,7 @@ impl<'a> ParserObsoleteMethods for parser::Parser<'a> {
               kind: ObsoleteSyntax,
               kind_str: &str,
               desc: &str,
+              replacement: Option<&str>,
               error: bool) {
         if error {
             self.span_err(sp, &format!("obsolete syntax: {}", kind_str));
@@ -65,7 +69,12 @@ impl<'a> ParserObsoleteMethods for parser::Parser<'a> {
             self.span_warn(sp, &format!("obsolete syntax: {}", kind_str));
         }
 
-        if !self.obsolete_set.contains(&kind) &&
+        // A fix is worth suggesting every time, unlike the description.
+        if let Some(replacement) = replacement {
+            if error || self.sess.span_diagnostic.handler().can_emit_warnings {
+                self.span_suggestion(sp, desc, replacement.to_string());
+            }
+        } else if !self.obsolete_set.contains(&kind) &&
             (error || self.sess.span_diagnostic.handler().can_emit_warnings) {
             self.sess
                 .span_diagnostic
@@ -1162,7 +1162,7 @@ impl<'a> Parser<'a> {
             return Ok(());
         }
 
-        let span = mk_sp(lo, self.span.hi);
+        let span = mk_sp(lo, self.last_span.hi);
         self.obsolete(span, ObsoleteSyntax::ClosureKind);
         Ok(())
     }
@@ -0,0 +1,123 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Rewriting source files according to the suggestions made by
+//! diagnostics, as done by `-Z apply-suggestions`.
+//!
+//! Suggestions whose span comes from a macro expansion are never
+//! applied, since the text they cover need not appear in the source.
+//! When two suggestions overlap, only the first one (in source order)
+//! is applied.
+
+use codemap::{CodeMap, NO_EXPANSION};
+use diagnostic::CodeSuggestion;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+
+/// A replacement of the bytes `lo..hi` of a file.
+struct Edit<'a> {
+    lo: usize,
+    hi: usize,
+    replacement: &'a str,
+}
+
+/// Applies `suggestions` to the files they refer to, and returns the
+/// number of suggestions which were applied.
+pub fn apply_suggestions(cm: &CodeMap, suggestions: &[CodeSuggestion]) -> io::Result<usize> {
+    let mut files = HashMap::new();
+    for suggestion in suggestions {
+        let sp = suggestion.span;
+        if sp.expn_id != NO_EXPANSION {
+            continue;
+        }
+
+        let lo = cm.lookup_byte_offset(sp.lo);
+        let hi = cm.lookup_byte_offset(sp.hi);
+        if lo.fm.name != hi.fm.name || !lo.fm.is_real_file() || lo.fm.is_imported() {
+            continue;
+        }
+
+        let name = lo.fm.name.clone();
+        files.entry(name)
+             .or_insert_with(|| (lo.fm.clone(), Vec::new()))
+             .1
+             .push(Edit {
+                 lo: lo.pos.0 as usize,
+                 hi: hi.pos.0 as usize,
+                 replacement: &suggestion.replacement,
+             });
+    }
+
+    let mut applied = 0;
+    for (name, (fm, mut edits)) in files {
+        let src = match fm.src {
+            Some(ref src) => src,
+            None => continue,
+        };
+        let (new_src, count) = apply_edits(src, &mut edits);
+        if count == 0 {
+            continue;
+        }
+        let mut file = try!(File::create(&name));
+        try!(file.write_all(new_src.as_bytes()));
+        applied += count;
+    }
+    Ok(applied)
+}
+
+/// Returns `src` with the non-overlapping `edits` applied, and the
+/// number of edits that were.
+fn apply_edits(src: &str, edits: &mut Vec<Edit>) -> (String, usize) {
+    edits.sort_by(|a, b| (a.lo, a.hi).cmp(&(b.lo, b.hi)));
+
+    let mut result = String::with_capacity(src.len());
+    let mut pos = 0;
+    let mut count = 0;
+    let mut last: Option<(usize, usize, &str)> = None;
+    for edit in edits.iter() {
+        // The same suggestion is sometimes made twice.
+        if last == Some((edit.lo, edit.hi, edit.replacement)) {
+            continue;
+        }
+        if edit.lo < pos || edit.hi > src.len() {
+            continue;
+        }
+        result.push_str(&src[pos..edit.lo]);
+        result.push_str(edit.replacement);
+        pos = edit.hi;
+        count += 1;
+        last = Some((edit.lo, edit.hi, edit.replacement));
+    }
+    result.push_str(&src[pos..]);
+    (result, count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{apply_edits, Edit};
+
+    #[test]
+    fn test_apply_edits() {
+        let src = "let x = y; let z = w;";
+        let mut edits = vec![
+            Edit { lo: 19, hi: 20, replacement: "&w" },
+            Edit { lo: 8, hi: 9, replacement: "self.y" },
+            // overlaps the edit above, and wins since it starts first
+            Edit { lo: 4, hi: 9, replacement: "a" },
+            // duplicate
+            Edit { lo: 19, hi: 20, replacement: "&w" },
+        ];
+        let (result, count) = apply_edits(src, &mut edits);
+        assert_eq!(result, "let a; let z = &w;");
+        assert_eq!(count, 2);
+    }
+}
@@ -40,9 +40,11 @@
 #![feature(fnbox)]
 #![feature(iter_cmp)]
 #![feature(libc)]
+#![feature(mpsc_recv_timeout)]
 #![feature(rustc_private)]
 #![feature(set_stdio)]
 #![feature(staged_api)]
+#![feature(time2)]
 
 extern crate getopts;
 extern crate serialize;
@@ -52,7 +54,9 @@ extern crate libc;
 
 pub use self::TestFn::*;
 pub use self::ColorConfig::*;
+pub use self::OutputFormat::*;
 pub use self::TestResult::*;
+pub use self::MetricChange::*;
 pub use self::TestName::*;
 use self::TestEvent::*;
 use self::NamePadding::*;
@@ -61,30 +65,32 @@ use self::OutputLocation::*;
 use stats::Stats;
 use getopts::{OptGroup, optflag, optopt};
 use serialize::Encodable;
+use serialize::json;
 use std::boxed::FnBox;
 use term::Terminal;
 use term::color::{Color, RED, YELLOW, GREEN, CYAN};
 
 use std::any::Any;
 use std::cmp;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
+use std::f64;
 use std::fmt;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io;
 use std::iter::repeat;
-use std::path::PathBuf;
-use std::sync::mpsc::{channel, Sender};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Sender, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 // to be used by rustc to compile tests in libtest
 pub mod test {
     pub use {Bencher, TestName, TestResult, TestDesc,
-             TestDescAndFn, TestOpts, TrFailed, TrIgnored, TrOk,
-             Metric, MetricMap,
+             TestDescAndFn, TestOpts, OutputFormat, TrFailed, TrIgnored, TrOk,
+             Metric, MetricMap, MetricChange, MetricDiff,
              StaticTestFn, StaticTestName, DynTestName, DynTestFn,
              run_test, test_main, test_main_static, filter_tests,
              parse_opts, StaticBenchFn, ShouldPanic};
@@ -191,6 +197,8 @@ pub struct Bencher {
     iterations: u64,
     dur: Duration,
     pub bytes: u64,
+    // how many samples of the last `auto_bench` run were outliers
+    outliers: usize,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -207,6 +215,9 @@ pub struct TestDesc {
     pub name: TestName,
     pub ignore: bool,
     pub should_panic: ShouldPanic,
+    /// How many seconds the test may run for before it fails, overriding
+    /// `TestOpts::test_timeout`.
+    pub timeout: Option<u64>,
 }
 
 unsafe impl Send for TestDesc {}
@@ -232,6 +243,21 @@ impl Metric {
 #[derive(PartialEq)]
 pub struct MetricMap(BTreeMap<String,Metric>);
 
+/// How a metric changed from one run to the next, see
+/// `MetricMap::compare_to_old`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum MetricChange {
+    LikelyNoise,
+    MetricAdded,
+    MetricRemoved,
+    /// The metric improved by this many percent.
+    Improvement(f64),
+    /// The metric regressed by this many percent.
+    Regression(f64),
+}
+
+pub type MetricDiff = BTreeMap<String,MetricChange>;
+
 impl Clone for MetricMap {
     fn clone(&self) -> MetricMap {
         let MetricMap(ref map) = *self;
@@ -281,14 +307,49 @@ pub enum ColorConfig {
     NeverColor,
 }
 
+/// The format that results are written to stdout in.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum OutputFormat {
+    /// Human readable text, one line per test.
+    PrettyOutput,
+    /// One JSON object per line for every event of the run.
+    JsonOutput,
+    /// A JUnit XML report, written once the run is over.
+    JunitOutput,
+}
+
 pub struct TestOpts {
     pub filter: Option<String>,
+    /// Only run tests whose name is `filter`, instead of all those whose
+    /// name contains it.
+    pub filter_exact: bool,
+    /// Don't run tests whose name contains (or is, with `filter_exact`) any
+    /// of these.
+    pub skip: Vec<String>,
+    /// Only run the `i`th of every `n` tests, for `Some((i, n))`. Shards are
+    /// counted from 1.
+    pub shard: Option<(usize, usize)>,
+    /// List the tests instead of running them.
+    pub list: bool,
     pub run_ignored: bool,
     pub run_tests: bool,
     pub bench_benchmarks: bool,
     pub logfile: Option<PathBuf>,
     pub nocapture: bool,
     pub color: ColorConfig,
+    /// Save the metrics of the benchmarks to this file once the run is over.
+    pub save_metrics: Option<PathBuf>,
+    /// Compare the metrics of the benchmarks against those saved to this
+    /// file, and fail the run if any of them regressed.
+    pub ratchet_metrics: Option<PathBuf>,
+    /// Treat changes of less than this percentage as noise when comparing
+    /// metrics, instead of the noise of the metrics themselves.
+    pub ratchet_noise_percent: Option<f64>,
+    pub format: OutputFormat,
+    /// How long a test may run for before it is failed, unless it sets its
+    /// own timeout.
+    pub test_timeout: Option<Duration>,
+    pub report_time: bool,
 }
 
 impl TestOpts {
@@ -296,12 +357,22 @@ impl TestOpts {
     fn new() -> TestOpts {
         TestOpts {
             filter: None,
+            filter_exact: false,
+            skip: Vec::new(),
+            shard: None,
+            list: false,
             run_ignored: false,
             run_tests: false,
             bench_benchmarks: false,
             logfile: None,
             nocapture: false,
             color: AutoColor,
+            save_metrics: None,
+            ratchet_metrics: None,
+            ratchet_noise_percent: None,
+            format: PrettyOutput,
+            test_timeout: None,
+            report_time: false,
         }
     }
 }
@@ -313,27 +384,59 @@ fn optgroups() -> Vec<getopts::OptGroup> {
     vec!(getopts::optflag("", "ignored", "Run ignored tests"),
       getopts::optflag("", "test", "Run tests and not benchmarks"),
       getopts::optflag("", "bench", "Run benchmarks instead of tests"),
+      getopts::optflag("", "list", "List all tests and benchmarks"),
+      getopts::optflag("", "exact", "Exactly match filters rather than by substring"),
+      getopts::optmulti("", "skip", "Skip tests whose names contain FILTER (this flag can \
+                                     be used multiple times)", "FILTER"),
+      getopts::optopt("", "shard", "Only run the I-th of every N tests, counting from 1",
+                      "I/N"),
       getopts::optflag("h", "help", "Display this message (longer with --help)"),
       getopts::optopt("", "logfile", "Write logs to the specified file instead \
                           of stdout", "PATH"),
       getopts::optflag("", "nocapture", "don't capture stdout/stderr of each \
                                          task, allow printing directly"),
+      getopts::optopt("", "save-metrics", "Save the metrics of the benchmarks to \
+                                           PATH", "PATH"),
+      getopts::optopt("", "ratchet-metrics", "Fail if the metrics of the benchmarks \
+                                              regressed from those saved to PATH", "PATH"),
+      getopts::optopt("", "ratchet-noise-percent", "Tolerate changes of less than \
+                                                    PERCENT when ratcheting metrics",
+                      "PERCENT"),
       getopts::optopt("", "color", "Configure coloring of output:
             auto   = colorize if stdout is a tty and tests are run on serially (default);
             always = always colorize output;
-            never  = never colorize output;", "auto|always|never"))
+            never  = never colorize output;", "auto|always|never"),
+      getopts::optopt("", "format", "Configure formatting of output:
+            pretty = human readable output (default);
+            json   = one JSON object per line for every event;
+            junit  = a JUnit XML report", "pretty|json|junit"),
+      getopts::optopt("", "test-timeout", "Fail tests which run for longer than \
+                                           SECS seconds", "SECS"),
+      getopts::optflag("", "report-time", "Show the slowest tests at the end \
+                                           of the run"))
 }
 
 fn usage(binary: &str) {
     let message = format!("Usage: {} [OPTIONS] [FILTER]", binary);
     println!(r#"{usage}
 
-The FILTER regex is tested against the name of all tests to run, and
-only those tests that match are run.
+The FILTER string is tested against the name of all tests to run, and
+only those tests whose names contain it are run. With --exact, the name
+has to be FILTER itself, and the same goes for the --skip filters.
+
+The --shard option splits the tests into N groups of about the same size,
+so that they can be run on N machines. Which group a test is in only
+depends on the names of the tests that are left after filtering.
 
 By default, all tests are run in parallel. This can be altered with the
 RUST_TEST_THREADS environment variable when running tests (set it to 1).
 
+Benchmark results can be saved with --save-metrics and later runs compared
+against them with --ratchet-metrics, which fails the run if a benchmark got
+significantly slower, i.e. if its 95% confidence intervals in the two runs
+don't overlap. The metrics of such a run are not saved, and a missing
+--ratchet-metrics file is not compared against.
+
 All tests have their standard output and standard error captured by default.
 This can be overridden with the --nocapture flag or the RUST_TEST_NOCAPTURE=1
 environment variable. Logging is not captured by default.
@@ -351,7 +454,10 @@ Test Attributes:
     #[ignore]      - When applied to a function which is already attributed as a
                      test, then the test runner will ignore these tests during
                      normal test runs. Running with --ignored will run these
-                     tests."#,
+                     tests.
+    #[timeout = "N"] - The test (also labeled with #[test]) fails if it runs
+                     for longer than N seconds, whatever --test-timeout is.
+                     The rest of the tests keep running."#,
              usage = getopts::usage(&message, &optgroups()));
 }
 
@@ -372,6 +478,19 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         None
     };
 
+    let filter_exact = matches.opt_present("exact");
+    let skip = matches.opt_strs("skip");
+    let list = matches.opt_present("list");
+
+    let shard = match matches.opt_str("shard") {
+        Some(shard) => match parse_shard(&shard) {
+            Some(shard) => Some(shard),
+            None => return Some(Err(format!("argument for --shard must be I/N, \
+                                             with 1 <= I <= N (was {})", shard))),
+        },
+        None => None,
+    };
+
     let run_ignored = matches.opt_present("ignored");
 
     let logfile = matches.opt_str("logfile");
@@ -386,6 +505,17 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         nocapture = env::var("RUST_TEST_NOCAPTURE").is_ok();
     }
 
+    let save_metrics = matches.opt_str("save-metrics").map(PathBuf::from);
+    let ratchet_metrics = matches.opt_str("ratchet-metrics").map(PathBuf::from);
+    let ratchet_noise_percent = match matches.opt_str("ratchet-noise-percent") {
+        Some(pct) => match pct.parse::<f64>() {
+            Ok(pct) if pct >= 0.0 => Some(pct),
+            _ => return Some(Err(format!("argument for --ratchet-noise-percent must be \
+                                         a non-negative number (was {})", pct))),
+        },
+        None => None,
+    };
+
     let color = match matches.opt_str("color").as_ref().map(|s| &**s) {
         Some("auto") | None => AutoColor,
         Some("always") => AlwaysColor,
@@ -396,23 +526,75 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
                                             v))),
     };
 
+    let format = match matches.opt_str("format").as_ref().map(|s| &**s) {
+        Some("pretty") | None => PrettyOutput,
+        Some("json") => JsonOutput,
+        Some("junit") => JunitOutput,
+
+        Some(v) => return Some(Err(format!("argument for --format must be \
+                                            pretty, json, or junit (was {})",
+                                            v))),
+    };
+
+    let test_timeout = match matches.opt_str("test-timeout") {
+        Some(secs) => match secs.parse() {
+            Ok(secs) => Some(Duration::from_secs(secs)),
+            Err(_) => return Some(Err(format!("argument for --test-timeout must be \
+                                               a number of seconds (was {})",
+                                              secs))),
+        },
+        None => None,
+    };
+
+    let report_time = matches.opt_present("report-time");
+
     let test_opts = TestOpts {
         filter: filter,
+        filter_exact: filter_exact,
+        skip: skip,
+        shard: shard,
+        list: list,
         run_ignored: run_ignored,
         run_tests: run_tests,
         bench_benchmarks: bench_benchmarks,
         logfile: logfile,
         nocapture: nocapture,
         color: color,
+        save_metrics: save_metrics,
+        ratchet_metrics: ratchet_metrics,
+        ratchet_noise_percent: ratchet_noise_percent,
+        format: format,
+        test_timeout: test_timeout,
+        report_time: report_time,
     };
 
     Some(Ok(test_opts))
 }
 
+// Parses the `I/N` argument of `--shard`
+fn parse_shard(s: &str) -> Option<(usize, usize)> {
+    let mut parts = s.splitn(2, '/');
+    let index = parts.next().and_then(|i| i.parse().ok());
+    let count = parts.next().and_then(|n| n.parse().ok());
+    match (index, count) {
+        (Some(i), Some(n)) if 1 <= i && i <= n => Some((i, n)),
+        _ => None,
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct BenchSamples {
     ns_iter_summ: stats::Summary,
     mb_s: usize,
+    // how many of the samples were outliers, before winsorizing them
+    outliers: usize,
+}
+
+impl BenchSamples {
+    // The 95% confidence interval of the median time per iteration
+    fn ns_iter_ci(&self) -> (f64, f64) {
+        self.ns_iter_summ.median_confidence_interval(BENCH_SAMPLES)
+    }
 }
 
 #[derive(Clone, PartialEq)]
@@ -435,6 +617,9 @@ struct ConsoleTestState<T> {
     log_out: Option<File>,
     out: OutputLocation<T>,
     use_color: bool,
+    format: OutputFormat,
+    start_time: Instant,
+    report_time: bool,
     total: usize,
     passed: usize,
     failed: usize,
@@ -442,6 +627,10 @@ struct ConsoleTestState<T> {
     measured: usize,
     metrics: MetricMap,
     failures: Vec<(TestDesc, Vec<u8> )> ,
+    // every result, kept until the end of the run for the JUnit report
+    results: Vec<(TestDesc, TestResult, Duration, Vec<u8>)>,
+    // how long each test took, for `--report-time`
+    exec_times: Vec<(TestName, Duration)>,
     max_name_len: usize, // number of columns to fill when aligning names
 }
 
@@ -460,7 +649,10 @@ impl<T: Write> ConsoleTestState<T> {
         Ok(ConsoleTestState {
             out: out,
             log_out: log_out,
-            use_color: use_color(opts),
+            use_color: use_color(opts) && opts.format == PrettyOutput,
+            format: opts.format,
+            start_time: Instant::now(),
+            report_time: opts.report_time,
             total: 0,
             passed: 0,
             failed: 0,
@@ -468,6 +660,8 @@ impl<T: Write> ConsoleTestState<T> {
             measured: 0,
             metrics: MetricMap::new(),
             failures: Vec::new(),
+            results: Vec::new(),
+            exec_times: Vec::new(),
             max_name_len: 0,
         })
     }
@@ -526,19 +720,84 @@ impl<T: Write> ConsoleTestState<T> {
         }
     }
 
+    pub fn write_list(&mut self, tests: &[TestDescAndFn]) -> io::Result<()> {
+        let mut ntests = 0;
+        let mut nbenches = 0;
+        for test in tests {
+            let kind = match test.testfn {
+                StaticTestFn(..) | DynTestFn(..) => { ntests += 1; "test" }
+                StaticBenchFn(..) | DynBenchFn(..) => { nbenches += 1; "benchmark" }
+                StaticMetricFn(..) | DynMetricFn(..) => { nbenches += 1; "metric" }
+            };
+            let line = match self.format {
+                JsonOutput => format!("{{ \"type\": \"{}\", \"event\": \"discovered\", \
+                                       \"name\": {} }}\n",
+                                      kind, json_str(test.desc.name.as_slice())),
+                PrettyOutput | JunitOutput => format!("{}: {}\n", test.desc.name, kind),
+            };
+            try!(self.write_plain(&line));
+        }
+
+        if self.format != JsonOutput {
+            let plural = |n: usize, s: &str| if n == 1 { format!("{} {}", n, s) }
+                                      else { format!("{} {}s", n, s) };
+            try!(self.write_plain(&format!("\n{}, {}\n", plural(ntests, "test"),
+                                           plural(nbenches, "benchmark"))));
+        }
+        Ok(())
+    }
+
     pub fn write_run_start(&mut self, len: usize) -> io::Result<()> {
         self.total = len;
-        let noun = if len != 1 { "tests" } else { "test" };
-        self.write_plain(&format!("\nrunning {} {}\n", len, noun))
+        match self.format {
+            PrettyOutput => {
+                let noun = if len != 1 { "tests" } else { "test" };
+                self.write_plain(&format!("\nrunning {} {}\n", len, noun))
+            }
+            JsonOutput => {
+                self.write_plain(&format!("{{ \"type\": \"suite\", \"event\": \"started\", \
+                                           \"test_count\": {} }}\n", len))
+            }
+            JunitOutput => Ok(()),
+        }
     }
 
     pub fn write_test_start(&mut self, test: &TestDesc,
                             align: NamePadding) -> io::Result<()> {
-        let name = test.padded_name(self.max_name_len, align);
-        self.write_plain(&format!("test {} ... ", name))
+        match self.format {
+            PrettyOutput => {
+                let name = test.padded_name(self.max_name_len, align);
+                self.write_plain(&format!("test {} ... ", name))
+            }
+            JsonOutput => {
+                self.write_plain(&format!("{{ \"type\": \"test\", \"event\": \"started\", \
+                                           \"name\": {} }}\n",
+                                          json_str(test.name.as_slice())))
+            }
+            JunitOutput => Ok(()),
+        }
     }
 
-    pub fn write_result(&mut self, result: &TestResult) -> io::Result<()> {
+    pub fn write_result(&mut self, test: &TestDesc, result: &TestResult,
+                        exec_time: Duration, stdout: &[u8]) -> io::Result<()> {
+        if self.report_time {
+            match *result {
+                TrOk | TrFailed => self.exec_times.push((test.name.clone(), exec_time)),
+                _ => {}
+            }
+        }
+        match self.format {
+            PrettyOutput => self.write_pretty_result(result),
+            JsonOutput => self.write_json_result(test, result, exec_time, stdout),
+            JunitOutput => {
+                self.results.push((test.clone(), result.clone(), exec_time,
+                                   stdout.to_vec()));
+                Ok(())
+            }
+        }
+    }
+
+    fn write_pretty_result(&mut self, result: &TestResult) -> io::Result<()> {
         try!(match *result {
             TrOk => self.write_ok(),
             TrFailed => self.write_failed(),
@@ -558,6 +817,78 @@ impl<T: Write> ConsoleTestState<T> {
         self.write_plain("\n")
     }
 
+    fn write_json_result(&mut self, test: &TestDesc, result: &TestResult,
+                         exec_time: Duration, stdout: &[u8]) -> io::Result<()> {
+        let name = json_str(test.name.as_slice());
+        let mut line = match *result {
+            TrOk | TrFailed | TrIgnored => {
+                let event = match *result {
+                    TrOk => "ok",
+                    TrFailed => "failed",
+                    _ => "ignored",
+                };
+                format!("{{ \"type\": \"test\", \"event\": \"{}\", \"name\": {}, \
+                         \"exec_time\": {}", event, name, fmt_secs(exec_time))
+            }
+            TrMetrics(ref mm) => {
+                let MetricMap(ref mm) = *mm;
+                format!("{{ \"type\": \"metric\", \"name\": {}, \"metrics\": {}",
+                        name, json::encode(mm).unwrap())
+            }
+            TrBench(ref bs) => {
+                let (lo, hi) = bs.ns_iter_ci();
+                format!("{{ \"type\": \"bench\", \"name\": {}, \"median\": {}, \
+                         \"deviation\": {}, \"ci_low\": {}, \"ci_high\": {}, \
+                         \"outliers\": {}, \"mib_per_second\": {}",
+                        name, bs.ns_iter_summ.median,
+                        bs.ns_iter_summ.max - bs.ns_iter_summ.min, lo, hi,
+                        bs.outliers, bs.mb_s)
+            }
+        };
+        if !stdout.is_empty() {
+            line.push_str(&format!(", \"stdout\": {}",
+                                   json_str(&String::from_utf8_lossy(stdout))));
+        }
+        line.push_str(" }\n");
+        self.write_plain(&line)
+    }
+
+    fn write_junit_report(&mut self) -> io::Result<()> {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<testsuites>\n");
+        out.push_str(&format!("<testsuite name=\"test\" package=\"test\" id=\"0\" \
+                               errors=\"0\" failures=\"{}\" tests=\"{}\" \
+                               skipped=\"{}\" time=\"{}\">\n",
+                              self.failed, self.total, self.ignored,
+                              fmt_secs(self.start_time.elapsed())));
+        for &(ref test, ref result, exec_time, ref stdout) in &self.results {
+            let name = test.name.as_slice();
+            let (class_name, test_name) = match name.rfind("::") {
+                Some(i) => (&name[..i], &name[i + 2..]),
+                None => ("test", name),
+            };
+            out.push_str(&format!("<testcase classname=\"{}\" name=\"{}\" time=\"{}\"",
+                                  escape_xml(class_name), escape_xml(test_name),
+                                  fmt_secs(exec_time)));
+            match *result {
+                TrOk | TrMetrics(..) | TrBench(..) => out.push_str("/>\n"),
+                TrIgnored => out.push_str("><skipped/></testcase>\n"),
+                TrFailed => {
+                    out.push_str("><failure type=\"assert\"/>");
+                    if !stdout.is_empty() {
+                        out.push_str(&format!("<system-out>{}</system-out>",
+                                              escape_xml(&String::from_utf8_lossy(stdout))));
+                    }
+                    out.push_str("</testcase>\n");
+                }
+            }
+        }
+        out.push_str("</testsuite>\n");
+        out.push_str("</testsuites>\n");
+        self.write_plain(&out)
+    }
+
     pub fn write_log(&mut self, test: &TestDesc,
                      result: &TestResult) -> io::Result<()> {
         match self.log_out {
@@ -601,14 +932,131 @@ impl<T: Write> ConsoleTestState<T> {
         Ok(())
     }
 
+    pub fn write_slowest(&mut self) -> io::Result<()> {
+        self.exec_times.sort_by(|a, b| b.1.cmp(&a.1));
+        let slowest = self.exec_times.iter().take(SLOWEST_TESTS).cloned().collect::<Vec<_>>();
+
+        try!(self.write_plain("\nslowest tests:\n"));
+        for (name, exec_time) in slowest {
+            try!(self.write_plain(&format!("    {:>8}s {}\n", fmt_secs(exec_time), name)));
+        }
+        Ok(())
+    }
+
+    /// Writes how the metrics changed, and returns whether none of them
+    /// regressed.
+    pub fn write_metric_diff(&mut self, diff: &MetricDiff) -> io::Result<bool> {
+        let mut regressed = 0;
+        let mut improved = 0;
+        let mut noise = 0;
+        let mut added = 0;
+        let mut removed = 0;
+
+        for (name, change) in diff {
+            let (kind, pct) = match *change {
+                LikelyNoise => { noise += 1; ("noise", None) }
+                MetricAdded => { added += 1; ("added", None) }
+                MetricRemoved => { removed += 1; ("removed", None) }
+                Improvement(pct) => { improved += 1; ("improvement", Some(pct)) }
+                Regression(pct) => { regressed += 1; ("regression", Some(pct)) }
+            };
+            match self.format {
+                PrettyOutput => {
+                    match *change {
+                        Improvement(pct) => {
+                            try!(self.write_plain(&format!("metric {}: ", name)));
+                            try!(self.write_pretty("improved", term::color::GREEN));
+                            try!(self.write_plain(&format!(" by {:.2}%\n", pct)));
+                        }
+                        Regression(pct) => {
+                            try!(self.write_plain(&format!("metric {}: ", name)));
+                            try!(self.write_pretty("regressed", term::color::RED));
+                            try!(self.write_plain(&format!(" by {:.2}%\n", pct)));
+                        }
+                        _ => {}
+                    }
+                }
+                JsonOutput => {
+                    let pct = pct.map_or(String::new(),
+                                         |pct| format!(", \"percent\": {}", pct));
+                    try!(self.write_plain(&format!("{{ \"type\": \"metric_change\", \
+                                                    \"name\": {}, \"change\": \"{}\"{} }}\n",
+                                                   json_str(name), kind, pct)));
+                }
+                JunitOutput => {}
+            }
+        }
+
+        if self.format == PrettyOutput {
+            try!(self.write_plain("\nmetric result: "));
+            if regressed == 0 {
+                try!(self.write_ok());
+            } else {
+                try!(self.write_failed());
+            }
+            let s = format!(". {} regressed; {} improved; {} noise; {} added; {} removed\n\n",
+                            regressed, improved, noise, added, removed);
+            try!(self.write_plain(&s));
+        }
+        Ok(regressed == 0)
+    }
+
+    /// Compares the metrics against those saved to `--ratchet-metrics`, if
+    /// that file exists yet, and saves them to `--save-metrics` unless some
+    /// regressed. Returns whether none of them regressed.
+    pub fn ratchet_and_save_metrics(&mut self, opts: &TestOpts) -> io::Result<bool> {
+        let mut ok = true;
+        // Compare before saving, so that the same file can be used for both
+        if let Some(ref path) = opts.ratchet_metrics {
+            match MetricMap::load(path) {
+                Ok(old) => {
+                    let diff = self.metrics.compare_to_old(&old, opts.ratchet_noise_percent);
+                    ok = try!(self.write_metric_diff(&diff));
+                }
+                // The first run has nothing to compare against
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        // A regression doesn't replace the metrics it regressed from
+        if let Some(ref path) = opts.save_metrics {
+            if ok {
+                try!(self.metrics.save(path));
+            }
+        }
+        Ok(ok)
+    }
+
     pub fn write_run_finish(&mut self) -> io::Result<bool> {
         assert!(self.passed + self.failed + self.ignored + self.measured == self.total);
 
         let success = self.failed == 0;
+        match self.format {
+            PrettyOutput => {}
+            JsonOutput => {
+                let s = format!("{{ \"type\": \"suite\", \"event\": \"{}\", \
+                                 \"passed\": {}, \"failed\": {}, \"ignored\": {}, \
+                                 \"measured\": {}, \"exec_time\": {} }}\n",
+                                if success { "ok" } else { "failed" },
+                                self.passed, self.failed, self.ignored,
+                                self.measured, fmt_secs(self.start_time.elapsed()));
+                try!(self.write_plain(&s));
+                return Ok(success);
+            }
+            JunitOutput => {
+                try!(self.write_junit_report());
+                return Ok(success);
+            }
+        }
+
         if !success {
             try!(self.write_failures());
         }
 
+        if self.report_time && !self.exec_times.is_empty() {
+            try!(self.write_slowest());
+        }
+
         try!(self.write_plain("\ntest result: "));
         if success {
             // There's no parallelism at this point so it's safe to use color
@@ -647,6 +1095,35 @@ fn fmt_thousands_sep(mut n: usize, sep: char) -> String {
     output
 }
 
+// How many tests `--report-time` lists
+const SLOWEST_TESTS: usize = 10;
+
+// Format a duration as a number of seconds, with millisecond precision
+fn fmt_secs(d: Duration) -> String {
+    format!("{}.{:03}", d.as_secs(), d.subsec_nanos() / 1_000_000)
+}
+
+// Quote and escape a string for use in JSON output
+fn json_str(s: &str) -> String {
+    json::encode(&s).unwrap()
+}
+
+// Escape a string for use in XML text and attribute values
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
 pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
     use std::fmt::Write;
     let mut output = String::new();
@@ -671,9 +1148,9 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::Res
         match (*event).clone() {
             TeFiltered(ref filtered_tests) => st.write_run_start(filtered_tests.len()),
             TeWait(ref test, padding) => st.write_test_start(test, padding),
-            TeResult(test, result, stdout) => {
+            TeResult(test, result, exec_time, stdout) => {
                 try!(st.write_log(&test, &result));
-                try!(st.write_result(&result));
+                try!(st.write_result(&test, &result, exec_time, &stdout));
                 match result {
                     TrOk => st.passed += 1,
                     TrIgnored => st.ignored += 1,
@@ -691,9 +1168,12 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::Res
                         st.measured += 1
                     }
                     TrBench(bs) => {
+                        // Use the confidence interval as the noise, so that
+                        // only significant changes count when ratcheting
+                        let (lo, hi) = bs.ns_iter_ci();
                         st.metrics.insert_metric(test.name.as_slice(),
                                                  bs.ns_iter_summ.median,
-                                                 bs.ns_iter_summ.max - bs.ns_iter_summ.min);
+                                                 (hi - lo) / 2.0);
                         st.measured += 1
                     }
                     TrFailed => {
@@ -707,6 +1187,10 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::Res
     }
 
     let mut st = try!(ConsoleTestState::new(opts, None::<io::Stdout>));
+    if opts.list {
+        try!(st.write_list(&filter_tests(opts, tests)));
+        return Ok(true);
+    }
     fn len_if_padded(t: &TestDescAndFn) -> usize {
         match t.testfn.padding() {
             PadNone => 0,
@@ -721,7 +1205,9 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::Res
         None => {}
     }
     try!(run_tests(opts, tests, |x| callback(&x, &mut st)));
-    return st.write_run_finish();
+    let success = try!(st.write_run_finish());
+    let metrics_ok = try!(st.ratchet_and_save_metrics(opts));
+    Ok(success && metrics_ok)
 }
 
 #[test]
@@ -729,19 +1215,24 @@ fn should_sort_failures_before_printing_them() {
     let test_a = TestDesc {
         name: StaticTestName("a"),
         ignore: false,
-        should_panic: ShouldPanic::No
+        should_panic: ShouldPanic::No,
+        timeout: None
     };
 
     let test_b = TestDesc {
         name: StaticTestName("b"),
         ignore: false,
-        should_panic: ShouldPanic::No
+        should_panic: ShouldPanic::No,
+        timeout: None
     };
 
     let mut st = ConsoleTestState {
         log_out: None,
         out: Raw(Vec::new()),
         use_color: false,
+        format: PrettyOutput,
+        start_time: Instant::now(),
+        report_time: false,
         total: 0,
         passed: 0,
         failed: 0,
@@ -749,7 +1240,9 @@ fn should_sort_failures_before_printing_them() {
         measured: 0,
         max_name_len: 10,
         metrics: MetricMap::new(),
-        failures: vec!((test_b, Vec::new()), (test_a, Vec::new()))
+        failures: vec!((test_b, Vec::new()), (test_a, Vec::new())),
+        results: Vec::new(),
+        exec_times: Vec::new(),
     };
 
     st.write_failures().unwrap();
@@ -797,7 +1290,7 @@ fn stdout_isatty() -> bool {
 enum TestEvent {
     TeFiltered(Vec<TestDesc> ),
     TeWait(TestDesc, NamePadding),
-    TeResult(TestDesc, TestResult, Vec<u8> ),
+    TeResult(TestDesc, TestResult, Duration, Vec<u8> ),
 }
 
 pub type MonitorMsg = (TestDesc, TestResult, Vec<u8> );
@@ -837,6 +1330,10 @@ fn run_tests<F>(opts: &TestOpts,
 
     let (tx, rx) = channel::<MonitorMsg>();
 
+    // When each running test was started, to time it and to tell when it
+    // times out
+    let mut running = HashMap::new();
+
     while pending > 0 || !remaining.is_empty() {
         while pending < concurrency && !remaining.is_empty() {
             let test = remaining.pop().unwrap();
@@ -846,31 +1343,97 @@ fn run_tests<F>(opts: &TestOpts,
                 // that hang forever.
                 try!(callback(TeWait(test.desc.clone(), test.testfn.padding())));
             }
-            run_test(opts, !opts.run_tests, test, tx.clone());
+            let desc = test.desc.clone();
+            let timeout = desc.timeout.map(Duration::from_secs).or(opts.test_timeout);
+            let start = Instant::now();
+            let output = start_test(opts, !opts.run_tests, test, tx.clone());
+            running.insert(desc, RunningTest {
+                start: start,
+                timeout: timeout,
+                output: output,
+            });
             pending += 1;
         }
 
-        let (desc, result, stdout) = rx.recv().unwrap();
+        let deadline = running.values().filter_map(|t| t.deadline()).min();
+        let msg = match deadline {
+            Some(deadline) => rx.recv_deadline(deadline),
+            None => Ok(rx.recv().unwrap()),
+        };
+        let (desc, result, stdout) = match msg {
+            Ok(msg) => msg,
+            Err(RecvTimeoutError::Timeout) => {
+                // There's no way to stop the threads of the tests which
+                // timed out, so they are failed and left running.
+                let now = Instant::now();
+                let timed_out = running.iter()
+                                       .filter(|&(_, t)| t.deadline().map_or(false, |d| d <= now))
+                                       .map(|(desc, _)| desc.clone())
+                                       .collect::<Vec<_>>();
+                for desc in timed_out {
+                    let test = running.remove(&desc).unwrap();
+                    let mut stdout = match test.output {
+                        Some(ref output) => output.lock().unwrap().to_vec(),
+                        None => Vec::new(),
+                    };
+                    let msg = format!("test timed out after {} seconds\n",
+                                      test.timeout.unwrap().as_secs());
+                    stdout.extend(msg.bytes());
+                    if concurrency != 1 {
+                        try!(callback(TeWait(desc.clone(), PadNone)));
+                    }
+                    try!(callback(TeResult(desc, TrFailed, test.start.elapsed(), stdout)));
+                    pending -= 1;
+                }
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => unreachable!(),
+        };
+        let test = match running.remove(&desc) {
+            Some(test) => test,
+            // The test timed out, and has already been reported as failed
+            None => continue,
+        };
         if concurrency != 1 {
             try!(callback(TeWait(desc.clone(), PadNone)));
         }
-        try!(callback(TeResult(desc, result, stdout)));
+        try!(callback(TeResult(desc, result, test.start.elapsed(), stdout)));
         pending -= 1;
     }
 
     if opts.bench_benchmarks {
+        // Tests which timed out may still send their results, so the
+        // benchmarks get a channel of their own.
+        let (tx, rx) = channel::<MonitorMsg>();
+
         // All benchmarks run at the end, in serial.
         // (this includes metric fns)
         for b in filtered_benchs_and_metrics {
             try!(callback(TeWait(b.desc.clone(), b.testfn.padding())));
+            let start = Instant::now();
             run_test(opts, false, b, tx.clone());
             let (test, result, stdout) = rx.recv().unwrap();
-            try!(callback(TeResult(test, result, stdout)));
+            try!(callback(TeResult(test, result, start.elapsed(), stdout)));
         }
     }
     Ok(())
 }
 
+// A test which has been started by `run_tests` and hasn't reported back yet
+struct RunningTest {
+    start: Instant,
+    timeout: Option<Duration>,
+    // the output the test has written so far
+    output: Option<Arc<Mutex<Vec<u8>>>>,
+}
+
+impl RunningTest {
+    // A timeout too long to be represented is the same as no timeout
+    fn deadline(&self) -> Option<Instant> {
+        self.timeout.and_then(|timeout| self.start.checked_add(timeout))
+    }
+}
+
 #[allow(deprecated)]
 fn get_concurrency() -> usize {
     return match env::var("RUST_TEST_THREADS") {
@@ -921,16 +1484,24 @@ fn get_concurrency() -> usize {
 pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
     let mut filtered = tests;
 
+    let matches_filter = |test: &TestDescAndFn, filter: &str| {
+        let name = test.desc.name.as_slice();
+        if opts.filter_exact { name == filter } else { name.contains(filter) }
+    };
+
     // Remove tests that don't match the test filter
     filtered = match opts.filter {
         None => filtered,
         Some(ref filter) => {
-            filtered.into_iter().filter(|test| {
-                test.desc.name.as_slice().contains(&filter[..])
-            }).collect()
+            filtered.into_iter().filter(|test| matches_filter(test, &filter[..])).collect()
         }
     };
 
+    // Skip tests that match any of the skip filters
+    filtered = filtered.into_iter().filter(|test| {
+        !opts.skip.iter().any(|skip| matches_filter(test, &skip[..]))
+    }).collect();
+
     // Maybe pull out the ignored test and unignore them
     filtered = if !opts.run_ignored {
         filtered
@@ -952,7 +1523,16 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
     // Sort the tests alphabetically
     filtered.sort_by(|t1, t2| t1.desc.name.as_slice().cmp(t2.desc.name.as_slice()));
 
-    filtered
+    // Keep every n-th test, starting from the i-th one, so that the shards
+    // are the same on every machine
+    match opts.shard {
+        None => filtered,
+        Some((index, count)) => {
+            filtered.into_iter().enumerate().filter(|&(i, _)| {
+                i % count == index - 1
+            }).map(|(_, test)| test).collect()
+        }
+    }
 }
 
 pub fn convert_benchmarks_to_tests(tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
@@ -975,18 +1555,27 @@ pub fn run_test(opts: &TestOpts,
                 force_ignore: bool,
                 test: TestDescAndFn,
                 monitor_ch: Sender<MonitorMsg>) {
+    start_test(opts, force_ignore, test, monitor_ch);
+}
+
+// Like `run_test`, but also returns the buffer the output of a test running
+// in its own thread is captured to.
+fn start_test(opts: &TestOpts,
+              force_ignore: bool,
+              test: TestDescAndFn,
+              monitor_ch: Sender<MonitorMsg>) -> Option<Arc<Mutex<Vec<u8>>>> {
 
     let TestDescAndFn {desc, testfn} = test;
 
     if force_ignore || desc.ignore {
         monitor_ch.send((desc, TrIgnored, Vec::new())).unwrap();
-        return;
+        return None;
     }
 
     fn run_test_inner(desc: TestDesc,
                       monitor_ch: Sender<MonitorMsg>,
                       nocapture: bool,
-                      testfn: Box<FnBox() + Send>) {
+                      testfn: Box<FnBox() + Send>) -> Arc<Mutex<Vec<u8>>> {
         struct Sink(Arc<Mutex<Vec<u8>>>);
         impl Write for Sink {
             fn write(&mut self, data: &[u8]) -> io::Result<usize> {
@@ -995,8 +1584,9 @@ pub fn run_test(opts: &TestOpts,
             fn flush(&mut self) -> io::Result<()> { Ok(()) }
         }
 
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let output = data.clone();
         thread::spawn(move || {
-            let data = Arc::new(Mutex::new(Vec::new()));
             let data2 = data.clone();
             let cfg = thread::Builder::new().name(match desc.name {
                 DynTestName(ref name) => name.clone(),
@@ -1012,36 +1602,39 @@ pub fn run_test(opts: &TestOpts,
             }).unwrap();
             let test_result = calc_result(&desc, result_guard.join());
             let stdout = data.lock().unwrap().to_vec();
-            monitor_ch.send((desc.clone(), test_result, stdout)).unwrap();
+            // The receiver is gone if the run finished after this test
+            // timed out.
+            let _ = monitor_ch.send((desc.clone(), test_result, stdout));
         });
+        output
     }
 
     match testfn {
         DynBenchFn(bencher) => {
             let bs = ::bench::benchmark(|harness| bencher.run(harness));
             monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
-            return;
+            None
         }
         StaticBenchFn(benchfn) => {
             let bs = ::bench::benchmark(|harness| (benchfn.clone())(harness));
             monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
-            return;
+            None
         }
         DynMetricFn(f) => {
             let mut mm = MetricMap::new();
             f.call_box((&mut mm,));
             monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
-            return;
+            None
         }
         StaticMetricFn(f) => {
             let mut mm = MetricMap::new();
             f(&mut mm);
             monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
-            return;
+            None
         }
-        DynTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture, f),
-        StaticTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture,
-                                          Box::new(f))
+        DynTestFn(f) => Some(run_test_inner(desc, monitor_ch, opts.nocapture, f)),
+        StaticTestFn(f) => Some(run_test_inner(desc, monitor_ch, opts.nocapture,
+                                               Box::new(f)))
     }
 }
 
@@ -1087,6 +1680,71 @@ impl MetricMap {
         map.insert(name.to_owned(), m);
     }
 
+    /// Load a MetricMap saved by `save`.
+    pub fn load(p: &Path) -> io::Result<MetricMap> {
+        let mut s = String::new();
+        try!(try!(File::open(p)).read_to_string(&mut s));
+        match json::decode(&s) {
+            Ok(map) => Ok(MetricMap(map)),
+            Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData,
+                                         format!("invalid metrics file {}: {}",
+                                                 p.display(), e))),
+        }
+    }
+
+    /// Write the MetricMap to a file as JSON.
+    pub fn save(&self, p: &Path) -> io::Result<()> {
+        let MetricMap(ref map) = *self;
+        let mut file = try!(File::create(p));
+        write!(&mut file, "{}", json::as_pretty_json(map))
+    }
+
+    /// Compare against another MetricMap. Changes of a metric are noise if
+    /// they're smaller than `noise_pct` percent of its old value, or if
+    /// `noise_pct` is `None`, if its old and new ranges of `value` +/-
+    /// `noise` overlap.
+    pub fn compare_to_old(&self, old: &MetricMap,
+                          noise_pct: Option<f64>) -> MetricDiff {
+        let mut diff : MetricDiff = BTreeMap::new();
+        let MetricMap(ref selfmap) = *self;
+        let MetricMap(ref old) = *old;
+        for (k, vold) in old {
+            let r = match selfmap.get(k) {
+                None => MetricRemoved,
+                Some(v) => {
+                    let delta = v.value - vold.value;
+                    let noise = match noise_pct {
+                        None => vold.noise.abs() + v.noise.abs(),
+                        Some(pct) => vold.value * pct / 100.0
+                    };
+                    if delta.abs() <= noise {
+                        LikelyNoise
+                    } else {
+                        let pct = delta.abs() / vold.value.max(f64::EPSILON) * 100.0;
+                        if vold.noise < 0.0 {
+                            // When 'noise' is negative, it means we want
+                            // to see deltas that go up over time, and can
+                            // only tolerate slight negative movement.
+                            if delta < 0.0 { Regression(pct) } else { Improvement(pct) }
+                        } else {
+                            // When 'noise' is positive, it means we want
+                            // to see deltas that go down over time, and
+                            // can only tolerate slight positive movements.
+                            if delta < 0.0 { Improvement(pct) } else { Regression(pct) }
+                        }
+                    }
+                }
+            };
+            diff.insert(k.clone(), r);
+        }
+        for k in selfmap.keys() {
+            if !diff.contains_key(k) {
+                diff.insert(k.clone(), MetricAdded);
+            }
+        }
+        diff
+    }
+
     pub fn fmt_metrics(&self) -> String {
         let MetricMap(ref mm) = *self;
         let v : Vec<String> = mm.iter()
@@ -1100,6 +1758,9 @@ impl MetricMap {
 
 // Benchmarking
 
+// How many samples `Bencher::auto_bench` summarizes
+const BENCH_SAMPLES: usize = 50;
+
 /// A function that is opaque to the optimizer, to allow benchmarks to
 /// pretend to use outputs to assist in avoiding dead-code
 /// elimination.
@@ -1166,10 +1827,11 @@ impl Bencher {
         if n == 0 { n = 1; }
 
         let mut total_run = Duration::new(0, 0);
-        let samples : &mut [f64] = &mut [0.0_f64; 50];
+        let samples : &mut [f64] = &mut [0.0_f64; BENCH_SAMPLES];
         loop {
             let mut summ = None;
             let mut summ5 = None;
+            let mut outliers = 0;
 
             let loop_run = Duration::span(|| {
 
@@ -1186,11 +1848,13 @@ impl Bencher {
                     *p = self.ns_per_iter() as f64;
                 };
 
+                outliers = stats::count_outliers(samples);
                 stats::winsorize(samples, 5.0);
                 summ5 = Some(stats::Summary::new(samples));
             });
             let summ = summ.unwrap();
             let summ5 = summ5.unwrap();
+            self.outliers = outliers;
 
             // If we've run for 100ms and seem to have converged to a
             // stable median.
@@ -1227,7 +1891,8 @@ pub mod bench {
         let mut bs = Bencher {
             iterations: 0,
             dur: Duration::new(0, 0),
-            bytes: 0
+            bytes: 0,
+            outliers: 0,
         };
 
         let ns_iter_summ = bs.auto_bench(f);
@@ -1238,7 +1903,8 @@ pub mod bench {
 
         BenchSamples {
             ns_iter_summ: ns_iter_summ,
-            mb_s: mb_s as usize
+            mb_s: mb_s as usize,
+            outliers: bs.outliers,
         }
     }
 
@@ -1246,7 +1912,8 @@ pub mod bench {
         let mut bs = Bencher {
             iterations: 0,
             dur: Duration::new(0, 0),
-            bytes: 0
+            bytes: 0,
+            outliers: 0,
         };
         bs.bench_n(1, f);
     }
@@ -1256,9 +1923,20 @@ pub mod bench {
 mod tests {
     use test::{TrFailed, TrIgnored, TrOk, filter_tests, parse_opts,
                TestDesc, TestDescAndFn, TestOpts, run_test,
-               MetricMap,
+               MetricMap, OutputFormat,
                StaticTestName, DynTestName, DynTestFn, ShouldPanic};
+    use super::{ConsoleTestState, PrettyOutput, JsonOutput, JunitOutput, RunningTest, run_tests};
+    use super::NamePadding::PadNone;
+    use super::OutputLocation::{Raw, Pretty};
+    use super::TestEvent::TeResult;
+    use super::{LikelyNoise, MetricAdded, MetricRemoved, Improvement, Regression};
+    use serialize::json;
+    use std::env;
+    use std::fs;
+    use libc;
     use std::sync::mpsc::channel;
+    use std::thread;
+    use std::time::{Duration, Instant};
 
     #[test]
     pub fn do_not_run_ignored_tests() {
@@ -1268,6 +1946,7 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: true,
                 should_panic: ShouldPanic::No,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
@@ -1285,6 +1964,7 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: true,
                 should_panic: ShouldPanic::No,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
@@ -1302,6 +1982,7 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: false,
                 should_panic: ShouldPanic::Yes,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
@@ -1319,6 +2000,7 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: false,
                 should_panic: ShouldPanic::YesWithMessage("error message"),
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
@@ -1336,6 +2018,7 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: false,
                 should_panic: ShouldPanic::YesWithMessage("foobar"),
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
@@ -1353,6 +2036,7 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: false,
                 should_panic: ShouldPanic::Yes,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
@@ -1374,6 +2058,310 @@ mod tests {
         assert!((opts.run_ignored));
     }
 
+    #[test]
+    fn parse_filter_flags() {
+        let args = vec!("progname".to_string(),
+                        "foo".to_string(),
+                        "--exact".to_string(),
+                        "--skip=bar".to_string(),
+                        "--skip".to_string(),
+                        "baz".to_string(),
+                        "--shard=2/3".to_string(),
+                        "--list".to_string());
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_filter_flags")
+        };
+        assert_eq!(opts.filter, Some("foo".to_string()));
+        assert!(opts.filter_exact);
+        assert_eq!(opts.skip, ["bar".to_string(), "baz".to_string()]);
+        assert_eq!(opts.shard, Some((2, 3)));
+        assert!(opts.list);
+
+        for shard in &["0/3", "4/3", "1", "a/b", "1/0"] {
+            let args = vec!("progname".to_string(), format!("--shard={}", shard));
+            assert!(parse_opts(&args).unwrap().is_err());
+        }
+    }
+
+    fn named_tests(names: &[&'static str]) -> Vec<TestDescAndFn> {
+        names.iter().map(|&name| {
+            TestDescAndFn {
+                desc: TestDesc {
+                    name: StaticTestName(name),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    timeout: None,
+                },
+                testfn: DynTestFn(Box::new(move|| {})),
+            }
+        }).collect()
+    }
+
+    fn filtered_names(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<String> {
+        filter_tests(opts, tests).iter().map(|t| t.desc.name.to_string()).collect()
+    }
+
+    #[test]
+    fn filter_exact_and_skip() {
+        let names = ["base", "base::test", "base::test_slow", "other::test"];
+
+        let mut opts = TestOpts::new();
+        opts.filter = Some("base::test".to_string());
+        assert_eq!(filtered_names(&opts, named_tests(&names)),
+                   ["base::test", "base::test_slow"]);
+
+        opts.filter_exact = true;
+        assert_eq!(filtered_names(&opts, named_tests(&names)), ["base::test"]);
+
+        let mut opts = TestOpts::new();
+        opts.skip = vec!("slow".to_string(), "other".to_string());
+        assert_eq!(filtered_names(&opts, named_tests(&names)), ["base", "base::test"]);
+
+        opts.filter_exact = true;
+        opts.skip = vec!("base".to_string(), "other".to_string());
+        assert_eq!(filtered_names(&opts, named_tests(&names)),
+                   ["base::test", "base::test_slow", "other::test"]);
+    }
+
+    #[test]
+    fn shard_tests() {
+        let names = ["a", "b", "c", "d", "e", "f", "g"];
+        let mut opts = TestOpts::new();
+        let mut all = Vec::new();
+        for i in 1..4 {
+            opts.shard = Some((i, 3));
+            let shard = filtered_names(&opts, named_tests(&names));
+            // shards are balanced and don't depend on the original order
+            assert!(shard.len() == 2 || shard.len() == 3);
+            let mut reversed = names.to_vec();
+            reversed.reverse();
+            assert_eq!(filtered_names(&opts, named_tests(&reversed)), shard);
+            all.extend(shard);
+        }
+        all.sort();
+        assert_eq!(all, names);
+    }
+
+    #[test]
+    fn list_tests() {
+        let mut st = raw_console(PrettyOutput);
+        st.write_list(&named_tests(&["a::b", "c"])).unwrap();
+        assert_eq!(console_output(&st), "a::b: test\nc: test\n\n2 tests, 0 benchmarks\n");
+
+        let mut st = raw_console(JsonOutput);
+        st.write_list(&named_tests(&["a::b"])).unwrap();
+        assert_eq!(console_output(&st),
+                   "{ \"type\": \"test\", \"event\": \"discovered\", \"name\": \"a::b\" }\n");
+    }
+
+    #[test]
+    fn parse_format_flag() {
+        let args = vec!("progname".to_string(),
+                        "--format=json".to_string());
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_format_flag")
+        };
+        assert_eq!(opts.format, JsonOutput);
+
+        let args = vec!("progname".to_string());
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.format, PrettyOutput);
+
+        let args = vec!("progname".to_string(),
+                        "--format=xml".to_string());
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn parse_timing_flags() {
+        let args = vec!("progname".to_string(),
+                        "--test-timeout=30".to_string(),
+                        "--report-time".to_string());
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_timing_flags")
+        };
+        assert_eq!(opts.test_timeout, Some(Duration::from_secs(30)));
+        assert!(opts.report_time);
+
+        let args = vec!("progname".to_string(),
+                        "--test-timeout=soon".to_string());
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn tests_time_out() {
+        fn hangs() {
+            println!("hanging");
+            thread::sleep(Duration::from_secs(60));
+        }
+        fn slow() {
+            thread::sleep(Duration::from_millis(200));
+        }
+        let tests = vec!(
+            TestDescAndFn {
+                desc: TestDesc {
+                    name: StaticTestName("hangs"),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    timeout: None,
+                },
+                testfn: DynTestFn(Box::new(move|| hangs())),
+            },
+            TestDescAndFn {
+                desc: TestDesc {
+                    name: StaticTestName("slow"),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    timeout: Some(60),
+                },
+                testfn: DynTestFn(Box::new(move|| slow())),
+            });
+
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+        opts.test_timeout = Some(Duration::from_millis(100));
+
+        let mut results = Vec::new();
+        run_tests(&opts, tests, |event| {
+            if let TeResult(desc, result, _, stdout) = event {
+                results.push((desc.name.to_string(), result, stdout));
+            }
+            Ok(())
+        }).unwrap();
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "hangs");
+        assert!(results[0].1 == TrFailed);
+        let stdout = String::from_utf8(results[0].2.clone()).unwrap();
+        assert!(stdout.contains("hanging"));
+        assert!(stdout.contains("timed out"));
+        assert_eq!(results[1].0, "slow");
+        assert!(results[1].1 == TrOk);
+    }
+
+    #[test]
+    fn report_slowest_tests() {
+        let mut st = raw_console(PrettyOutput);
+        st.report_time = true;
+        for (i, &ms) in [10u64, 30, 20].iter().enumerate() {
+            let desc = TestDesc {
+                name: DynTestName(format!("test{}", i)),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                timeout: None,
+            };
+            st.write_result(&desc, &TrOk, Duration::from_millis(ms), b"").unwrap();
+        }
+        st.write_slowest().unwrap();
+
+        let out = console_output(&st);
+        let first = out.find("test1").unwrap();
+        let second = out.find("test2").unwrap();
+        let third = out.find("test0").unwrap();
+        assert!(first < second && second < third);
+        assert!(out.contains("0.030s test1"));
+    }
+
+    fn raw_console(format: OutputFormat) -> ConsoleTestState<Vec<u8>> {
+        ConsoleTestState {
+            log_out: None,
+            out: Raw(Vec::new()),
+            use_color: false,
+            format: format,
+            start_time: Instant::now(),
+            report_time: false,
+            total: 0,
+            passed: 0,
+            failed: 0,
+            ignored: 0,
+            measured: 0,
+            max_name_len: 0,
+            metrics: MetricMap::new(),
+            failures: Vec::new(),
+            results: Vec::new(),
+            exec_times: Vec::new(),
+        }
+    }
+
+    fn console_output(st: &ConsoleTestState<Vec<u8>>) -> String {
+        match st.out {
+            Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+            Pretty(_) => unreachable!()
+        }
+    }
+
+    #[test]
+    fn json_output() {
+        let desc = TestDesc {
+            name: StaticTestName("a::\"quoted\""),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            timeout: None,
+        };
+        let mut st = raw_console(JsonOutput);
+        st.write_run_start(1).unwrap();
+        st.write_test_start(&desc, PadNone).unwrap();
+        st.write_result(&desc, &TrFailed, Duration::from_millis(1500),
+                        b"out\n").unwrap();
+        st.failed += 1;
+        assert!(!st.write_run_finish().unwrap());
+
+        let out = console_output(&st);
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        for line in &lines {
+            assert!(json::from_str(line).is_ok(), "invalid JSON: {}", line);
+        }
+        assert_eq!(lines[0],
+                   r#"{ "type": "suite", "event": "started", "test_count": 1 }"#);
+        assert_eq!(lines[1],
+                   r#"{ "type": "test", "event": "started", "name": "a::\"quoted\"" }"#);
+        assert_eq!(lines[2],
+                   concat!(r#"{ "type": "test", "event": "failed", "name": "a::\"quoted\"", "#,
+                           r#""exec_time": 1.500, "stdout": "out\n" }"#));
+        assert!(lines[3].starts_with(
+            concat!(r#"{ "type": "suite", "event": "failed", "passed": 0, "failed": 1, "#,
+                    r#""ignored": 0, "measured": 0, "exec_time": "#)));
+    }
+
+    #[test]
+    fn junit_output() {
+        let ok = TestDesc {
+            name: StaticTestName("foo::passes"),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            timeout: None,
+        };
+        let bad = TestDesc {
+            name: StaticTestName("foo::fails<T>"),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            timeout: None,
+        };
+        let mut st = raw_console(JunitOutput);
+        st.write_run_start(2).unwrap();
+        st.write_test_start(&ok, PadNone).unwrap();
+        st.write_result(&ok, &TrOk, Duration::from_millis(2), b"").unwrap();
+        st.passed += 1;
+        st.write_test_start(&bad, PadNone).unwrap();
+        st.write_result(&bad, &TrFailed, Duration::new(0, 0), b"a & b").unwrap();
+        st.failed += 1;
+        assert!(!st.write_run_finish().unwrap());
+
+        let out = console_output(&st);
+        assert!(out.starts_with("<?xml"));
+        assert!(out.contains(r#"failures="1" tests="2" skipped="0""#));
+        assert!(out.contains(r#"<testcase classname="foo" name="passes" time="0.002"/>"#));
+        assert!(out.contains(concat!(r#"<testcase classname="foo" name="fails&lt;T&gt;" "#,
+                                     r#"time="0.000"><failure type="assert"/>"#,
+                                     r#"<system-out>a &amp; b</system-out></testcase>"#)));
+        assert!(out.ends_with("</testsuites>\n"));
+    }
+
     #[test]
     pub fn filter_for_ignored_option() {
         // When we run ignored tests the test filter should filter out all the
@@ -1389,6 +2377,7 @@ mod tests {
                     name: StaticTestName("1"),
                     ignore: true,
                     should_panic: ShouldPanic::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move|| {})),
             },
@@ -1397,6 +2386,7 @@ mod tests {
                     name: StaticTestName("2"),
                     ignore: false,
                     should_panic: ShouldPanic::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move|| {})),
             });
@@ -1433,6 +2423,7 @@ mod tests {
                         name: DynTestName((*name).clone()),
                         ignore: false,
                         should_panic: ShouldPanic::No,
+                        timeout: None,
                     },
                     testfn: DynTestFn(Box::new(testfn)),
                 };
@@ -1479,5 +2470,97 @@ mod tests {
 
         m1.insert_metric("in-both-want-upwards-and-improved", 1000.0, -10.0);
         m2.insert_metric("in-both-want-upwards-and-improved", 2000.0, -10.0);
+
+        let diff1 = m2.compare_to_old(&m1, None);
+
+        assert_eq!(diff1["in-both-noise"], LikelyNoise);
+        assert_eq!(diff1["in-first-noise"], MetricRemoved);
+        assert_eq!(diff1["in-second-noise"], MetricAdded);
+        assert_eq!(diff1["in-both-want-downwards-but-regressed"], Regression(100.0));
+        assert_eq!(diff1["in-both-want-downwards-and-improved"], Improvement(50.0));
+        assert_eq!(diff1["in-both-want-upwards-but-regressed"], Regression(50.0));
+        assert_eq!(diff1["in-both-want-upwards-and-improved"], Improvement(100.0));
+        assert_eq!(diff1.len(), 7);
+
+        let diff2 = m2.compare_to_old(&m1, Some(200.0));
+
+        assert_eq!(diff2["in-both-noise"], LikelyNoise);
+        assert_eq!(diff2["in-first-noise"], MetricRemoved);
+        assert_eq!(diff2["in-second-noise"], MetricAdded);
+        assert_eq!(diff2["in-both-want-downwards-but-regressed"], LikelyNoise);
+        assert_eq!(diff2["in-both-want-downwards-and-improved"], LikelyNoise);
+        assert_eq!(diff2["in-both-want-upwards-but-regressed"], LikelyNoise);
+        assert_eq!(diff2["in-both-want-upwards-and-improved"], LikelyNoise);
+        assert_eq!(diff2.len(), 7);
+    }
+
+    #[test]
+    pub fn test_metricmap_save_load() {
+        let path = env::temp_dir().join(format!("libtest-metrics-{}.json",
+                                                   unsafe { libc::getpid() }));
+        let mut m = MetricMap::new();
+        m.insert_metric("bench::a", 1000.0, 12.5);
+        m.insert_metric("bench::b", 20.0, -1.0);
+        m.save(&path).unwrap();
+        let loaded = MetricMap::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(loaded.unwrap() == m);
+    }
+
+    #[test]
+    pub fn metric_regressions_fail() {
+        let mut old = MetricMap::new();
+        old.insert_metric("fast", 100.0, 1.0);
+        old.insert_metric("slow", 100.0, 1.0);
+        let mut new = MetricMap::new();
+        new.insert_metric("fast", 50.0, 1.0);
+        new.insert_metric("slow", 101.0, 1.0);
+
+        let mut st = raw_console(PrettyOutput);
+        assert!(st.write_metric_diff(&new.compare_to_old(&old, None)).unwrap());
+
+        new.insert_metric("slow", 150.0, 1.0);
+        let mut st = raw_console(PrettyOutput);
+        assert!(!st.write_metric_diff(&new.compare_to_old(&old, None)).unwrap());
+        let out = console_output(&st);
+        assert!(out.contains("metric slow: regressed by 50.00%"));
+        assert!(out.contains("metric fast: improved by 50.00%"));
+        assert!(out.contains("1 regressed; 1 improved; 0 noise"));
+    }
+
+    #[test]
+    fn overflowing_timeout_has_no_deadline() {
+        let test = RunningTest {
+            start: Instant::now(),
+            timeout: Some(Duration::new(u64::max_value(), 0)),
+            output: None,
+        };
+        assert!(test.deadline().is_none());
+    }
+
+    #[test]
+    pub fn regressed_metrics_are_not_saved() {
+        let path = env::temp_dir().join(format!("libtest-ratchet-{}.json",
+                                                unsafe { libc::getpid() }));
+        let _ = fs::remove_file(&path);
+        let mut opts = TestOpts::new();
+        opts.ratchet_metrics = Some(path.clone());
+        opts.save_metrics = Some(path.clone());
+
+        // Without a baseline, the metrics are only saved
+        let mut st = raw_console(PrettyOutput);
+        st.metrics.insert_metric("slow", 100.0, 1.0);
+        assert!(st.ratchet_and_save_metrics(&opts).unwrap());
+        assert_eq!(console_output(&st), "");
+
+        let mut st = raw_console(PrettyOutput);
+        st.metrics.insert_metric("slow", 150.0, 1.0);
+        assert!(!st.ratchet_and_save_metrics(&opts).unwrap());
+
+        let saved = MetricMap::load(&path);
+        fs::remove_file(&path).unwrap();
+        let mut baseline = MetricMap::new();
+        baseline.insert_metric("slow", 100.0, 1.0);
+        assert!(saved.unwrap() == baseline);
     }
 }
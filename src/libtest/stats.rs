@@ -156,6 +156,27 @@ impl Summary {
             iqr: samples.iqr()
         }
     }
+
+    /// An approximate 95% confidence interval for the median of the `n`
+    /// samples this is a summary of, from their inter-quartile range. Two
+    /// sample sets whose intervals don't overlap have different medians with
+    /// about 95% confidence.
+    ///
+    /// See: McGill, Tukey and Larsen, "Variations of Box Plots" (1978)
+    pub fn median_confidence_interval(&self, n: usize) -> (f64, f64) {
+        let half_width = 1.57 * self.iqr / (n as f64).sqrt();
+        (self.median - half_width, self.median + half_width)
+    }
+
+    /// Tukey's fences of the samples: values below the first or above the
+    /// second are outliers. They lie 1.5 times the inter-quartile range
+    /// beyond the first and third quartiles.
+    ///
+    /// See: https://en.wikipedia.org/wiki/Outlier#Tukey.27s_fences
+    pub fn outlier_fences(&self) -> (f64, f64) {
+        let (q1, _, q3) = self.quartiles;
+        (q1 - 1.5 * self.iqr, q3 + 1.5 * self.iqr)
+    }
 }
 
 impl Stats for [f64] {
@@ -326,12 +347,20 @@ pub fn winsorize(samples: &mut [f64], pct: f64) {
     }
 }
 
+/// Count the samples which are outliers, i.e. which lie outside of the
+/// `outlier_fences` of their summary.
+pub fn count_outliers(samples: &[f64]) -> usize {
+    let (lo, hi) = Summary::new(samples).outlier_fences();
+    samples.iter().filter(|&&s| s < lo || s > hi).count()
+}
+
 // Test vectors generated from R, using the script src/etc/stat-test-vectors.r.
 
 #[cfg(test)]
 mod tests {
     use stats::Stats;
     use stats::Summary;
+    use stats::count_outliers;
     use std::f64;
     use std::io::prelude::*;
     use std::io;
@@ -904,6 +933,28 @@ mod tests {
     fn test_sum_f64_between_ints_that_sum_to_0() {
         assert_eq!([1e30f64, 1.2f64, -1e30f64].sum(), 1.2);
     }
+
+    #[test]
+    fn test_outliers() {
+        let samples = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0];
+        let summ = Summary::new(&samples);
+        let (lo, hi) = summ.outlier_fences();
+        assert_approx_eq!(lo, -3.5);
+        assert_approx_eq!(hi, 14.5);
+        assert_eq!(count_outliers(&samples), 1);
+        assert_eq!(count_outliers(&samples[..9]), 0);
+    }
+
+    #[test]
+    fn test_median_confidence_interval() {
+        let samples = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0];
+        let (lo, hi) = Summary::new(&samples).median_confidence_interval(samples.len());
+        assert_approx_eq!(lo, 5.5 - 1.57 * 4.5 / 10f64.sqrt());
+        assert_approx_eq!(hi, 5.5 + 1.57 * 4.5 / 10f64.sqrt());
+
+        let (lo, hi) = Summary::new(&[3.0; 10]).median_confidence_interval(10);
+        assert_eq!((lo, hi), (3.0, 3.0));
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,36 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z unstable-options --error-format=json
+
+// Check the labels of the borrow conflict errors, which point at both
+// borrows and at the end of the first one.
+
+fn two_mutable_borrows() {
+    let mut x = 1;
+    let a = &mut x; //~ NOTE first mutable borrow occurs here
+    let b = &mut x; //~ ERROR cannot borrow `x` as mutable more than once at a time
+}
+//~^ NOTE first borrow ends here
+
+fn mutable_and_immutable_borrows() {
+    let mut x = 1;
+    let a = &x; //~ NOTE immutable borrow occurs here
+    let b = &mut x; //~ ERROR cannot borrow `x` as mutable because it is also borrowed as immutable
+}
+//~^ NOTE immutable borrow ends here
+
+fn move_out_while_borrowed() {
+    let x = String::new();
+    let a = &x; //~ NOTE borrow of `x` occurs here
+    let b = x; //~ ERROR cannot move out of `x` because it is borrowed
+}
+
+fn main() {}
@@ -8,6 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// compile-flags: -Z unstable-options --error-format=json
+
 #![allow(dead_code)]
 fn main() {
     // Original borrow ends at end of function
@@ -15,7 +17,7 @@ fn main() {
     let y = &mut x;
     let z = &x; //~ ERROR cannot borrow
 }
-//~^ NOTE previous borrow ends here
+//~^ NOTE mutable borrow ends here
 
 fn foo() {
     match true {
@@ -25,7 +27,7 @@ fn foo() {
             let y = &x;
             let z = &mut x; //~ ERROR cannot borrow
         }
-     //~^ NOTE previous borrow ends here
+     //~^ NOTE immutable borrow ends here
         false => ()
     }
 }
@@ -37,5 +39,5 @@ fn bar() {
         let y = &mut x;
         let z = &mut x; //~ ERROR cannot borrow
     };
- //~^ NOTE previous borrow ends here
+ //~^ NOTE first borrow ends here
 }
@@ -8,6 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// compile-flags: -Z unstable-options --error-format=json
+
 #![feature(box_syntax)]
 
 struct Foo { a: isize, b: isize }
@@ -16,10 +18,10 @@ fn main() {
     let mut x: Box<_> = box Foo { a: 1, b: 2 };
     let (a, b) = (&mut x.a, &mut x.b);
     //~^ ERROR cannot borrow `x` (here through borrowing `x.b`) as mutable more than once at a time
-    //~^^ NOTE previous borrow of `x` occurs here (through borrowing `x.a`)
+    //~^^ NOTE first mutable borrow occurs here (through borrowing `x.a`)
 
     let mut foo: Box<_> = box Foo { a: 1, b: 2 };
     let (c, d) = (&mut foo.a, &foo.b);
     //~^ ERROR cannot borrow `foo` (here through borrowing `foo.b`) as immutable
-    //~^^ NOTE previous borrow of `foo` occurs here (through borrowing `foo.a`)
+    //~^^ NOTE mutable borrow occurs here (through borrowing `foo.a`)
 }
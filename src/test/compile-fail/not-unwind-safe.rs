@@ -0,0 +1,28 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+
+#![feature(std_panic)]
+
+use std::cell::RefCell;
+use std::panic::catch_unwind;
+
+fn main() {
+    let mut a = 1;
+    catch_unwind(|| { a += 1; });
+    //~^ ERROR the trait `std::panic::UnwindSafe` is not implemented for the type `&mut i32`
+    //~| NOTE the type &mut i32 may not be safely transferred across an unwind boundary
+
+    let b = RefCell::new(1);
+    catch_unwind(|| { *b.borrow_mut() += 1; });
+    //~^ ERROR the trait `std::panic::RefUnwindSafe` is not implemented for the type `core::cell::UnsafeCell<i32>`
+    //~| NOTE the type core::cell::UnsafeCell<i32> contains interior mutability
+}
@@ -0,0 +1,22 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn main() {
+    let x = 22;
+}
+
+// END RUST SOURCE
+// START rustc.main.mir_map.0.mir
+// fn() -> () {
+//     let Var(0): i32; // x
+// END rustc.main.mir_map.0.mir
+// START rustc.main.mir_map.0.mir
+//         Var(0) = const 22;
+// END rustc.main.mir_map.0.mir
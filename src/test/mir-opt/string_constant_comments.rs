@@ -0,0 +1,23 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A `//` inside a string constant does not start a comment, in the
+// expected lines or in the dump.
+
+fn main() {
+    let url = "http://example.com";
+    let quoted = "\"//\" is a comment";
+}
+
+// END RUST SOURCE
+// START rustc.main.mir_map.0.mir
+//         Var(0) = const "http://example.com";
+//         Var(1) = const "\"//\" is a comment"; // a real comment
+// END rustc.main.mir_map.0.mir
@@ -14,5 +14,7 @@
 fn main() {
     let a = |:| {};  //~ ERROR obsolete syntax: `:`, `&mut:`, or `&:`
     let a = |&:| {};  //~ ERROR obsolete syntax: `:`, `&mut:`, or `&:`
+    //~^ HELP rely on inference instead
+    //~| SUGGESTION let a = || {};
     let a = |&mut:| {};  //~ ERROR obsolete syntax: `:`, `&mut:`, or `&:`
 }
@@ -0,0 +1,29 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct Wrapper(Vec<i32>);
+
+impl Wrapper {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+pub fn add_one(x: i32) -> i32 {
+    x + 1
+}
+
+pub fn main() {
+    let mut w = Wrapper(vec![1, 2, 3]);
+    let mut bump = |n: i32| w.0.push(n);
+    bump(4);
+    let s: &[i32] = &vec![add_one(1)];
+    println!("{} {}", w.len(), s.len());
+}
@@ -0,0 +1,27 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+#![crate_type = "rlib"]
+
+pub fn used_by_main() {}
+pub fn used_by_mid() {}
+pub fn never_used() {}
+fn private_helper() {}
+
+pub enum Mode { Fast, Slow }
+
+pub mod inner {
+    pub struct Unused;
+    pub trait Trait { fn method(&self) {} }
+    impl Trait for i32 {}
+}
+
+mod private {
+    pub fn not_exported() {}
+}
@@ -0,0 +1,50 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(std_panic)]
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+
+fn main() {
+    // The closure borrows from the stack, so it isn't 'static.
+    let data = vec![1, 2, 3];
+    let sum = panic::catch_unwind(|| data.iter().fold(0, |a, b| a + b));
+    assert_eq!(sum.unwrap(), 6);
+
+    let err = panic::catch_unwind(|| {
+        if data.len() == 3 {
+            panic!("foo");
+        }
+    }).unwrap_err();
+    assert_eq!(err.downcast_ref::<&'static str>(), Some(&"foo"));
+
+    // Mutexes are unwind safe through poisoning.
+    let mutex = Mutex::new(0);
+    let _ = panic::catch_unwind(|| {
+        *mutex.lock().unwrap() += 1;
+        panic!("poisoned");
+    });
+    assert!(mutex.is_poisoned());
+
+    let mut counter = 0;
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        counter += 1;
+        panic!("bar");
+    }));
+    assert_eq!(counter, 1);
+
+    // A caught panic can be resumed and caught again.
+    let err = panic::catch_unwind(|| {
+        let err = panic::catch_unwind(|| panic!(String::from("baz"))).unwrap_err();
+        panic::resume_unwind(err);
+    }).unwrap_err();
+    assert_eq!(err.downcast_ref::<String>().map(|s| &s[..]), Some("baz"));
+}
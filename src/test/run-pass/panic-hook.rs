@@ -0,0 +1,53 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(panic_handler, std_panic)]
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use std::thread;
+
+static A: AtomicUsize = ATOMIC_USIZE_INIT;
+static B: AtomicUsize = ATOMIC_USIZE_INIT;
+
+fn main() {
+    panic::set_hook(Box::new(|info| {
+        assert_eq!(info.payload().downcast_ref::<&'static str>(), Some(&"foobar"));
+        let location = info.location().unwrap();
+        assert!(location.file().ends_with("panic-hook.rs"));
+        assert!(location.line() > 0);
+        A.fetch_add(1, Ordering::SeqCst);
+    }));
+    let _ = thread::spawn(|| {
+        panic!("foobar");
+    }).join();
+    assert_eq!(1, A.load(Ordering::SeqCst));
+
+    // Hooks can be chained by taking the previous one.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        B.fetch_add(1, Ordering::SeqCst);
+        hook(info);
+    }));
+    let _ = thread::spawn(|| {
+        panic!("foobar");
+    }).join();
+    assert_eq!(2, A.load(Ordering::SeqCst));
+    assert_eq!(1, B.load(Ordering::SeqCst));
+
+    // Taking the last custom hook restores the default one.
+    drop(panic::take_hook());
+    drop(panic::take_hook());
+    let _ = thread::spawn(|| {
+        panic!("foobar");
+    }).join();
+    assert_eq!(2, A.load(Ordering::SeqCst));
+}